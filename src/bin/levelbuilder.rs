@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 
-use ggez::event::{EventHandler, KeyCode};
-use ggez::graphics::{DrawParam, Image, Rect};
+use ggez::event::{Axis, Button, EventHandler, GamepadId, KeyCode};
+use ggez::graphics::{DrawMode, DrawParam, Image, MeshBuilder, Rect};
 use ggez::input::{keyboard, mouse};
 use ggez::*;
 use keyboard::KeyMods;
 
+use macguffin_quest::entities::environment::door::Door;
 use macguffin_quest::entities::environment::level::Level;
 use macguffin_quest::entities::environment::level_builder::LevelBuilder;
+use macguffin_quest::entities::items::loot::KeyColour;
 use macguffin_quest::entities::DrawableEntity;
+use macguffin_quest::input::{self, Action};
 use macguffin_quest::sprites::Sprite;
 
 /// State used by LevelBuilder
@@ -24,6 +27,7 @@ struct State {
     level: Level,          // level being designed
 
     map_tiles: HashMap<(i64, i64), usize>, // generate level from
+    extras: Vec<String>,                   // non tile lines (spawners, encounters...) kept as is
     vector_types: Vec<Sprite>,             // various tile images (after sheet is split up)
 
     path: String, // where to save to
@@ -34,28 +38,38 @@ impl State {
     /// needs a context and path to level to load
     /// Note: that it makes use of ggez filesystem so path needs to exits in game's stored data (In windows this is AppData/author_name/levelbuilder/config).
     /// Note: if no file was found returns a level builder.
-    fn readfile(ctx: &mut Context, path: &str) -> HashMap<(i64, i64), usize> {
+    /// Note: lines that are not tiles (ie spawners) are returned separately so they can be saved back out.
+    fn readfile(ctx: &mut Context, path: &str) -> (HashMap<(i64, i64), usize>, Vec<String>) {
         let mut retvalue = HashMap::new();
+        let mut extras = Vec::new();
 
-        if !ggez::filesystem::exists(ctx, &path) {
+        if !ggez::filesystem::exists(ctx, path) {
             println!("unable to find {} loading empty level", path);
-            return retvalue;
+            return (retvalue, extras);
         }
-        let file = ggez::filesystem::open(ctx, &path).unwrap();
+        let file = ggez::filesystem::open(ctx, path).unwrap();
 
         let reader = BufReader::new(file);
         for (i, line) in reader.lines().enumerate() {
             let line = line.unwrap();
             let mut parse = line.split_whitespace();
+            let first = match parse.next() {
+                Some(word) => word,
+                None => continue,
+            };
+            if first.parse::<i64>().is_err() {
+                extras.push(line.clone());
+                continue;
+            }
             let message = format!("Error on line {}, unable to parse valid value", i + 1);
-            let x = parse.next().unwrap().parse::<i64>().expect(&message);
+            let x = first.parse::<i64>().expect(&message);
             let y = parse.next().unwrap().parse::<i64>().expect(&message);
             let t = parse.next().unwrap().parse::<usize>().expect(&message);
             //println!("{}, ({},{}), {}", i, x, y, t);
             retvalue.insert((x, y), t);
         }
 
-        retvalue
+        (retvalue, extras)
     }
 
     /// load a level from file into LevelBuilder
@@ -75,17 +89,101 @@ impl State {
             use std::io::Write;
             file.write_all(output.as_bytes()).unwrap();
         }
+
+        for line in &self.extras {
+            let output = format!("{}\n", line);
+            use std::io::Write;
+            file.write_all(output.as_bytes()).unwrap();
+        }
     }
 
     /// Turns state data into a Level
-    /// needs a LevelBuilder and all the tiles to turn into a Level
-    fn buildlevel(builder: &mut LevelBuilder, map_tiles: &HashMap<(i64, i64), usize>) -> Level {
-        builder.generate_level(
+    /// needs a LevelBuilder, all the tiles to turn into a Level and the extra lines (for doors)
+    fn buildlevel(
+        builder: &mut LevelBuilder,
+        map_tiles: &HashMap<(i64, i64), usize>,
+        extras: &[String],
+    ) -> Level {
+        let mut level = builder.generate_level(
             map_tiles
                 .iter()
                 .map(|(k, v)| ((k.0 as f32, k.1 as f32), *v))
                 .collect(),
-        )
+        );
+        level.doors = Door::parse_all(extras);
+        level
+    }
+
+    /// Cycles the colour of the door (or key) on the tile under the mouse: none, red, blue, gold, none...
+    /// word is the first word of its line ("door" or "item") and suffix follows the colour ("" or "_key").
+    fn cycle_colour(&mut self, word: &str, suffix: &str) {
+        let x = self.mouse_position.x as i64;
+        let y = self.mouse_position.y as i64;
+        let found = self.extras.iter().position(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            parts.len() >= 4
+                && parts[0] == word
+                && parts[1].ends_with(suffix)
+                && parts[2] == x.to_string()
+                && parts[3] == y.to_string()
+        });
+        let (next, rest) = match found {
+            Some(i) => {
+                let line = self.extras.remove(i);
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let colour = KeyColour::from_name(parts[1].trim_end_matches(suffix));
+                let next = KeyColour::ALL
+                    .iter()
+                    .skip_while(|c| Some(**c) != colour)
+                    .nth(1)
+                    .cloned();
+                // anything after the position (ie "keep") stays
+                (next, parts[4..].join(" "))
+            }
+            None => (Some(KeyColour::ALL[0]), String::new()),
+        };
+        if let Some(colour) = next {
+            let line = format!(
+                "{} {}{} {} {} {}",
+                word,
+                colour.level_name(),
+                suffix,
+                x,
+                y,
+                rest
+            );
+            self.extras.push(line.trim_end().to_string());
+        }
+        self.level.doors = Door::parse_all(&self.extras);
+    }
+
+    /// Draws a marker for each door key placed in the level (the items sheet isn't loaded here).
+    fn draw_keys(&self, ctx: &mut Context) -> GameResult {
+        let mut mb = MeshBuilder::new();
+        let mut any = false;
+        for line in &self.extras {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 4 || parts[0] != "item" {
+                continue;
+            }
+            let colour = parts[1].strip_suffix("_key").and_then(KeyColour::from_name);
+            let x = parts[2].parse::<f32>();
+            let y = parts[3].parse::<f32>();
+            if let (Some(colour), Ok(x), Ok(y)) = (colour, x, y) {
+                let centre = mint::Point2 {
+                    x: x + 32.0,
+                    y: y + 32.0,
+                };
+                mb.circle(DrawMode::fill(), centre, 12.0, 0.5, colour.color());
+                mb.circle(DrawMode::stroke(2.0), centre, 12.0, 0.5, graphics::BLACK);
+                any = true;
+            }
+        }
+        if any {
+            let mesh = mb.build(ctx)?;
+            graphics::draw(ctx, &mesh, DrawParam::default())?;
+        }
+        Ok(())
     }
 
     /// Creates a new LevelBuilder state
@@ -105,9 +203,9 @@ impl State {
         let mut builder = LevelBuilder::new(ctx, None);
 
         // new level + tile information
-        let map_tiles = State::readfile(ctx, path);
+        let (map_tiles, extras) = State::readfile(ctx, path);
         let vector_types = builder.tileize(sheet);
-        let level = State::buildlevel(&mut builder, &map_tiles);
+        let level = State::buildlevel(&mut builder, &map_tiles, &extras);
 
         // where to save
         let path = path.to_string();
@@ -119,6 +217,7 @@ impl State {
             builder,
             level,
             map_tiles,
+            extras,
             vector_types,
             path,
         }
@@ -145,23 +244,46 @@ impl EventHandler for State {
         //println!("{}", self.tile_value);
     }
 
-    /// Detect 'O' being pressed and saving level
+    /// Detect Save ('O') being pressed and saving level
+    /// Interact ('E') cycles the door on the tile under the mouse (held with Run it cycles a key instead)
     fn key_up_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) {
-        if keycode == KeyCode::O {
+        let actions = input::actions_for_key(keycode);
+        if actions.contains(&Action::Save) {
             self.writefile(ctx);
+        } else if actions.contains(&Action::Interact) {
+            if input::is_pressed(ctx, Action::Run) {
+                self.cycle_colour("item", "_key");
+            } else {
+                self.cycle_colour("door", "");
+            }
         }
     }
 
+    /// Keeps track of gamepad buttons so the screen can be moved with a gamepad
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, btn: Button, _id: GamepadId) {
+        input::button_event(btn, true);
+    }
+
+    /// Keeps track of gamepad buttons so the screen can be moved with a gamepad
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, btn: Button, _id: GamepadId) {
+        input::button_event(btn, false);
+    }
+
+    /// Keeps track of the thumb sticks so the screen can be moved with a gamepad
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        input::axis_event(axis, value);
+    }
+
     /// Updates State (ie the level being edited)
     fn update(&mut self, ctx: &mut Context) -> GameResult {
         // move screen
-        if keyboard::is_key_pressed(ctx, KeyCode::D) {
+        if input::is_pressed(ctx, Action::Right) {
             self.screen.x += 16f32;
-        } else if keyboard::is_key_pressed(ctx, KeyCode::A) {
+        } else if input::is_pressed(ctx, Action::Left) {
             self.screen.x -= 16f32;
-        } else if keyboard::is_key_pressed(ctx, KeyCode::W) {
+        } else if input::is_pressed(ctx, Action::Up) {
             self.screen.y -= 16f32;
-        } else if keyboard::is_key_pressed(ctx, KeyCode::S) {
+        } else if input::is_pressed(ctx, Action::Down) {
             self.screen.y += 16f32;
         }
 
@@ -172,13 +294,13 @@ impl EventHandler for State {
                 self.tile_value,
             );
             // build level with new tile
-            self.level = State::buildlevel(&mut self.builder, &self.map_tiles);
+            self.level = State::buildlevel(&mut self.builder, &self.map_tiles, &self.extras);
         } else if mouse::button_pressed(ctx, mouse::MouseButton::Right) {
             // update tile
             self.map_tiles
                 .remove(&(self.mouse_position.x as i64, self.mouse_position.y as i64));
             // build level with new tile
-            self.level = State::buildlevel(&mut self.builder, &self.map_tiles);
+            self.level = State::buildlevel(&mut self.builder, &self.map_tiles, &self.extras);
         }
 
         Ok(())
@@ -193,6 +315,7 @@ impl EventHandler for State {
 
         // draw level
         self.level.draw(ctx)?;
+        self.draw_keys(ctx)?;
 
         // draw mouse placement
         let dp = DrawParam::default().dest(self.mouse_position);
@@ -233,6 +356,18 @@ fn main() {
             .build()
             .unwrap();
 
+    // load the game's saved controls (defaults if there are none)
+    // they live in the game's config folder, which is the editor's one with the game's name in it
+    let game_config: std::path::PathBuf = ggez::filesystem::user_config_dir(ctx)
+        .iter()
+        .map(|part| {
+            part.to_string_lossy()
+                .replace("levelbuilder", "macguffin_quest")
+        })
+        .collect();
+    ggez::filesystem::mount(ctx, &game_config, true);
+    macguffin_quest::input::load(ctx);
+
     // initial state to level builder
     let sheet = Image::new(ctx, sheet).unwrap();
     let state = &mut State::new(ctx, &sheet, &path);
@@ -9,9 +9,13 @@ fn main() {
             .add_resource_path(std::path::PathBuf::from("./resources/texture"))
             .add_resource_path(std::path::PathBuf::from("./resources/font"))
             .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
             .build()
             .unwrap();
 
+    // load saved controls (defaults if there are none)
+    macguffin_quest::input::load(ctx);
+
     // initial state to start game
     let state = Box::new(MainMenuState::new(ctx));
 
@@ -0,0 +1,278 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+
+/// How likely the player's hits are to crit.
+pub const PLAYER_CRIT_CHANCE: f32 = 0.1;
+/// How likely enemy hits are to crit.
+pub const ENEMY_CRIT_CHANCE: f32 = 0.05;
+/// How much harder a crit hits.
+pub const CRIT_MULTIPLIER: f32 = 1.5;
+/// Hits land anywhere from 10% under to 10% over their power.
+pub const VARIANCE: f32 = 0.1;
+/// Least damage a hit can do once it gets through defence.
+pub const MIN_DAMAGE: f32 = 1.0;
+
+/// What kind of damage a hit does.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Frost,
+}
+
+/// Functions for the DamageType enum
+impl DamageType {
+    /// FloatingText colour for damage of this type.
+    pub fn text_color(self) -> &'static str {
+        match self {
+            DamageType::Physical => "Red",
+            DamageType::Fire => "Orange",
+            DamageType::Frost => "Cyan",
+        }
+    }
+
+    /// How much of the defender's defence stops this type of damage
+    /// (armour is better at stopping blades than flames).
+    fn def_scale(self) -> f32 {
+        match self {
+            DamageType::Physical => 1.0,
+            DamageType::Fire | DamageType::Frost => 0.5,
+        }
+    }
+}
+
+/// How much of each type of damage is shrugged off.
+/// 0.5 takes half damage, 1.0 takes none and below zero is a weakness.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Resistances {
+    pub physical: f32,
+    pub fire: f32,
+    pub frost: f32,
+}
+
+/// Functions for the Resistances struct
+impl Resistances {
+    /// No resistances (or weaknesses) at all.
+    pub const NONE: Resistances = Resistances {
+        physical: 0.0,
+        fire: 0.0,
+        frost: 0.0,
+    };
+
+    /// Resistance to one type of damage.
+    pub fn against(&self, kind: DamageType) -> f32 {
+        match kind {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Frost => self.frost,
+        }
+    }
+}
+
+/// The side of a hit doing the damage.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Attacker {
+    // atk with the hit's power already worked in
+    pub power: f32,
+    pub kind: DamageType,
+    pub crit_chance: f32,
+}
+
+/// Functions for the Attacker struct
+impl Attacker {
+    /// News up an Attacker with the enemy crit chance.
+    pub fn new(power: f32, kind: DamageType) -> Attacker {
+        Attacker {
+            power,
+            kind,
+            crit_chance: ENEMY_CRIT_CHANCE,
+        }
+    }
+
+    /// Changes how likely the hit is to crit.
+    pub fn with_crit(mut self, crit_chance: f32) -> Attacker {
+        self.crit_chance = crit_chance;
+        self
+    }
+}
+
+/// The side of a hit taking the damage.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Defender {
+    pub def: f32,
+    pub resist: Resistances,
+}
+
+/// Functions for the Defender struct
+impl Defender {
+    /// News up a Defender (defence never goes below zero).
+    pub fn new(def: f32, resist: Resistances) -> Defender {
+        Defender {
+            def: def.max(0.0),
+            resist,
+        }
+    }
+}
+
+/// What a hit ended up doing.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Damage {
+    pub amount: f32,
+    pub kind: DamageType,
+    pub crit: bool,
+    // nothing got through
+    pub blocked: bool,
+}
+
+/// Functions for the Damage struct
+impl Damage {
+    /// What the FloatingText says.
+    pub fn text(&self) -> String {
+        if self.blocked {
+            "Blocked".to_string()
+        } else if self.crit {
+            format!("{}!", self.amount)
+        } else {
+            self.amount.to_string()
+        }
+    }
+
+    /// What colour the FloatingText is.
+    pub fn text_color(&self) -> &'static str {
+        if self.blocked {
+            "Blue"
+        } else if self.crit {
+            "Yellow"
+        } else {
+            self.kind.text_color()
+        }
+    }
+}
+
+/// Works out the damage a hit does using rng for crits and variance.
+/// Defence is taken off first, then resistance. A hit that defence stops
+/// completely is blocked, unless it crit, and anything that gets through
+/// does at least MIN_DAMAGE (or nothing at all when fully resisted).
+pub fn resolve<R: Rng + ?Sized>(attacker: Attacker, defender: Defender, rng: &mut R) -> Damage {
+    let crit = rng.gen::<f32>() < attacker.crit_chance;
+    let mut raw = attacker.power * rng.gen_range(1.0 - VARIANCE, 1.0 + VARIANCE);
+    if crit {
+        raw *= CRIT_MULTIPLIER;
+    }
+    let resist = defender.resist.against(attacker.kind);
+    let through = raw - defender.def * attacker.kind.def_scale();
+    let blocked = resist >= 1.0 || (through <= 0.0 && !crit);
+    let amount = if blocked {
+        0.0
+    } else {
+        // rounded so the FloatingText stays short
+        ((through * (1.0 - resist)).max(MIN_DAMAGE) * 10.0).round() / 10.0
+    };
+    Damage {
+        amount,
+        kind: attacker.kind,
+        crit,
+        blocked,
+    }
+}
+
+// Combat has its own rng so that fights (and what comes out of them) can be replayed from a seed.
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Starts the combat rng over from a seed.
+pub fn seed(seed: u64) {
+    RNG.with(|r| *r.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Works out the damage a hit does using the combat rng.
+pub fn roll(attacker: Attacker, defender: Defender) -> Damage {
+    with_rng(|rng| resolve(attacker, defender, rng))
+}
+
+/// Lends out the combat rng for anything else a seed should repeat (ie loot, elites and summons).
+pub fn with_rng<T, F: FnOnce(&mut StdRng) -> T>(f: F) -> T {
+    RNG.with(|r| f(&mut r.borrow_mut()))
+}
+
+#[cfg(test)]
+mod testcombat {
+    use super::*;
+
+    #[test]
+    fn test_defence_resistance_and_minimum() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let sword = Attacker::new(10.0, DamageType::Physical).with_crit(0.0);
+        let fire = Attacker::new(10.0, DamageType::Fire).with_crit(0.0);
+        let armoured = Defender::new(4.0, Resistances::NONE);
+        for _ in 0..100 {
+            let hit = resolve(sword, armoured, &mut rng);
+            assert!(!hit.crit && !hit.blocked);
+            assert!(hit.amount >= 5.0 && hit.amount <= 7.0);
+            // fire only half cares about armour
+            assert!(resolve(fire, armoured, &mut rng).amount >= 7.0);
+        }
+
+        let wall = Defender::new(20.0, Resistances::NONE);
+        assert!(resolve(sword, wall, &mut rng).blocked);
+        let fireproof = Defender::new(
+            0.0,
+            Resistances {
+                fire: 1.0,
+                ..Resistances::NONE
+            },
+        );
+        assert!(resolve(fire, fireproof, &mut rng).blocked);
+        // a big resistance still lets a little through
+        let tough = Defender::new(
+            0.0,
+            Resistances {
+                physical: 0.95,
+                ..Resistances::NONE
+            },
+        );
+        assert_eq!(resolve(sword, tough, &mut rng).amount, MIN_DAMAGE);
+    }
+
+    #[test]
+    fn test_crits() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let always = Attacker::new(10.0, DamageType::Frost).with_crit(1.0);
+        let wall = Defender::new(40.0, Resistances::NONE);
+        // crits can't be blocked
+        let hit = resolve(always, wall, &mut rng);
+        assert!(hit.crit && !hit.blocked);
+        assert_eq!(hit.text_color(), "Yellow");
+
+        let hits: Vec<Damage> = (0..1000)
+            .map(|_| {
+                resolve(
+                    Attacker::new(10.0, DamageType::Physical),
+                    Defender::new(0.0, Resistances::NONE),
+                    &mut rng,
+                )
+            })
+            .collect();
+        let crits = hits.iter().filter(|h| h.crit).count();
+        assert!(crits > 20 && crits < 100);
+        assert!(hits.iter().all(|h| h.crit || h.amount <= 11.0));
+    }
+
+    #[test]
+    fn test_seeded_rolls_repeat() {
+        let attacker = Attacker::new(10.0, DamageType::Physical);
+        let defender = Defender::new(1.0, Resistances::NONE);
+        seed(42);
+        let first: Vec<Damage> = (0..20).map(|_| roll(attacker, defender)).collect();
+        seed(42);
+        let second: Vec<Damage> = (0..20).map(|_| roll(attacker, defender)).collect();
+        assert_eq!(first, second);
+
+        seed(7);
+        let first: u32 = with_rng(|rng| rng.gen());
+        seed(7);
+        assert_eq!(with_rng(|rng| rng.gen::<u32>()), first);
+    }
+}
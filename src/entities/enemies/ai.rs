@@ -1,4 +1,4 @@
-use crate::entities::enemies::enemiesstruct::Enemy;
+use crate::entities::enemies::enemiesstruct::Positioned;
 use crate::entities::environment::level::Level;
 use crate::entities::player::playerstruct::Player;
 use ggez::*;
@@ -37,7 +37,7 @@ impl AI {
         &mut self,
         ctx: &mut Context,
         delta: Duration,
-        enemy: &mut dyn Enemy,
+        enemy: &mut dyn Positioned,
         player: &mut Player,
         level: &Level,
     ) {
@@ -46,10 +46,7 @@ impl AI {
             AITypes::MeleeLineOfSight => enemy.chase_player_sight(ctx, delta, player, level),
             AITypes::RangeDirect => enemy.chase_player(ctx, delta, player, level),
             AITypes::RangeLineOfSight => enemy.chase_player_sight(ctx, delta, player, level),
-            AITypes::Boss => {
-                enemy.chase_player(ctx, delta, player, level);
-                enemy.chase_player_sight(ctx, delta, player, level);
-            }
+            AITypes::Boss => enemy.chase_player(ctx, delta, player, level),
             AITypes::Error => println!("Error"),
         }
     }
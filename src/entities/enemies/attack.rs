@@ -0,0 +1,251 @@
+use ggez::graphics::{Color, Rect};
+use ggez::*;
+use std::time::Duration;
+
+use super::super::{Animations, CollideEntity, Direction};
+
+/// How hard enemy hits shove the player (and player hits shove enemies), in pixels per second.
+pub const KNOCKBACK: f32 = 480.0;
+
+/// The stages every enemy attack goes through.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AttackPhase {
+    // not attacking, free to move
+    Ready,
+    // winding up, the player can see it coming
+    Windup,
+    // the attack hitbox is out and can hurt the player
+    Strike,
+    // catching its breath after the attack
+    Recovery,
+}
+
+/// An enemy's melee attack. Handles the timing of each phase and where the
+/// attack hitbox is so that the player can read (and dodge) what is coming.
+pub struct EnemyAttack {
+    pub phase: AttackPhase,
+    timer: Duration,
+    windup: Duration,
+    strike: Duration,
+    recovery: Duration,
+    reach: f32,
+    width: f32,
+    hitbox: Rect,
+    landed: bool,
+}
+
+/// Functions for the EnemyAttack struct
+impl EnemyAttack {
+    /// News up an EnemyAttack.
+    /// Phase lengths are in milliseconds, reach is how far the hitbox sticks out
+    /// in front of the enemy and width is how wide it is.
+    pub fn new(windup: u64, strike: u64, recovery: u64, reach: f32, width: f32) -> EnemyAttack {
+        EnemyAttack {
+            phase: AttackPhase::Ready,
+            timer: Duration::new(0, 0),
+            windup: Duration::from_millis(windup),
+            strike: Duration::from_millis(strike),
+            recovery: Duration::from_millis(recovery),
+            reach,
+            width,
+            hitbox: Rect::new(0.0, 0.0, 0.0, 0.0),
+            landed: false,
+        }
+    }
+
+    /// Returns true while the enemy is in the middle of attacking.
+    pub fn busy(&self) -> bool {
+        self.phase != AttackPhase::Ready
+    }
+
+    /// Where the attack hitbox would be for an attacker at `me` facing `direction`.
+    fn place(&self, me: Rect, direction: Direction) -> Rect {
+        match direction {
+            Direction::Up => Rect::new(
+                me.x + (me.w - self.width) / 2.0,
+                me.y - self.reach,
+                self.width,
+                self.reach,
+            ),
+            Direction::Down => Rect::new(
+                me.x + (me.w - self.width) / 2.0,
+                me.y + me.h,
+                self.width,
+                self.reach,
+            ),
+            Direction::Left => Rect::new(
+                me.x - self.reach,
+                me.y + (me.h - self.width) / 2.0,
+                self.reach,
+                self.width,
+            ),
+            Direction::Right => Rect::new(
+                me.x + me.w,
+                me.y + (me.h - self.width) / 2.0,
+                self.reach,
+                self.width,
+            ),
+        }
+    }
+
+    /// Returns true if an attack started now (facing the target) could hit the target.
+    pub fn in_range(&self, me: Rect, target: Rect) -> bool {
+        self.place(me, facing(me, target)).overlaps(&target) || me.overlaps(&target)
+    }
+
+    /// Starts winding up an attack from `me` facing `direction`.
+    pub fn start(&mut self, me: Rect, direction: Direction) {
+        self.hitbox = self.place(me, direction);
+        self.phase = AttackPhase::Windup;
+        self.timer = Duration::new(0, 0);
+        self.landed = false;
+    }
+
+    /// Stops the attack (ie the enemy got hit out of it).
+    pub fn interrupt(&mut self) {
+        self.phase = AttackPhase::Ready;
+        self.timer = Duration::new(0, 0);
+    }
+
+    /// Moves the attack through its phases.
+    pub fn update(&mut self, delta: Duration) {
+        if !self.busy() {
+            return;
+        }
+        self.timer += delta;
+        let (length, next) = match self.phase {
+            AttackPhase::Windup => (self.windup, AttackPhase::Strike),
+            AttackPhase::Strike => (self.strike, AttackPhase::Recovery),
+            AttackPhase::Recovery => (self.recovery, AttackPhase::Ready),
+            AttackPhase::Ready => return,
+        };
+        if self.timer >= length {
+            self.timer -= length;
+            self.phase = next;
+        }
+    }
+
+    /// Returns true the first time the strike hitbox touches the target (one hit per attack).
+    pub fn strike_hits(&mut self, target: &dyn CollideEntity) -> bool {
+        if self.phase == AttackPhase::Strike && !self.landed && target.collision(self) {
+            self.landed = true;
+            return true;
+        }
+        false
+    }
+
+    /// The animation the enemy should be showing for the current phase.
+    pub fn animation(&self) -> Animations {
+        match self.phase {
+            AttackPhase::Ready => Animations::Walking,
+            AttackPhase::Windup => Animations::Cast,
+            AttackPhase::Strike => Animations::Slash,
+            AttackPhase::Recovery => Animations::Stand,
+        }
+    }
+
+    /// Enemies flash red while winding up.
+    pub fn tint(&self) -> Color {
+        match self.phase {
+            AttackPhase::Windup => Color::new(1.0, 0.5, 0.5, 1.0),
+            _ => graphics::WHITE,
+        }
+    }
+
+    /// How much to stretch the enemy's sprite (width, height) for enemies without attack frames
+    /// of their own: squashes down further and further while winding up, then springs up tall to strike.
+    pub fn squash(&self) -> (f32, f32) {
+        match self.phase {
+            AttackPhase::Windup => {
+                let done = self.timer.as_secs_f32() / self.windup.as_secs_f32().max(f32::EPSILON);
+                let amount = 0.25 * done.min(1.0);
+                (1.0 + amount, 1.0 - amount)
+            }
+            AttackPhase::Strike => (0.85, 1.2),
+            _ => (1.0, 1.0),
+        }
+    }
+
+    /// Draws the telegraph (where the attack will land) and the strike itself.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let color = match self.phase {
+            AttackPhase::Windup => Color::new(1.0, 0.0, 0.0, 0.3),
+            AttackPhase::Strike => Color::new(1.0, 1.0, 1.0, 0.6),
+            _ => return Ok(()),
+        };
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), self.hitbox, color)
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+/// Collide trait for the EnemyAttack
+impl CollideEntity for EnemyAttack {
+    /// The attack hitbox (only dangerous during the strike)
+    fn get_hitbox(&self) -> Rect {
+        self.hitbox
+    }
+}
+
+/// Which way `me` should face to look at `target`.
+pub fn facing(me: Rect, target: Rect) -> Direction {
+    let dx = (target.x + target.w / 2.0) - (me.x + me.w / 2.0);
+    let dy = (target.y + target.h / 2.0) - (me.y + me.h / 2.0);
+    if dx.abs() > dy.abs() {
+        if dx > 0.0 {
+            Direction::Right
+        } else {
+            Direction::Left
+        }
+    } else if dy > 0.0 {
+        Direction::Down
+    } else {
+        Direction::Up
+    }
+}
+
+#[cfg(test)]
+mod testattack {
+    use super::*;
+
+    #[test]
+    fn test_attack_phases() {
+        let mut atk = EnemyAttack::new(100, 50, 100, 20.0, 40.0);
+        assert!(!atk.busy());
+        atk.start(Rect::new(0.0, 0.0, 64.0, 64.0), Direction::Right);
+        assert_eq!(atk.phase, AttackPhase::Windup);
+        atk.update(Duration::from_millis(100));
+        assert_eq!(atk.phase, AttackPhase::Strike);
+        atk.update(Duration::from_millis(50));
+        assert_eq!(atk.phase, AttackPhase::Recovery);
+        atk.update(Duration::from_millis(100));
+        assert!(!atk.busy());
+    }
+
+    #[test]
+    fn test_squash_builds_up_during_windup() {
+        let mut atk = EnemyAttack::new(100, 50, 100, 20.0, 40.0);
+        assert_eq!(atk.squash(), (1.0, 1.0));
+        atk.start(Rect::new(0.0, 0.0, 64.0, 64.0), Direction::Right);
+        atk.update(Duration::from_millis(20));
+        let early = atk.squash();
+        atk.update(Duration::from_millis(70));
+        let late = atk.squash();
+        assert!(late.0 > early.0 && late.1 < early.1);
+        atk.update(Duration::from_millis(10));
+        assert!(atk.squash().1 > 1.0);
+    }
+
+    #[test]
+    fn test_facing_and_range() {
+        let me = Rect::new(0.0, 0.0, 64.0, 64.0);
+        let right = Rect::new(74.0, 10.0, 64.0, 64.0);
+        let far = Rect::new(300.0, 10.0, 64.0, 64.0);
+        assert_eq!(facing(me, right), Direction::Right);
+        assert_eq!(facing(me, Rect::new(0.0, -200.0, 10.0, 10.0)), Direction::Up);
+        let atk = EnemyAttack::new(100, 50, 100, 20.0, 40.0);
+        assert!(atk.in_range(me, right));
+        assert!(!atk.in_range(me, far));
+    }
+}
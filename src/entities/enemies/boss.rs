@@ -1,26 +1,171 @@
+use crate::entities::combat::{self, Resistances};
 use crate::entities::enemies::ai::*;
-use crate::entities::enemies::enemiesstruct::Enemy;
+use crate::entities::enemies::attack::KNOCKBACK;
+use crate::entities::enemies::enemiesstruct::{Enemy, Positioned, Struck, INVULNERABLE_TIME};
+use crate::entities::enemies::projectile::Projectile;
 use crate::entities::environment::level::Level;
+use crate::entities::items::loot::LootTable;
+use crate::entities::movement::{slide, towards};
+use crate::entities::player::atk_box::Hit;
 use crate::entities::player::playerstruct::Player;
+use crate::entities::status::{StatusEffects, StatusKind};
 use crate::sprites::*;
 use ggez::graphics::{Image, Rect};
 use ggez::nalgebra as na;
 use ggez::*;
 use rand::prelude::*;
 use std::collections::HashMap;
+use std::f32::consts::PI;
 use std::time::Duration;
 
-use super::super::{Animations, CollideEntity, Direction, DrawableEntity};
+use super::super::{Animations, CollideEntity, Direction, DrawableEntity, EntityId};
 use crate::entities::enemies::sight::*;
 use crate::ui::FloatingText;
 
+/// How much of each type of damage the boss shrugs off (the boss has been through hotter fires than a fireball).
+const RESIST: Resistances = Resistances {
+    fire: 0.5,
+    ..Resistances::NONE
+};
+
+/// Fraction of max hp below which the boss moves on to phase two and three.
+const PHASE_TWO_HP: f32 = 0.66;
+const PHASE_THREE_HP: f32 = 0.33;
+
+/// How fast the boss walks and charges (pixels per second).
+const WALK_SPEED: f32 = 60.0;
+const CHARGE_SPEED: f32 = 360.0;
+const CHARGE_TIME: Duration = Duration::from_millis(600);
+
+/// Area slam reach measured from the center of the boss.
+const SLAM_RADIUS: f32 = 140.0;
+const SLAM_FLASH: Duration = Duration::from_millis(200);
+
+/// Projectile volley settings (speed in pixels per second).
+const VOLLEY_SPEED: f32 = 240.0;
+const VOLLEY_RADIUS: f32 = 8.0;
+
+/// Minions are summoned on a ring this far from the center of the boss.
+const SUMMON_RADIUS: f32 = 96.0;
+const SUMMON_PER_WAVE: usize = 2;
+
+/// The stages of the boss fight, decided by how much hp the boss has left.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BossPhase {
+    One,
+    Two,
+    Three,
+}
+
+/// The attacks the boss can telegraph and then strike with.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BossAttack {
+    Charge,
+    Slam,
+    Volley,
+}
+
+/// What the boss is busy doing right now.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BossAction {
+    // walk after the player between attacks
+    Chase,
+    // warn the player about the coming attack
+    Telegraph(BossAttack),
+    // actually perform the attack
+    Strike(BossAttack),
+}
+
+/// Functions for the BossPhase enum (each phase has its own tuning)
+impl BossPhase {
+    /// Finds the phase matching the boss's remaining hp.
+    pub fn from_hp(hp: f32, max_hp: f32) -> BossPhase {
+        let ratio = hp / max_hp;
+        if ratio > PHASE_TWO_HP {
+            BossPhase::One
+        } else if ratio > PHASE_THREE_HP {
+            BossPhase::Two
+        } else {
+            BossPhase::Three
+        }
+    }
+
+    /// The attacks the boss cycles through while in this phase.
+    pub fn pattern(self) -> &'static [BossAttack] {
+        match self {
+            BossPhase::One => &[BossAttack::Charge, BossAttack::Volley],
+            BossPhase::Two => &[BossAttack::Charge, BossAttack::Slam, BossAttack::Volley],
+            BossPhase::Three => &[
+                BossAttack::Slam,
+                BossAttack::Charge,
+                BossAttack::Volley,
+                BossAttack::Charge,
+            ],
+        }
+    }
+
+    /// How long the boss walks around between attacks.
+    fn recover_time(self) -> Duration {
+        match self {
+            BossPhase::One => Duration::from_millis(1500),
+            BossPhase::Two => Duration::from_millis(1200),
+            BossPhase::Three => Duration::from_millis(800),
+        }
+    }
+
+    /// How long the warning for an attack lasts (gets shorter as the fight goes on).
+    fn telegraph_time(self, attack: BossAttack) -> Duration {
+        let base = match attack {
+            BossAttack::Charge => 800,
+            BossAttack::Slam => 1000,
+            BossAttack::Volley => 600,
+        };
+        let scale = match self {
+            BossPhase::One => 100,
+            BossPhase::Two => 85,
+            BossPhase::Three => 70,
+        };
+        Duration::from_millis(base * scale / 100)
+    }
+
+    /// Number of projectiles fired per volley and the angle they spread over.
+    fn volley(self) -> (usize, f32) {
+        match self {
+            BossPhase::One => (3usize, PI / 6.0),
+            BossPhase::Two => (5usize, PI / 4.0),
+            BossPhase::Three => (7usize, PI / 2.0),
+        }
+    }
+
+    /// Most minions that may be alive at once.
+    pub fn minion_cap(self) -> usize {
+        match self {
+            BossPhase::One => 2,
+            BossPhase::Two => 3,
+            BossPhase::Three => 4,
+        }
+    }
+
+    /// How often the boss tries to summon more minions.
+    fn summon_time(self) -> Duration {
+        match self {
+            BossPhase::One => Duration::from_secs(8),
+            BossPhase::Two => Duration::from_secs(6),
+            BossPhase::Three => Duration::from_secs(4),
+        }
+    }
+}
+
 /// Everything associated with the boss enemy
 pub struct Boss {
+    pub id: EntityId,
     pub x: f32,
     pub y: f32,
     pub hp: f32,
+    pub max_hp: f32,
     pub atk: f32,
     pub def: f32,
+    pub xp: u32,
     floating_text: Vec<FloatingText>,
     pub invulnerable: Duration,
     pub line_of_sight: LineOfSight,
@@ -28,6 +173,14 @@ pub struct Boss {
     pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
     pub animation: (Animations, Direction),
     pub direction: Direction,
+    pub phase: BossPhase,
+    pub action: BossAction,
+    action_timer: Duration,
+    pattern_index: usize,
+    target: (f32, f32),
+    summon_timer: Duration,
+    projectiles: Vec<Projectile>,
+    pub status: StatusEffects,
 }
 
 /// The functions used by the Boss struct
@@ -67,11 +220,14 @@ impl Boss {
         let floating_text = Vec::new();
 
         Boss {
+            id: EntityId::fresh(),
             x: xpos,
             y: ypos,
             hp: 100.0,
+            max_hp: 100.0,
             atk: 5.0,
             def: 2.0,
+            xp: 50,
             floating_text,
             invulnerable: Duration::new(1u64, 0u32),
             line_of_sight: LineOfSight::new(xpos, ypos),
@@ -79,44 +235,169 @@ impl Boss {
             sprite,
             animation: (Animations::Walking, Direction::Down),
             direction: Direction::Down,
+            phase: BossPhase::One,
+            action: BossAction::Chase,
+            action_timer: Duration::new(0, 0),
+            pattern_index: 0usize,
+            target: (xpos, ypos),
+            summon_timer: Duration::new(0, 0),
+            projectiles: Vec::new(),
+            status: StatusEffects::new(),
         }
     }
 
-    /// Whenever an enemy needs to take damage, we check to see if it is invulnerable
-    /// or not, then checks to see if the defence is high enough to reduce hp or not.
-    /// If the enemy has 0 or less hp give the player experience.
-    pub fn take_dmg(&mut self, ctx: &mut Context, player: &mut Player) {
-        let true_dmg = player.stats.atk - self.def;
-        if !self.invulnerable() {
-            if true_dmg > 0.0 {
-                self.hp -= true_dmg;
-                self.invulnerable = Duration::new(0u64, 0u32);
-                self.floating_text.push(FloatingText::new(
-                    ctx,
-                    self.x,
-                    self.y,
-                    true_dmg.to_string(),
-                    "Red",
-                ));
+    /// Takes a hit from the player (see Struck::react).
+    pub fn take_dmg(&mut self, ctx: &mut Context, player: &mut Player, hit: Hit) {
+        Struck {
+            x: self.x,
+            y: self.y,
+            def: self.def,
+            resist: RESIST,
+            hp: &mut self.hp,
+            invulnerable: &mut self.invulnerable,
+            status: &mut self.status,
+            floating_text: &mut self.floating_text,
+            knockback: None,
+            attack: None,
+            // too big to be slowed or stunned, but poison and fire still hurt
+            sticks: |kind| kind == StatusKind::Poison || kind == StatusKind::Burn,
+        }
+        .react(ctx, player, hit);
+    }
+
+    /// Returns if the enemy should be able to take damage (time is 1/4 sec)
+    fn invulnerable(&self) -> bool {
+        self.invulnerable < INVULNERABLE_TIME
+    }
+
+    /// Center of the boss (x and y are the top left of the sprite).
+    fn center(&self) -> (f32, f32) {
+        let hb = self.get_hitbox();
+        (hb.x + hb.w / 2.0, hb.y + hb.h / 2.0)
+    }
+
+    /// Switches to a new action and restarts the action timer.
+    fn start_action(&mut self, action: BossAction) {
+        self.action = action;
+        self.action_timer = Duration::new(0, 0);
+    }
+
+    /// Moves the boss `speed` pixels (already scaled by the time step) towards (xpos, ypos),
+    /// sliding along any wall it runs into.
+    /// Returns false if the boss was blocked.
+    fn move_towards(&mut self, xpos: f32, ypos: f32, speed: f32, level: &Level) -> bool {
+        let (cx, cy) = self.center();
+        let (dx, dy) = (xpos - cx, ypos - cy);
+        let dist = (dx * dx + dy * dy).sqrt();
+        if dist < 1.0 {
+            return true;
+        }
+
+        // Which way am I facing?
+        self.direction = if dx.abs() > dy.abs() {
+            if dx > 0.0 {
+                Direction::Right
             } else {
-                self.floating_text.push(FloatingText::new(
-                    ctx,
-                    self.x,
-                    self.y,
-                    "Blocked".to_string(),
-                    "Blue",
-                ));
+                Direction::Left
             }
-        }
+        } else if dy > 0.0 {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+        self.animation = (Animations::Walking, self.direction);
+
+        let (sx, sy) = towards((cx, cy), (xpos, ypos), speed);
+        let (mx, my) = slide(self.get_hitbox(), sx, sy, |r| r.collision(level));
+        self.x += mx;
+        self.y += my;
+        mx != 0.0 || my != 0.0
+    }
 
-        if self.hp <= 0.0 {
-            player.stats.check_for_level_up(5);
+    /// Unleashes the attack that was just telegraphed.
+    fn strike(&mut self, ctx: &mut Context, attack: BossAttack, player: &mut Player) {
+        let (cx, cy) = self.center();
+        match attack {
+            BossAttack::Charge => (),
+            BossAttack::Slam => {
+                // measured to the middle of the player so the slam is even all round
+                let hb = player.get_hitbox();
+                let (dx, dy) = (hb.x + hb.w / 2.0 - cx, hb.y + hb.h / 2.0 - cy);
+                if (dx * dx + dy * dy).sqrt() <= SLAM_RADIUS {
+                    player.take_hit(ctx, self.atk * 2.0, (cx, cy), KNOCKBACK * 2.0);
+                    player.apply_status(StatusKind::Stun);
+                }
+            }
+            BossAttack::Volley => {
+                let (count, spread) = self.phase.volley();
+                let aim = (self.target.1 - cy).atan2(self.target.0 - cx);
+                let step = spread / (count - 1usize) as f32;
+                for i in 0..count {
+                    let angle = aim - spread / 2.0 + step * i as f32;
+                    self.projectiles.push(
+                        Projectile::new(
+                            ctx,
+                            cx,
+                            cy,
+                            (angle.cos() * VOLLEY_SPEED, angle.sin() * VOLLEY_SPEED),
+                            self.atk,
+                            VOLLEY_RADIUS,
+                            graphics::Color::from_rgb(160u8, 32u8, 240u8),
+                        )
+                        .with_effect(StatusKind::Burn),
+                    );
+                }
+            }
         }
+        self.start_action(BossAction::Strike(attack));
     }
 
-    /// Returns if the enemy should be able to take damage (time is 1/4 sec)
-    fn invulnerable(&self) -> bool {
-        self.invulnerable < Duration::from_millis(250u64)
+    /// Builds the warning shown before an attack (or the flash of a landing slam).
+    fn telegraph_mesh(&self, ctx: &mut Context) -> GameResult<Option<graphics::Mesh>> {
+        let (cx, cy) = self.center();
+        let warn = graphics::Color::new(1.0, 0.0, 0.0, 0.4);
+        let mut mb = graphics::MeshBuilder::new();
+        match self.action {
+            BossAction::Telegraph(BossAttack::Charge) => {
+                mb.line(
+                    &[
+                        na::Point2::new(cx, cy),
+                        na::Point2::new(self.target.0, self.target.1),
+                    ],
+                    48.0,
+                    warn,
+                )?;
+            }
+            BossAction::Telegraph(BossAttack::Slam) => {
+                mb.circle(
+                    graphics::DrawMode::stroke(4.0),
+                    na::Point2::new(cx, cy),
+                    SLAM_RADIUS,
+                    1.0,
+                    warn,
+                );
+            }
+            BossAction::Telegraph(BossAttack::Volley) => {
+                mb.circle(
+                    graphics::DrawMode::fill(),
+                    na::Point2::new(cx, cy),
+                    48.0,
+                    1.0,
+                    graphics::Color::new(0.6, 0.1, 0.9, 0.4),
+                );
+            }
+            BossAction::Strike(BossAttack::Slam) => {
+                mb.circle(
+                    graphics::DrawMode::fill(),
+                    na::Point2::new(cx, cy),
+                    SLAM_RADIUS,
+                    1.0,
+                    warn,
+                );
+            }
+            _ => return Ok(None),
+        }
+        mb.build(ctx).map(Some)
     }
 }
 
@@ -124,9 +405,19 @@ impl Boss {
 impl DrawableEntity for Boss {
     /// Attempts to draw the enemy
     fn draw(&self, ctx: &mut Context) -> GameResult {
-        let dp = graphics::DrawParam::default().dest(na::Point2::new(self.x, self.y));
+        if let Some(mesh) = self.telegraph_mesh(ctx)? {
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+
+        let dp = graphics::DrawParam::default()
+            .dest(na::Point2::new(self.x, self.y))
+            .color(self.status.tint());
         graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
 
+        for p in &self.projectiles {
+            p.draw(ctx)?;
+        }
+
         self.floating_text.iter().for_each(|t| t.draw(ctx));
 
         Ok(())
@@ -151,8 +442,9 @@ impl CollideEntity for Boss {
 
 /// Functions associated with the Enemy trait implemented for the Boss struct
 impl Enemy for Boss {
-    /// Every update check for floating text, if we can be hit again, and if I have touched the player's attack box.
-    fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, _level: &Level) {
+    /// Every update check for floating text, if we can be hit again, if I have touched the player's attack box,
+    /// if I have moved into a new phase, and move my projectiles.
+    fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, level: &Level) {
         self.floating_text.retain(|t| t.live());
         self.floating_text.iter_mut().for_each(|t| t.update(delta));
 
@@ -161,12 +453,46 @@ impl Enemy for Boss {
             self.invulnerable += delta;
         }
 
-        // Player's atk_box hits me
-        if let Some(atk) = &player.atk_box {
-            if self.collision(atk) {
-                self.take_dmg(ctx, player);
+        // Poison and burn tick away at my hp
+        if let Some((dmg, kind)) = self.status.update(delta) {
+            if self.hp > 0.0 {
+                self.hp -= dmg;
+                self.floating_text.push(FloatingText::new(
+                    ctx,
+                    self.x,
+                    self.y,
+                    dmg.to_string(),
+                    kind.text_color(),
+                ));
             }
         }
+
+        // Player's attacks (swings, spells and fireballs) hit me
+        if let Some(hit) = player.attack_hitting(self, self.id) {
+            self.take_dmg(ctx, player, hit);
+        }
+
+        // Getting hurt enough moves the fight along (and interrupts whatever I was doing)
+        let phase = BossPhase::from_hp(self.hp, self.max_hp);
+        if phase != self.phase && self.islive() {
+            self.phase = phase;
+            self.pattern_index = 0usize;
+            self.start_action(BossAction::Chase);
+            self.floating_text.push(FloatingText::new(
+                ctx,
+                self.x,
+                self.y - 20.0,
+                "The Grue Grows Angrier!".to_string(),
+                "Red",
+            ));
+        }
+
+        self.summon_timer += delta;
+
+        self.projectiles
+            .iter_mut()
+            .for_each(|p| p.update(ctx, delta, player, level));
+        self.projectiles.retain(|p| p.live());
     }
 
     /// Checks to see if the enemy is still alive or not.
@@ -179,54 +505,69 @@ impl Enemy for Boss {
         &self.ai_type
     }
 
-    /// Describes how this enemy will chase the player.
+    /// Runs the boss's attack pattern: chase for a bit, telegraph an attack, strike, repeat.
     fn chase_player(
         &mut self,
         ctx: &mut Context,
         delta: Duration,
         player: &mut Player,
-        _level: &Level,
+        level: &Level,
     ) {
-        // Charge towards player.
-        if self.x >= player.x {
-            self.x -= 1.0;
-        }
-        if self.x <= player.x {
-            self.x += 1.0;
-        }
+        self.action_timer += delta;
+        let dt = delta.as_secs_f32();
 
-        if self.y >= player.y {
-            self.y -= 1.0;
-        }
-        if self.y <= player.y {
-            self.y += 1.0;
-        }
-
-        // Which way am I facing?
-        if self.x > player.x && self.y > player.y {
-            self.animation = (Animations::Walking, Direction::Left);
-            self.direction = Direction::Left;
-        } else if self.x < player.x && self.y < player.y {
-            self.animation = (Animations::Walking, Direction::Right);
-            self.direction = Direction::Right;
-        } else if self.y > player.y && self.x < player.x {
-            self.animation = (Animations::Walking, Direction::Up);
-            self.direction = Direction::Up;
-        } else {
-            self.animation = (Animations::Walking, Direction::Down);
-            self.direction = Direction::Down;
+        match self.action {
+            BossAction::Chase => {
+                self.move_towards(player.x, player.y, WALK_SPEED * dt, level);
+                if self.action_timer >= self.phase.recover_time() {
+                    let pattern = self.phase.pattern();
+                    let attack = pattern[self.pattern_index % pattern.len()];
+                    self.pattern_index += 1usize;
+                    self.target = (player.x, player.y);
+                    self.start_action(BossAction::Telegraph(attack));
+                }
+            }
+            BossAction::Telegraph(attack) => {
+                // keep the volley aimed at the player while winding up
+                if attack == BossAttack::Volley {
+                    self.target = (player.x, player.y);
+                }
+                if self.action_timer >= self.phase.telegraph_time(attack) {
+                    self.strike(ctx, attack, player);
+                }
+            }
+            BossAction::Strike(BossAttack::Charge) => {
+                let (cx, cy) = self.center();
+                let (tx, ty) = self.target;
+                // keep going past the target so the charge has some follow through
+                let ahead = (tx + (tx - cx), ty + (ty - cy));
+                let moving = self.move_towards(ahead.0, ahead.1, CHARGE_SPEED * dt, level);
+                if !moving || self.action_timer >= CHARGE_TIME {
+                    self.start_action(BossAction::Chase);
+                }
+            }
+            BossAction::Strike(_) => {
+                if self.action_timer >= SLAM_FLASH {
+                    self.start_action(BossAction::Chase);
+                }
+            }
         }
 
         self.sprite.get_mut(&self.animation).unwrap().animate(delta);
 
         // I touched the player.
         if self.collision(player) {
-            // need attack animation
-            player.take_dmg(ctx, self.atk);
+            // charging into the player hurts more
+            let dmg = match self.action {
+                BossAction::Strike(BossAttack::Charge) => self.atk * 1.5,
+                _ => self.atk,
+            };
+            let center = self.center();
+            player.take_hit(ctx, dmg, center, KNOCKBACK);
         }
     }
 
-    /// If the player comes within sight of the enemy then chase the player.
+    /// The boss always knows where the player is... just follow the pattern.
     fn chase_player_sight(
         &mut self,
         ctx: &mut Context,
@@ -234,19 +575,87 @@ impl Enemy for Boss {
         player: &mut Player,
         level: &Level,
     ) {
-        self.line_of_sight
-            .update(self.x - 100.0, self.y - 100.0, 200.0, 200.0);
+        self.chase_player(ctx, delta, player, level);
+    }
 
-        if self.line_of_sight.collision(player) {
-            // && !self.line_of_sight.collision(level) {
-            self.chase_player(ctx, delta, player, level);
+    /// Places minions around the boss when the summon timer is up, without going over the phase's cap.
+    fn spawn(&mut self, minions: usize) -> Vec<(f32, f32)> {
+        let cap = self.phase.minion_cap();
+        if self.summon_timer < self.phase.summon_time() || minions >= cap {
+            return Vec::new();
         }
+        self.summon_timer = Duration::new(0, 0);
+
+        let count = SUMMON_PER_WAVE.min(cap - minions);
+        let offset = combat::with_rng(|rng| rng.gen_range(0.0, 2.0 * PI));
+        let (cx, cy) = self.center();
+        (0..count)
+            .map(|i| {
+                let angle = offset + 2.0 * PI * i as f32 / count as f32;
+                // ghost sprites are 64x64 so shift to their top left corner
+                (
+                    cx + angle.cos() * SUMMON_RADIUS - 32.0,
+                    cy + angle.sin() * SUMMON_RADIUS - 32.0,
+                )
+            })
+            .collect()
+    }
+
+    /// The boss is too big to be shoved around by its minions.
+    fn pushable(&self) -> bool {
+        false
+    }
+
+    /// Experience the player gets for beating the boss.
+    fn xp_reward(&self) -> u32 {
+        self.xp
     }
 
-    /// Determines with this enemy spawns enemies.
-    fn spawn(&self) -> bool {
-        let mut rng = thread_rng();
-        let spawn = rng.gen_range(0, 100) as u64;
-        spawn == 5
+    /// What the boss drops when it dies.
+    fn loot_table(&self) -> LootTable {
+        LootTable::boss()
+    }
+
+    /// Bosses get a health bar.
+    fn boss_health(&self) -> Option<(f32, f32)> {
+        Some((self.hp, self.max_hp))
+    }
+}
+
+/// Where the boss is in the level
+impl Positioned for Boss {
+    /// Where the boss is.
+    fn position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    /// Moves the boss.
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+}
+
+#[cfg(test)]
+mod testboss {
+    use super::*;
+
+    #[test]
+    fn test_phase_from_hp() {
+        assert_eq!(BossPhase::from_hp(100.0, 100.0), BossPhase::One);
+        assert_eq!(BossPhase::from_hp(66.0, 100.0), BossPhase::Two);
+        assert_eq!(BossPhase::from_hp(34.0, 100.0), BossPhase::Two);
+        assert_eq!(BossPhase::from_hp(33.0, 100.0), BossPhase::Three);
+        assert_eq!(BossPhase::from_hp(-5.0, 100.0), BossPhase::Three);
+    }
+
+    #[test]
+    fn test_later_phases_are_harder() {
+        assert!(BossPhase::One.minion_cap() < BossPhase::Three.minion_cap());
+        assert!(
+            BossPhase::One.telegraph_time(BossAttack::Slam)
+                > BossPhase::Three.telegraph_time(BossAttack::Slam)
+        );
+        assert!(BossPhase::Three.pattern().contains(&BossAttack::Slam));
     }
 }
@@ -0,0 +1,334 @@
+use crate::entities::combat;
+use crate::entities::enemies::ai::*;
+use crate::entities::enemies::attack::KNOCKBACK;
+use crate::entities::enemies::enemiesstruct::{Enemy, Positioned};
+use crate::entities::enemies::spawner::EnemyKind;
+use crate::entities::environment::level::Level;
+use crate::entities::items::loot::LootTable;
+use crate::entities::player::playerstruct::Player;
+use ggez::graphics::{Color, Rect};
+use ggez::nalgebra as na;
+use ggez::*;
+use rand::prelude::*;
+use std::time::Duration;
+
+use super::super::{CollideEntity, DrawableEntity};
+
+/// Chance any enemy that shows up is an elite.
+const ELITE_CHANCE: f64 = 0.15;
+/// Chance an elite rolls a second affix.
+const SECOND_AFFIX_CHANCE: f64 = 0.3;
+/// How much of the damage a vampiric elite deals comes back as hp.
+const VAMPIRIC_HEAL: f32 = 0.5;
+/// Explosive elites go off in this radius when they die.
+const EXPLOSION_RADIUS: f32 = 96.0;
+const EXPLOSION_DMG: f32 = 6.0;
+/// How many weaker copies a splitting elite breaks into.
+const SPLIT_COUNT: usize = 2;
+
+/// Modifiers an elite enemy can roll.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Affix {
+    Armoured,
+    Fast,
+    Vampiric,
+    Explosive,
+    Splitting,
+}
+
+/// Functions for the Affix enum
+impl Affix {
+    /// Every affix (used for rolling).
+    pub const ALL: [Affix; 5] = [
+        Affix::Armoured,
+        Affix::Fast,
+        Affix::Vampiric,
+        Affix::Explosive,
+        Affix::Splitting,
+    ];
+
+    /// Word put in front of the enemy's name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Affix::Armoured => "Armoured",
+            Affix::Fast => "Fast",
+            Affix::Vampiric => "Vampiric",
+            Affix::Explosive => "Explosive",
+            Affix::Splitting => "Splitting",
+        }
+    }
+
+    /// Rolls the affixes for a new enemy (empty if it is not an elite).
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R) -> Vec<Affix> {
+        if !rng.gen_bool(ELITE_CHANCE) {
+            return Vec::new();
+        }
+        let count = if rng.gen_bool(SECOND_AFFIX_CHANCE) {
+            2
+        } else {
+            1
+        };
+        Affix::ALL.choose_multiple(rng, count).cloned().collect()
+    }
+
+    /// How an elite's stats change: (hp scale, atk scale, def bonus, speed scale).
+    /// Every elite is tougher, and some affixes add more on top.
+    pub fn scaling(affixes: &[Affix]) -> (f32, f32, f32, f32) {
+        let (mut hp, mut atk, mut def, mut speed) = (1.5, 1.25, 0.0, 1.0);
+        for a in affixes {
+            match a {
+                Affix::Armoured => {
+                    hp *= 1.25;
+                    def += 1.5;
+                }
+                Affix::Fast => speed *= 1.6,
+                Affix::Vampiric => atk *= 1.1,
+                Affix::Explosive | Affix::Splitting => (),
+            }
+        }
+        (hp, atk, def, speed)
+    }
+}
+
+/// Wraps any enemy and makes it an elite: tougher, with a coloured nameplate and affixes.
+pub struct Elite {
+    inner: Box<dyn Positioned>,
+    pub affixes: Vec<Affix>,
+    nameplate: graphics::Text,
+    color: Color,
+}
+
+/// Functions for the Elite struct
+impl Elite {
+    /// News up an Elite around an enemy, scaling its stats for the affixes.
+    pub fn new(ctx: &mut Context, mut inner: Box<dyn Positioned>, affixes: Vec<Affix>) -> Elite {
+        let (hp, atk, def, speed) = Affix::scaling(&affixes);
+        inner.empower(hp, atk, def, speed);
+
+        let mut name: Vec<&str> = affixes.iter().map(|a| a.name()).collect();
+        name.push(inner.kind().map_or("Enemy", |k| k.name()));
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let nameplate = graphics::Text::new((name.join(" "), font, 16.0));
+
+        // one affix is blue, more than that is gold
+        let color = if affixes.len() > 1 {
+            Color::from_rgb(255u8, 200u8, 0u8)
+        } else {
+            Color::from_rgb(100u8, 150u8, 255u8)
+        };
+
+        Elite {
+            inner,
+            affixes,
+            nameplate,
+            color,
+        }
+    }
+
+    /// Maybe turns a newly spawned enemy into an elite (one of a kinds like the boss never are).
+    pub fn roll(ctx: &mut Context, enemy: Box<dyn Positioned>) -> Box<dyn Positioned> {
+        if enemy.kind().is_none() {
+            return enemy;
+        }
+        let affixes = combat::with_rng(Affix::roll);
+        if affixes.is_empty() {
+            return enemy;
+        }
+        Box::new(Elite::new(ctx, enemy, affixes))
+    }
+
+    /// Returns true if this elite rolled affix.
+    pub fn has(&self, affix: Affix) -> bool {
+        self.affixes.contains(&affix)
+    }
+
+    /// Center of the enemy.
+    fn center(&self) -> (f32, f32) {
+        let hb = self.inner.get_hitbox();
+        (hb.x + hb.w / 2.0, hb.y + hb.h / 2.0)
+    }
+}
+
+/// Draw trait for the Elite
+impl DrawableEntity for Elite {
+    /// Draws the enemy with its nameplate above it
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        self.inner.draw(ctx)?;
+
+        let hb = self.inner.get_hitbox();
+        let width = self.nameplate.width(ctx) as f32;
+        let dest = na::Point2::new(hb.x + (hb.w - width) / 2.0, hb.y - 18.0);
+        graphics::queue_text(ctx, &self.nameplate, dest, Some(self.color));
+        graphics::draw_queued_text(
+            ctx,
+            graphics::DrawParam::default(),
+            None,
+            graphics::FilterMode::Linear,
+        )
+    }
+}
+
+/// Collide trait for the Elite (same as the enemy inside)
+impl CollideEntity for Elite {
+    /// The enemy's hitbox
+    fn get_hitbox(&self) -> Rect {
+        self.inner.get_hitbox()
+    }
+
+    /// The enemy's hitboxes
+    fn get_sub_hitboxs(&self) -> Vec<Rect> {
+        self.inner.get_sub_hitboxs()
+    }
+}
+
+/// Functions associated with the Enemy trait implemented for the Elite struct.
+/// Mostly passes everything through to the enemy inside.
+impl Enemy for Elite {
+    /// Updates the enemy. Vampiric elites heal off the damage they deal.
+    fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, level: &Level) {
+        let before = player.stats.hp;
+        self.inner.update(ctx, delta, player, level);
+        let dealt = before - player.stats.hp;
+        if self.has(Affix::Vampiric) && dealt > 0.0 {
+            self.inner.heal(dealt * VAMPIRIC_HEAL);
+        }
+    }
+
+    /// Checks to see if the enemy is still alive or not.
+    fn islive(&self) -> bool {
+        self.inner.islive()
+    }
+
+    /// Returns what type of AI the enemy is set at.
+    fn get_aitype(&mut self) -> &AITypes {
+        self.inner.get_aitype()
+    }
+
+    /// Chases the player like the enemy inside does.
+    fn chase_player(
+        &mut self,
+        ctx: &mut Context,
+        delta: Duration,
+        player: &mut Player,
+        level: &Level,
+    ) {
+        self.inner.chase_player(ctx, delta, player, level);
+    }
+
+    /// Chases the player (when seen) like the enemy inside does.
+    fn chase_player_sight(
+        &mut self,
+        ctx: &mut Context,
+        delta: Duration,
+        player: &mut Player,
+        level: &Level,
+    ) {
+        self.inner.chase_player_sight(ctx, delta, player, level);
+    }
+
+    /// Summons whatever the enemy inside summons.
+    fn spawn(&mut self, minions: usize) -> Vec<(f32, f32)> {
+        self.inner.spawn(minions)
+    }
+
+    /// Can other enemies shove this enemy around.
+    fn pushable(&self) -> bool {
+        self.inner.pushable()
+    }
+
+    /// Elites drop from the same table as the enemy inside.
+    fn loot_table(&self) -> LootTable {
+        self.inner.loot_table()
+    }
+
+    /// What kind of enemy is inside.
+    fn kind(&self) -> Option<EnemyKind> {
+        self.inner.kind()
+    }
+
+    /// Elites are worth the enemy's experience again for every affix.
+    fn xp_reward(&self) -> u32 {
+        self.inner.xp_reward() * (1 + self.affixes.len() as u32)
+    }
+
+    /// Makes the enemy inside even tougher.
+    fn empower(&mut self, hp: f32, atk: f32, def: f32, speed: f32) {
+        self.inner.empower(hp, atk, def, speed);
+    }
+
+    /// Heals the enemy inside.
+    fn heal(&mut self, amount: f32) {
+        self.inner.heal(amount);
+    }
+
+    /// Blows up on the player if explosive, and breaks into weaker copies if splitting.
+    fn on_death(&mut self, ctx: &mut Context, player: &mut Player) -> Vec<Box<dyn Positioned>> {
+        let mut left = self.inner.on_death(ctx, player);
+
+        let (cx, cy) = self.center();
+        if self.has(Affix::Explosive) {
+            let (dx, dy) = (player.x - cx, player.y - cy);
+            if (dx * dx + dy * dy).sqrt() <= EXPLOSION_RADIUS {
+                player.take_hit(ctx, EXPLOSION_DMG, (cx, cy), KNOCKBACK * 1.5);
+            }
+        }
+
+        if let (true, Some(kind)) = (self.has(Affix::Splitting), self.kind()) {
+            let (x, y) = self.position();
+            for i in 0..SPLIT_COUNT {
+                let offset = if i % 2 == 0 { -16.0 } else { 16.0 };
+                let mut copy = kind.create(ctx, x + offset, y, AITypes::MeleeDirect);
+                // weaker, but a bit quicker
+                copy.empower(0.5, 0.75, 0.0, 1.2);
+                left.push(copy);
+            }
+        }
+        left
+    }
+}
+
+/// Where the elite is in the level
+impl Positioned for Elite {
+    /// Where the enemy is.
+    fn position(&self) -> (f32, f32) {
+        self.inner.position()
+    }
+
+    /// Moves the enemy.
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.inner.set_position(x, y);
+    }
+}
+
+#[cfg(test)]
+mod testelite {
+    use super::*;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_roll_affixes() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let rolls: Vec<Vec<Affix>> = (0..1000).map(|_| Affix::roll(&mut rng)).collect();
+        let elites = rolls.iter().filter(|a| !a.is_empty()).count();
+        // about 15% should be elites
+        assert!(elites > 80 && elites < 250);
+        for a in &rolls {
+            assert!(a.len() <= 2);
+            if a.len() == 2 {
+                assert_ne!(a[0], a[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scaling() {
+        let (hp, atk, def, speed) = Affix::scaling(&[]);
+        assert!(hp > 1.0 && atk > 1.0);
+        assert_eq!((def, speed), (0.0, 1.0));
+
+        let (armoured_hp, _, def, _) = Affix::scaling(&[Affix::Armoured]);
+        assert!(armoured_hp > hp && def > 0.0);
+        let (_, _, _, speed) = Affix::scaling(&[Affix::Fast, Affix::Explosive]);
+        assert!(speed > 1.0);
+    }
+}
@@ -1,14 +1,29 @@
+use crate::entities::combat::{self, Defender, Resistances};
 use crate::entities::enemies::ai::*;
+use crate::entities::enemies::attack::{AttackPhase, EnemyAttack, KNOCKBACK};
+use crate::entities::enemies::elite::Elite;
 use crate::entities::enemies::ghost::Ghost;
+use crate::entities::enemies::separation::separation;
+use crate::entities::enemies::spawner::{Encounter, EnemyKind, Spawner};
 use crate::entities::environment::level::Level;
+use crate::entities::items::loot::{LootTable, Pile};
+use crate::entities::knockback::Knockback;
+use crate::entities::player::atk_box::Hit;
 use crate::entities::player::playerstruct::Player;
-use crate::entities::DrawableEntity;
+use crate::entities::status::{StatusEffect, StatusEffects, StatusKind};
+use crate::entities::{CollideEntity, DrawableEntity};
+use crate::ui::FloatingText;
+use ggez::graphics::Rect;
 use ggez::*;
-use rand::prelude::*;
 use std::time::Duration;
 
-/// Setting up the DrawableEntity triat for the Enemy struct
-pub trait Enemy: DrawableEntity {
+/// How fast regular enemies move (pixels per second).
+pub const ENEMY_SPEED: f32 = 60.0;
+/// How long an enemy can't be hurt again after taking a hit.
+pub const INVULNERABLE_TIME: Duration = Duration::from_millis(250);
+
+/// Setting up the DrawableEntity and CollideEntity triats for the Enemy struct
+pub trait Enemy: DrawableEntity + CollideEntity {
     fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, level: &Level);
     fn islive(&self) -> bool;
     fn get_aitype(&mut self) -> &AITypes;
@@ -26,14 +41,144 @@ pub trait Enemy: DrawableEntity {
         player: &mut Player,
         level: &Level,
     );
-    fn spawn(&self) -> bool;
+    fn spawn(&mut self, minions: usize) -> Vec<(f32, f32)>;
+    /// Can other enemies shove this enemy around.
+    fn pushable(&self) -> bool {
+        true
+    }
+    /// Experience the player gets for killing this enemy (given once, when it is cleared away).
+    fn xp_reward(&self) -> u32 {
+        0
+    }
+    /// What the enemy might drop when it dies.
+    fn loot_table(&self) -> LootTable {
+        LootTable::empty()
+    }
+    /// Returns (hp, max_hp) if this enemy should get a boss health bar.
+    fn boss_health(&self) -> Option<(f32, f32)> {
+        None
+    }
+    /// What kind of enemy this is (None for one of a kinds like the boss).
+    fn kind(&self) -> Option<EnemyKind> {
+        None
+    }
+    /// Scales hp and atk, adds def and scales speed (ie for elites).
+    fn empower(&mut self, _hp: f32, _atk: f32, _def: f32, _speed: f32) {}
+    /// Gives back some hp.
+    fn heal(&mut self, _amount: f32) {}
+    /// Called once when the enemy dies. Returns any enemies it leaves behind.
+    fn on_death(&mut self, _ctx: &mut Context, _player: &mut Player) -> Vec<Box<dyn Positioned>> {
+        Vec::new()
+    }
+}
+
+/// A single enemy standing somewhere in the level (the Enemies group as a whole isn't anywhere).
+pub trait Positioned: Enemy {
+    /// Where the enemy is (top left corner).
+    fn position(&self) -> (f32, f32);
+    /// Moves the enemy to (x, y) (top left corner).
+    fn set_position(&mut self, x: f32, y: f32);
+}
+
+/// The parts of an enemy that one of the player's hits changes, and the enemy's own numbers.
+/// Lets every enemy react to being hit the same way.
+pub struct Struck<'a> {
+    // top left corner
+    pub x: f32,
+    pub y: f32,
+    pub def: f32,
+    pub resist: Resistances,
+    pub hp: &'a mut f32,
+    // time since the last hit landed
+    pub invulnerable: &'a mut Duration,
+    pub status: &'a mut StatusEffects,
+    pub floating_text: &'a mut Vec<FloatingText>,
+    // None for enemies that can't be shoved around or knocked out of their attacks (ie the boss)
+    pub knockback: Option<&'a mut Knockback>,
+    pub attack: Option<&'a mut EnemyAttack>,
+    // which status effects the hit can leave behind
+    pub sticks: fn(StatusKind) -> bool,
+}
+
+/// Functions for the Struck struct
+impl<'a> Struck<'a> {
+    /// Whenever an enemy needs to take damage, we check to see if it is already dead
+    /// or invulnerable, then lets combat work out how much gets past its defence and resistances.
+    /// A hit that gets through knocks the enemy back, out of its wind up, and may leave a status effect.
+    /// (Experience for the kill is given out by Enemies::update once the enemy is cleared away.)
+    pub fn react(self, ctx: &mut Context, player: &mut Player, hit: Hit) {
+        // already dead, just waiting to be cleared away
+        if *self.hp <= 0.0 {
+            return;
+        }
+        let sticks = self.sticks;
+        if *self.invulnerable >= INVULNERABLE_TIME {
+            let damage = combat::roll(
+                player.attacker(hit),
+                Defender::new(self.def + self.status.def_modifier(), self.resist),
+            );
+            if !damage.blocked {
+                *self.hp -= damage.amount;
+                *self.invulnerable = Duration::new(0u64, 0u32);
+                if let Some(knockback) = self.knockback {
+                    knockback.hit(
+                        (player.x, player.y),
+                        (self.x + 32.0, self.y + 32.0),
+                        KNOCKBACK,
+                    );
+                }
+                // getting hit knocks me out of my wind up
+                if let Some(attack) = self.attack {
+                    if attack.phase == AttackPhase::Windup {
+                        attack.interrupt();
+                    }
+                }
+                // spells can leave something nasty behind
+                if let Some(kind) = hit.effect.filter(|k| sticks(*k)) {
+                    self.status.apply(StatusEffect::new(kind));
+                }
+                player.start_hit_stop();
+            }
+            self.floating_text
+                .push(FloatingText::damage(ctx, self.x, self.y, &damage));
+        }
+    }
+}
+
+/// Where an enemy in the level came from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Origin {
+    // placed in the level when it was built
+    Placed,
+    // summoned by another enemy (ie the boss)
+    Summoned,
+    // came out of the spawner at this index
+    Spawner(usize),
+    // part of a wave from the encounter with this id
+    Encounter(usize),
+}
+
+/// Functions for the Origin enum
+impl Origin {
+    /// Only enemies that can't be farmed forever drop loot.
+    pub fn drops_loot(self) -> bool {
+        match self {
+            Origin::Placed | Origin::Encounter(_) => true,
+            Origin::Summoned | Origin::Spawner(_) => false,
+        }
+    }
 }
 
 /// The enemies struct contains a Vec of Boxed Enemy.
 /// This will be the group of enemies in each level.
 #[derive(Default)]
 pub struct Enemies {
-    enemies: Vec<Box<dyn Enemy>>,
+    enemies: Vec<(Origin, Box<dyn Positioned>)>,
+    drops: Vec<(Pile, f32, f32)>,
+    // how many enemies have died
+    kills: u32,
+    spawners: Vec<Spawner>,
+    encounters: Vec<Encounter>,
 }
 
 /// The functions for the Enemies struct
@@ -42,12 +187,84 @@ impl Enemies {
     pub fn new() -> Enemies {
         Enemies {
             enemies: Vec::new(),
+            drops: Vec::new(),
+            kills: 0,
+            spawners: Vec::new(),
+            encounters: Vec::new(),
         }
     }
 
     /// Add a new enemy onto the enemies stack.
-    pub fn push(&mut self, enemy: Box<dyn Enemy>) {
-        self.enemies.push(enemy)
+    pub fn push(&mut self, ctx: &mut Context, enemy: Box<dyn Positioned>) {
+        self.push_from(ctx, Origin::Placed, enemy)
+    }
+
+    /// Add a new enemy onto the enemies stack, remembering where it came from.
+    /// Anything that wasn't summoned might turn out to be an elite.
+    pub fn push_from(&mut self, ctx: &mut Context, origin: Origin, enemy: Box<dyn Positioned>) {
+        let enemy = match origin {
+            Origin::Summoned => enemy,
+            _ => Elite::roll(ctx, enemy),
+        };
+        self.enemies.push((origin, enemy))
+    }
+
+    /// Sets up the spawners and encounter rooms described in the level file.
+    pub fn load(&mut self, level: &Level) {
+        self.spawners = level
+            .extras
+            .iter()
+            .filter_map(|l| Spawner::parse(l))
+            .collect();
+        self.encounters = Encounter::parse_all(&level.extras);
+    }
+
+    /// Gates of encounter rooms that are currently locked.
+    pub fn barriers(&self) -> Vec<Rect> {
+        self.encounters
+            .iter()
+            .filter(|e| e.locked())
+            .flat_map(|e| e.gates.iter().cloned())
+            .collect()
+    }
+
+    /// Hands over everything dropped by enemies that died (so it can be placed in the level).
+    pub fn take_drops(&mut self) -> Vec<(Pile, f32, f32)> {
+        std::mem::take(&mut self.drops)
+    }
+
+    /// How many enemies have died so far.
+    pub fn kills(&self) -> u32 {
+        self.kills
+    }
+
+    /// Pushes overlapping enemies apart (without pushing them into walls) so groups spread out around the player.
+    fn separate(&mut self, level: &Level) {
+        let boxes: Vec<Rect> = self.enemies.iter().map(|(_, e)| e.get_hitbox()).collect();
+        let pinned: Vec<bool> = self.enemies.iter().map(|(_, e)| !e.pushable()).collect();
+        let push = separation(&boxes, &pinned);
+
+        for ((_, e), (dx, dy)) in self.enemies.iter_mut().zip(push) {
+            let (x, y) = e.position();
+            if dx != 0.0 {
+                e.set_position(x + dx, y);
+                if e.collision(level) {
+                    e.set_position(x, y);
+                }
+            }
+            let (x, _) = e.position();
+            if dy != 0.0 {
+                e.set_position(x, y + dy);
+                if e.collision(level) {
+                    e.set_position(x, y);
+                }
+            }
+        }
+    }
+
+    /// Counts the living enemies that came from origin.
+    pub fn count_from(&self, origin: Origin) -> usize {
+        self.enemies.iter().filter(|(o, _)| *o == origin).count()
     }
 }
 
@@ -55,46 +272,103 @@ impl Enemies {
 impl DrawableEntity for Enemies {
     /// Draws the enemies
     fn draw(&self, ctx: &mut Context) -> GameResult {
-        for me in &self.enemies {
+        for (_, me) in &self.enemies {
             me.draw(ctx)?;
         }
         Ok(())
     }
 }
 
+/// Collide trait for the enemies struct (every enemy is a sub hitbox)
+impl CollideEntity for Enemies {
+    /// Box around all the enemies
+    fn get_hitbox(&self) -> Rect {
+        let mut boxes = self.get_sub_hitboxs().into_iter();
+        match boxes.next() {
+            Some(first) => boxes.fold(first, |r, b| r.combine_with(b)),
+            None => Rect::new(0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Hitbox of each enemy
+    fn get_sub_hitboxs(&self) -> Vec<Rect> {
+        self.enemies.iter().map(|(_, e)| e.get_hitbox()).collect()
+    }
+}
+
 /// Functions for the enemies struct from the Enemy trait
 impl Enemy for Enemies {
-    /// Removes "dead" enemies, updates all enemies and their ai, and checks to see if the ghost need to be spawned.
+    /// Removes "dead" enemies, updates all enemies and their ai, and places any minions the enemies summon.
     fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, level: &Level) {
-        let mut spawning = false;
+        let mut spawning = Vec::new();
 
-        // remove dead enemies
-        self.enemies.retain(|e| e.islive());
+        // experience and loot for dead enemies (summoned and spawned enemies don't drop anything, no farming)
+        for (origin, e) in self.enemies.iter().filter(|(_, e)| !e.islive()) {
+            self.kills += 1;
+            player.gain_exp(ctx, e.xp_reward());
+            if !origin.drops_loot() {
+                continue;
+            }
+            if let Some(pile) = combat::with_rng(|rng| e.loot_table().roll(rng)) {
+                let (x, y) = e.position();
+                self.drops.push((pile, x, y));
+            }
+        }
+
+        // remove dead enemies (and place whatever they left behind)
+        let mut leftovers = Vec::new();
+        for (origin, e) in self.enemies.iter_mut().filter(|(_, e)| !e.islive()) {
+            let origin = *origin;
+            leftovers.extend(
+                e.on_death(ctx, player)
+                    .into_iter()
+                    .map(|left| (origin, left)),
+            );
+        }
+        self.enemies.retain(|(_, e)| e.islive());
+        // leftovers count towards wherever the dead enemy came from (and never turn into elites themselves)
+        self.enemies.append(&mut leftovers);
 
+        let minions = self.count_from(Origin::Summoned);
         let mut ai = AI::new();
         // update enemies
-        self.enemies.iter_mut().for_each(|e| {
+        self.enemies.iter_mut().for_each(|(_, e)| {
             e.update(ctx, delta, player, level);
             ai.update(ctx, delta, e.as_mut(), player, level);
-            // I need to find a better spot for this spawning, keeps spawning ghosts if spawn comes back true. (boss only)
-            if e.spawn() {
-                spawning = true;
-            }
+            spawning.append(&mut e.spawn(minions));
         });
 
-        if spawning {
-            let mut rng = thread_rng();
-            self.enemies.push(Box::new(Ghost::new(
-                ctx,
-                rng.gen_range(0, 800) as f32,
-                rng.gen_range(0, 800) as f32,
-                AITypes::MeleeDirect,
-            )));
+        self.separate(level);
+
+        for (x, y) in spawning {
+            let ghost = Ghost::new(ctx, x, y, AITypes::MeleeDirect);
+            // don't summon minions inside of walls
+            if !ghost.collision(level) {
+                self.push_from(ctx, Origin::Summoned, Box::new(ghost));
+            }
         }
 
-        if !self.islive() {
-            // do something if there are no more enemies
-            // maybe spawn some new ones
+        // spawners keep enemies coming while the player is near
+        let target = (player.x, player.y);
+        for i in 0..self.spawners.len() {
+            let alive = self.count_from(Origin::Spawner(i));
+            let sp = &mut self.spawners[i];
+            if sp.update(delta, target, alive) {
+                let enemy = sp.kind.create(ctx, sp.x, sp.y, AITypes::MeleeDirect);
+                self.push_from(ctx, Origin::Spawner(i), enemy);
+            }
+        }
+
+        // encounter rooms send in the next wave once the last one is beaten
+        for i in 0..self.encounters.len() {
+            let id = self.encounters[i].id;
+            let alive = self.count_from(Origin::Encounter(id));
+            if let Some(wave) = self.encounters[i].update(target, alive) {
+                for (kind, x, y) in wave {
+                    let enemy = kind.create(ctx, x, y, AITypes::MeleeDirect);
+                    self.push_from(ctx, Origin::Encounter(id), enemy);
+                }
+            }
         }
     }
 
@@ -128,8 +402,13 @@ impl Enemy for Enemies {
     ) {
     }
 
-    /// Returns true
-    fn spawn(&self) -> bool {
-        true
+    /// Enemies as a whole never summon anything
+    fn spawn(&mut self, _minions: usize) -> Vec<(f32, f32)> {
+        Vec::new()
+    }
+
+    /// Returns the health of the first boss found (if there is one)
+    fn boss_health(&self) -> Option<(f32, f32)> {
+        self.enemies.iter().find_map(|(_, e)| e.boss_health())
     }
 }
@@ -1,7 +1,16 @@
+use crate::entities::combat::Resistances;
 use crate::entities::enemies::ai::*;
-use crate::entities::enemies::enemiesstruct::Enemy;
+use crate::entities::enemies::attack::*;
+use crate::entities::enemies::enemiesstruct::ENEMY_SPEED;
+use crate::entities::enemies::enemiesstruct::{Enemy, Positioned, Struck, INVULNERABLE_TIME};
+use crate::entities::enemies::spawner::EnemyKind;
 use crate::entities::environment::level::Level;
+use crate::entities::items::loot::LootTable;
+use crate::entities::knockback::Knockback;
+use crate::entities::movement::towards;
+use crate::entities::player::atk_box::Hit;
 use crate::entities::player::playerstruct::Player;
+use crate::entities::status::{StatusEffects, StatusKind};
 use crate::sprites::*;
 use ggez::graphics::{Image, Rect};
 use ggez::nalgebra as na;
@@ -9,17 +18,28 @@ use ggez::*;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use super::super::{Animations, CollideEntity, Direction, DrawableEntity};
+use super::super::{Animations, CollideEntity, Direction, DrawableEntity, EntityId};
 use crate::entities::enemies::sight::*;
 use crate::ui::FloatingText;
 
+/// How much of each type of damage the ghost shrugs off (blades mostly pass straight through ghosts).
+const RESIST: Resistances = Resistances {
+    physical: 0.5,
+    ..Resistances::NONE
+};
+
 /// Everything associated with the ghost enemy
 pub struct Ghost {
+    pub id: EntityId,
     pub x: f32,
     pub y: f32,
     pub hp: f32,
+    pub max_hp: f32,
     pub atk: f32,
     pub def: f32,
+    pub xp: u32,
+    // pixels per second
+    pub speed: f32,
     floating_text: Vec<FloatingText>,
     pub invulnerable: Duration,
     pub line_of_sight: LineOfSight,
@@ -27,6 +47,9 @@ pub struct Ghost {
     pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
     pub animation: (Animations, Direction),
     pub direction: Direction,
+    pub attack: EnemyAttack,
+    pub knockback: Knockback,
+    pub status: StatusEffects,
 }
 
 /// The functions used by the Ghost struct
@@ -62,15 +85,94 @@ impl Ghost {
                 .create_animated(Rect::new(0f32, 64f32, 64f32, 64f32), 8usize)
                 .unwrap(),
         );
+        // standing (attack recovery)
+        sprite.insert(
+            (Animations::Stand, Direction::Up),
+            builder
+                .create_animated(Rect::new(0f32, 0f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Stand, Direction::Left),
+            builder
+                .create_animated(Rect::new(0f32, 0f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Stand, Direction::Down),
+            builder
+                .create_animated(Rect::new(0f32, 64f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Stand, Direction::Right),
+            builder
+                .create_animated(Rect::new(0f32, 64f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        // attack wind up (holds the frame leaning back and squashes down)
+        sprite.insert(
+            (Animations::Cast, Direction::Up),
+            builder
+                .create_animated(Rect::new(128f32, 0f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Cast, Direction::Left),
+            builder
+                .create_animated(Rect::new(128f32, 0f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Cast, Direction::Down),
+            builder
+                .create_animated(Rect::new(128f32, 64f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Cast, Direction::Right),
+            builder
+                .create_animated(Rect::new(128f32, 64f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        // attack strike (holds the frame lunging forward and springs up)
+        sprite.insert(
+            (Animations::Slash, Direction::Up),
+            builder
+                .create_animated(Rect::new(64f32, 0f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Slash, Direction::Left),
+            builder
+                .create_animated(Rect::new(64f32, 0f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Slash, Direction::Down),
+            builder
+                .create_animated(Rect::new(64f32, 64f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Slash, Direction::Right),
+            builder
+                .create_animated(Rect::new(64f32, 64f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
 
         let floating_text = Vec::new();
 
         Ghost {
+            id: EntityId::fresh(),
             x: xpos,
             y: ypos,
             hp: 15.0,
+            max_hp: 15.0,
             atk: 3.0,
             def: 0.0,
+            xp: 5,
+            speed: ENEMY_SPEED,
             floating_text,
             invulnerable: Duration::new(1u64, 0u32),
             line_of_sight: LineOfSight::new(xpos, ypos),
@@ -78,44 +180,33 @@ impl Ghost {
             sprite,
             animation: (Animations::Walking, Direction::Down),
             direction: Direction::Down,
+            attack: EnemyAttack::new(300, 100, 600, 24.0, 48.0),
+            knockback: Knockback::new(),
+            status: StatusEffects::new(),
         }
     }
 
-    /// Whenever an enemy needs to take damage, we check to see if it is invulnerable
-    /// or not, then checks to see if the defence is high enough to reduce hp or not.
-    /// If the enemy has 0 or less hp give the player experience.
-    pub fn take_dmg(&mut self, ctx: &mut Context, player: &mut Player) {
-        let true_dmg = player.stats.atk - self.def;
-        if !self.invulnerable() {
-            if true_dmg > 0.0 {
-                self.hp -= true_dmg;
-                self.invulnerable = Duration::new(0u64, 0u32);
-                self.floating_text.push(FloatingText::new(
-                    ctx,
-                    self.x,
-                    self.y,
-                    true_dmg.to_string(),
-                    "Red",
-                ));
-            } else {
-                self.floating_text.push(FloatingText::new(
-                    ctx,
-                    self.x,
-                    self.y,
-                    "Blocked".to_string(),
-                    "Blue",
-                ));
-            }
-        }
-
-        if self.hp <= 0.0 {
-            player.stats.check_for_level_up(5);
+    /// Takes a hit from the player (see Struck::react).
+    pub fn take_dmg(&mut self, ctx: &mut Context, player: &mut Player, hit: Hit) {
+        Struck {
+            x: self.x,
+            y: self.y,
+            def: self.def,
+            resist: RESIST,
+            hp: &mut self.hp,
+            invulnerable: &mut self.invulnerable,
+            status: &mut self.status,
+            floating_text: &mut self.floating_text,
+            knockback: Some(&mut self.knockback),
+            attack: Some(&mut self.attack),
+            sticks: |_| true,
         }
+        .react(ctx, player, hit);
     }
 
     /// Returns if the enemy should be able to take damage (time is 1/4 sec)
     fn invulnerable(&self) -> bool {
-        self.invulnerable < Duration::from_millis(250u64)
+        self.invulnerable < INVULNERABLE_TIME
     }
 }
 
@@ -123,7 +214,21 @@ impl Ghost {
 impl DrawableEntity for Ghost {
     /// Attempts to draw the enemy
     fn draw(&self, ctx: &mut Context) -> GameResult {
-        let dp = graphics::DrawParam::default().dest(na::Point2::new(self.x, self.y));
+        self.attack.draw(ctx)?;
+
+        // squash and stretch around my feet to show the attack coming
+        let (sx, sy) = self.attack.squash();
+        let dp = graphics::DrawParam::default()
+            .dest(na::Point2::new(
+                self.x + 32.0 * (1.0 - sx),
+                self.y + 64.0 * (1.0 - sy),
+            ))
+            .scale(na::Vector2::new(sx, sy))
+            .color(if self.attack.busy() {
+                self.attack.tint()
+            } else {
+                self.status.tint()
+            });
         graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
 
         self.floating_text.iter().for_each(|t| t.draw(ctx));
@@ -150,8 +255,9 @@ impl CollideEntity for Ghost {
 
 /// Functions associated with the Enemy trait implemented for the Ghost struct
 impl Enemy for Ghost {
-    /// Every update check for floating text, if we can be hit again, and if I have touched the player's attack box.
-    fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, _level: &Level) {
+    /// Every update check for floating text, if we can be hit again, if I have touched the player's attack box,
+    /// where my own attack is at, and if I am being knocked back.
+    fn update(&mut self, ctx: &mut Context, delta: Duration, player: &mut Player, level: &Level) {
         self.floating_text.retain(|t| t.live());
         self.floating_text.iter_mut().for_each(|t| t.update(delta));
 
@@ -160,12 +266,46 @@ impl Enemy for Ghost {
             self.invulnerable += delta;
         }
 
-        // Player's atk_box hits me
-        if let Some(atk) = &player.atk_box {
-            if self.collision(atk) {
-                self.take_dmg(ctx, player);
+        // Poison and burn tick away at my hp
+        if let Some((dmg, kind)) = self.status.update(delta) {
+            if self.hp > 0.0 {
+                self.hp -= dmg;
+                self.floating_text.push(FloatingText::new(
+                    ctx,
+                    self.x,
+                    self.y,
+                    dmg.to_string(),
+                    kind.text_color(),
+                ));
             }
         }
+        // Stunned enemies drop what they were doing
+        if self.status.has(StatusKind::Stun) {
+            self.attack.interrupt();
+        }
+
+        // Player's attacks (swings, spells and fireballs) hit me
+        if let Some(hit) = player.attack_hitting(self, self.id) {
+            self.take_dmg(ctx, player, hit);
+        }
+
+        // My attack hits the player
+        self.attack.update(delta);
+        if self.attack.strike_hits(player) {
+            player.take_hit(ctx, self.atk, (self.x + 32.0, self.y + 32.0), KNOCKBACK);
+            player.apply_status(StatusKind::Slow);
+        }
+        if self.attack.busy() {
+            self.animation = (self.attack.animation(), self.direction);
+            self.sprite.get_mut(&self.animation).unwrap().animate(delta);
+        }
+
+        // Get knocked back (but not through walls)
+        let (dx, dy) = self
+            .knockback
+            .resolve(self.get_hitbox(), level, delta.as_secs_f32());
+        self.x += dx;
+        self.y += dy;
     }
 
     /// Checks to see if the enemy is still alive or not.
@@ -181,25 +321,21 @@ impl Enemy for Ghost {
     /// Describes how this enemy will chase the player.
     fn chase_player(
         &mut self,
-        ctx: &mut Context,
+        _ctx: &mut Context,
         delta: Duration,
         player: &mut Player,
         _level: &Level,
     ) {
-        // Charge towards player.
-        if self.x >= player.x {
-            self.x -= 1.0;
-        }
-        if self.x <= player.x {
-            self.x += 1.0;
+        // can't move while attacking, being knocked around or stunned
+        if self.attack.busy() || self.knockback.active() || self.status.has(StatusKind::Stun) {
+            return;
         }
+        let step = self.speed * self.status.speed_scale() * delta.as_secs_f32();
 
-        if self.y >= player.y {
-            self.y -= 1.0;
-        }
-        if self.y <= player.y {
-            self.y += 1.0;
-        }
+        // Charge towards player (diagonals aren't faster).
+        let (dx, dy) = towards((self.x, self.y), (player.x, player.y), step);
+        self.x += dx;
+        self.y += dy;
 
         // Which way am I facing?
         if self.x > player.x && self.y > player.y {
@@ -218,10 +354,10 @@ impl Enemy for Ghost {
 
         self.sprite.get_mut(&self.animation).unwrap().animate(delta);
 
-        // I touched the player.
-        if self.collision(player) {
-            // need attack animation
-            player.take_dmg(ctx, self.atk);
+        // Close enough to swing at the player (ghosts float right through them otherwise)
+        if self.attack.in_range(self.get_hitbox(), player.get_hitbox()) {
+            self.direction = facing(self.get_hitbox(), player.get_hitbox());
+            self.attack.start(self.get_hitbox(), self.direction);
         }
     }
 
@@ -242,8 +378,51 @@ impl Enemy for Ghost {
         }
     }
 
+    /// Experience the player gets for killing this enemy.
+    fn xp_reward(&self) -> u32 {
+        self.xp
+    }
+
+    /// What this enemy drops when it dies.
+    fn loot_table(&self) -> LootTable {
+        LootTable::ghost()
+    }
+
+    /// What kind of enemy this is.
+    fn kind(&self) -> Option<EnemyKind> {
+        Some(EnemyKind::Ghost)
+    }
+
+    /// Makes this enemy tougher (ie it rolled as an elite).
+    fn empower(&mut self, hp: f32, atk: f32, def: f32, speed: f32) {
+        self.max_hp *= hp;
+        self.hp = self.max_hp;
+        self.atk *= atk;
+        self.def += def;
+        self.speed *= speed;
+    }
+
+    /// Heals without going over max hp.
+    fn heal(&mut self, amount: f32) {
+        self.hp = (self.hp + amount).min(self.max_hp);
+    }
+
     /// Determines with this enemy spawns enemies.
-    fn spawn(&self) -> bool {
-        false
+    fn spawn(&mut self, _minions: usize) -> Vec<(f32, f32)> {
+        Vec::new()
+    }
+}
+
+/// Where the ghost is in the level
+impl Positioned for Ghost {
+    /// Where this enemy is.
+    fn position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    /// Moves this enemy.
+    fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
     }
 }
@@ -11,6 +11,16 @@ pub mod skeleton;
 
 pub mod ai;
 
+pub mod attack;
+
 pub mod enemiesstruct;
 
 pub mod sight;
+
+pub mod projectile;
+
+pub mod spawner;
+
+pub mod separation;
+
+pub mod elite;
@@ -0,0 +1,125 @@
+use crate::entities::enemies::attack::KNOCKBACK;
+use crate::entities::environment::level::Level;
+use crate::entities::player::playerstruct::Player;
+use crate::entities::status::StatusKind;
+use ggez::nalgebra as na;
+use ggez::*;
+use std::time::Duration;
+
+use super::super::{CollideEntity, DrawableEntity};
+
+/// How long a projectile flies before fizzling out.
+const LIFETIME: Duration = Duration::from_millis(3000);
+
+/// A simple projectile fired by enemies. Flies in a straight line until it hits
+/// the player, a wall, or runs out of time.
+pub struct Projectile {
+    pub x: f32,
+    pub y: f32,
+    // pixels per second
+    pub dx: f32,
+    pub dy: f32,
+    pub dmg: f32,
+    pub radius: f32,
+    pub effect: Option<StatusKind>,
+    duration: Duration,
+    hit: bool,
+    shape: graphics::Mesh,
+}
+
+/// Functions for the Projectile struct
+impl Projectile {
+    /// News up a Projectile centered at (xpos, ypos) moving velocity (dx, dy) every frame.
+    pub fn new(
+        ctx: &mut Context,
+        xpos: f32,
+        ypos: f32,
+        velocity: (f32, f32),
+        dmg: f32,
+        radius: f32,
+        color: graphics::Color,
+    ) -> Projectile {
+        let shape = graphics::MeshBuilder::new()
+            .circle(
+                graphics::DrawMode::fill(),
+                na::Point2::new(0.0, 0.0),
+                radius,
+                0.5,
+                color,
+            )
+            .build(ctx)
+            .unwrap();
+
+        Projectile {
+            x: xpos,
+            y: ypos,
+            dx: velocity.0,
+            dy: velocity.1,
+            dmg,
+            radius,
+            effect: None,
+            duration: Duration::new(0, 0),
+            hit: false,
+            shape,
+        }
+    }
+
+    /// Makes the projectile leave a status effect on whoever it hits.
+    pub fn with_effect(mut self, kind: StatusKind) -> Projectile {
+        self.effect = Some(kind);
+        self
+    }
+
+    /// Moves the projectile and checks if it hit the player or a wall.
+    pub fn update(
+        &mut self,
+        ctx: &mut Context,
+        delta: Duration,
+        player: &mut Player,
+        level: &Level,
+    ) {
+        self.duration += delta;
+        let dt = delta.as_secs_f32();
+        self.x += self.dx * dt;
+        self.y += self.dy * dt;
+
+        if self.collision(player) {
+            // shove the player the way the projectile was flying
+            let from = (self.x - self.dx, self.y - self.dy);
+            player.take_hit(ctx, self.dmg, from, KNOCKBACK / 2.0);
+            if let Some(kind) = self.effect {
+                player.apply_status(kind);
+            }
+            self.hit = true;
+        } else if self.collision(level) {
+            self.hit = true;
+        }
+    }
+
+    /// Returns true while the projectile is still flying.
+    pub fn live(&self) -> bool {
+        !self.hit && self.duration < LIFETIME
+    }
+}
+
+/// Draw trait for the Projectile
+impl DrawableEntity for Projectile {
+    /// Draws the Projectile
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let dp = graphics::DrawParam::default().dest(na::Point2::new(self.x, self.y));
+        graphics::draw(ctx, &self.shape, dp)
+    }
+}
+
+/// Collide trait for the Projectile
+impl CollideEntity for Projectile {
+    /// Finds the hitbox for the Projectile
+    fn get_hitbox(&self) -> graphics::Rect {
+        graphics::Rect::new(
+            self.x - self.radius,
+            self.y - self.radius,
+            self.radius * 2.0,
+            self.radius * 2.0,
+        )
+    }
+}
@@ -0,0 +1,155 @@
+use ggez::graphics::Rect;
+use std::collections::HashMap;
+
+/// Size of a grid cell, about the size of an enemy so neighbours are at most one cell away.
+const CELL_SIZE: f32 = 64.0;
+/// How much of the overlap gets pushed apart every frame (less is smoother).
+const STRENGTH: f32 = 0.5;
+/// Most an enemy can be pushed in one frame.
+const MAX_PUSH: f32 = 2.0;
+
+/// Buckets hitboxes by grid cell so only nearby hitboxes need to be checked against each other.
+pub struct SpatialGrid {
+    cell: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+/// Functions for the SpatialGrid struct
+impl SpatialGrid {
+    /// News up an empty SpatialGrid with square cells of size cell.
+    pub fn new(cell: f32) -> SpatialGrid {
+        SpatialGrid {
+            cell,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Which cell the center of r is in.
+    fn cell_of(&self, r: &Rect) -> (i32, i32) {
+        (
+            ((r.x + r.w / 2.0) / self.cell).floor() as i32,
+            ((r.y + r.h / 2.0) / self.cell).floor() as i32,
+        )
+    }
+
+    /// Adds hitbox number index to the grid.
+    pub fn insert(&mut self, index: usize, r: &Rect) {
+        let key = self.cell_of(r);
+        self.cells.entry(key).or_default().push(index);
+    }
+
+    /// Indexes of everything in the cell of r and the cells around it.
+    pub fn neighbours(&self, r: &Rect) -> Vec<usize> {
+        let (cx, cy) = self.cell_of(r);
+        let mut found = Vec::new();
+        for x in cx - 1..=cx + 1 {
+            for y in cy - 1..=cy + 1 {
+                if let Some(list) = self.cells.get(&(x, y)) {
+                    found.extend_from_slice(list);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Works out how far to push every hitbox so overlapping ones drift apart.
+/// Pinned hitboxes don't move, whoever is touching them gets the whole push.
+/// Hitboxes bigger than a grid cell may miss some neighbours, which is fine for steering.
+pub fn separation(boxes: &[Rect], pinned: &[bool]) -> Vec<(f32, f32)> {
+    let mut grid = SpatialGrid::new(CELL_SIZE);
+    for (i, b) in boxes.iter().enumerate() {
+        grid.insert(i, b);
+    }
+
+    let mut push = vec![(0f32, 0f32); boxes.len()];
+    for (i, a) in boxes.iter().enumerate() {
+        for j in grid.neighbours(a) {
+            // look at each pair once
+            if j <= i || pinned[i] && pinned[j] {
+                continue;
+            }
+            let b = &boxes[j];
+            if !a.overlaps(b) {
+                continue;
+            }
+
+            // how deep the overlap goes (use the shallow side)
+            let ox = (a.x + a.w).min(b.x + b.w) - a.x.max(b.x);
+            let oy = (a.y + a.h).min(b.y + b.h) - a.y.max(b.y);
+            let depth = ox.min(oy) * STRENGTH;
+
+            // push along the line between centers
+            let mut dx = (a.x + a.w / 2.0) - (b.x + b.w / 2.0);
+            let mut dy = (a.y + a.h / 2.0) - (b.y + b.h / 2.0);
+            let dist = (dx * dx + dy * dy).sqrt();
+            if dist > 0.001 {
+                dx /= dist;
+                dy /= dist;
+            } else {
+                // right on top of each other, spread out using the golden angle so they don't all go the same way
+                let angle = (i + j) as f32 * 2.399_963;
+                dx = angle.cos();
+                dy = angle.sin();
+            }
+
+            let (share_a, share_b) = match (pinned[i], pinned[j]) {
+                (true, _) => (0.0, 1.0),
+                (_, true) => (1.0, 0.0),
+                _ => (0.5, 0.5),
+            };
+            push[i].0 += dx * depth * share_a;
+            push[i].1 += dy * depth * share_a;
+            push[j].0 -= dx * depth * share_b;
+            push[j].1 -= dy * depth * share_b;
+        }
+    }
+
+    push.iter()
+        .map(|(x, y)| (x.clamp(-MAX_PUSH, MAX_PUSH), y.clamp(-MAX_PUSH, MAX_PUSH)))
+        .collect()
+}
+
+#[cfg(test)]
+mod testseparation {
+    use super::*;
+
+    #[test]
+    fn test_stacked_boxes_push_apart() {
+        let boxes = vec![Rect::new(0.0, 0.0, 64.0, 64.0); 2];
+        let push = separation(&boxes, &[false, false]);
+        assert!(push[0].0 != 0.0 || push[0].1 != 0.0);
+        assert!((push[0].0 + push[1].0).abs() < 0.001);
+        assert!((push[0].1 + push[1].1).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_pinned_and_apart() {
+        let boxes = vec![Rect::new(0.0, 0.0, 64.0, 64.0), Rect::new(32.0, 0.0, 64.0, 64.0)];
+        let push = separation(&boxes, &[true, false]);
+        assert_eq!(push[0], (0.0, 0.0));
+        assert!(push[1].0 > 0.0);
+
+        let boxes = vec![Rect::new(0.0, 0.0, 64.0, 64.0), Rect::new(500.0, 0.0, 64.0, 64.0)];
+        assert_eq!(separation(&boxes, &[false, false]), vec![(0.0, 0.0); 2]);
+    }
+
+    #[test]
+    fn test_grid_finds_same_overlaps_as_brute_force() {
+        let boxes: Vec<Rect> = (0..300)
+            .map(|i| Rect::new((i * 37 % 900) as f32, (i * 53 % 700) as f32, 64.0, 64.0))
+            .collect();
+        let mut grid = SpatialGrid::new(CELL_SIZE);
+        for (i, b) in boxes.iter().enumerate() {
+            grid.insert(i, b);
+        }
+        for (i, a) in boxes.iter().enumerate() {
+            let near = grid.neighbours(a);
+            for (j, b) in boxes.iter().enumerate() {
+                if i != j && a.overlaps(b) {
+                    assert!(near.contains(&j));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use crate::entities::enemies::ai::AITypes;
+use crate::entities::enemies::blob::Blob;
+use crate::entities::enemies::enemiesstruct::Positioned;
+use crate::entities::enemies::ghost::Ghost;
+use crate::entities::enemies::skeleton::Skeleton;
+use ggez::graphics::Rect;
+use ggez::Context;
+use std::time::Duration;
+
+/// The kinds of enemies that can be spawned by name (ie from a level file).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EnemyKind {
+    Blob,
+    Skeleton,
+    Ghost,
+}
+
+/// Functions for the EnemyKind enum
+impl EnemyKind {
+    /// Looks up an EnemyKind from the name used in level files.
+    pub fn from_name(name: &str) -> Option<EnemyKind> {
+        match name {
+            "blob" => Some(EnemyKind::Blob),
+            "skeleton" => Some(EnemyKind::Skeleton),
+            "ghost" => Some(EnemyKind::Ghost),
+            _ => None,
+        }
+    }
+
+    /// Name shown for this kind of enemy (ie on elite nameplates).
+    pub fn name(self) -> &'static str {
+        match self {
+            EnemyKind::Blob => "Blob",
+            EnemyKind::Skeleton => "Skeleton",
+            EnemyKind::Ghost => "Ghost",
+        }
+    }
+
+    /// Creates a new enemy of this kind at (xpos, ypos).
+    pub fn create(
+        self,
+        ctx: &mut Context,
+        xpos: f32,
+        ypos: f32,
+        ai_type: AITypes,
+    ) -> Box<dyn Positioned> {
+        match self {
+            EnemyKind::Blob => Box::new(Blob::new(ctx, xpos, ypos, ai_type)),
+            EnemyKind::Skeleton => Box::new(Skeleton::new(ctx, xpos, ypos, ai_type)),
+            EnemyKind::Ghost => Box::new(Ghost::new(ctx, xpos, ypos, ai_type)),
+        }
+    }
+}
+
+/// Keeps spitting out enemies while the player is close by.
+/// Level file line: `spawner <kind> <x> <y> <max alive> <interval ms> <trigger radius>`
+pub struct Spawner {
+    pub kind: EnemyKind,
+    pub x: f32,
+    pub y: f32,
+    pub max_alive: usize,
+    pub interval: Duration,
+    pub trigger_radius: f32,
+    timer: Duration,
+}
+
+/// Functions for the Spawner struct
+impl Spawner {
+    /// Reads a spawner from a level file line (None if the line is not a valid spawner).
+    pub fn parse(line: &str) -> Option<Spawner> {
+        let mut parse = line.split_whitespace();
+        if parse.next()? != "spawner" {
+            return None;
+        }
+        let kind = EnemyKind::from_name(parse.next()?)?;
+        let x = parse.next()?.parse::<f32>().ok()?;
+        let y = parse.next()?.parse::<f32>().ok()?;
+        let max_alive = parse.next()?.parse::<usize>().ok()?;
+        let interval = Duration::from_millis(parse.next()?.parse::<u64>().ok()?);
+        let trigger_radius = parse.next()?.parse::<f32>().ok()?;
+        Some(Spawner {
+            kind,
+            x,
+            y,
+            max_alive,
+            interval,
+            trigger_radius,
+            // first enemy comes out as soon as the player shows up
+            timer: interval,
+        })
+    }
+
+    /// Returns true when a new enemy should come out: the target is within the trigger radius,
+    /// the interval has passed, and fewer than max alive of this spawner's enemies are around.
+    pub fn update(&mut self, delta: Duration, target: (f32, f32), alive: usize) -> bool {
+        let (dx, dy) = (target.0 - self.x, target.1 - self.y);
+        if (dx * dx + dy * dy).sqrt() > self.trigger_radius {
+            return false;
+        }
+        if self.timer < self.interval {
+            self.timer += delta;
+            return false;
+        }
+        if alive >= self.max_alive {
+            return false;
+        }
+        self.timer = Duration::new(0, 0);
+        true
+    }
+}
+
+/// Where an encounter room is in the fight.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EncounterState {
+    // player hasn't walked in yet
+    Waiting,
+    // gates are shut and waves are coming
+    Fighting,
+    // all waves beaten, gates open for good
+    Cleared,
+}
+
+/// A room that locks the player in until every wave of enemies has been beaten.
+/// Level file lines:
+/// `encounter <id> <x> <y> <w> <h>` the room (walking into it starts the fight)
+/// `gate <id> <x> <y>` a 64x64 gate that blocks while the fight is on
+/// `wave <id> <kind> <x> <y> [<kind> <x> <y> ...]` one wave of enemies (in order)
+pub struct Encounter {
+    pub id: usize,
+    pub area: Rect,
+    pub gates: Vec<Rect>,
+    pub waves: Vec<Vec<(EnemyKind, f32, f32)>>,
+    pub wave: usize,
+    pub state: EncounterState,
+}
+
+/// Functions for the Encounter struct
+impl Encounter {
+    /// News up an Encounter with no gates or waves.
+    pub fn new(id: usize, area: Rect) -> Encounter {
+        Encounter {
+            id,
+            area,
+            gates: Vec::new(),
+            waves: Vec::new(),
+            wave: 0usize,
+            state: EncounterState::Waiting,
+        }
+    }
+
+    /// Reads all encounters (with their gates and waves) out of level file lines.
+    /// Lines that aren't about encounters are ignored.
+    pub fn parse_all(lines: &[String]) -> Vec<Encounter> {
+        fn num(word: Option<&str>) -> Option<f32> {
+            word?.parse::<f32>().ok()
+        }
+
+        let mut encounters: Vec<Encounter> = Vec::new();
+        for line in lines {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.len() < 2 {
+                continue;
+            }
+            let id = match words[1].parse::<usize>() {
+                Ok(id) => id,
+                Err(_) => continue,
+            };
+            match words[0] {
+                "encounter" if words.len() == 6 => {
+                    if let (Some(x), Some(y), Some(w), Some(h)) = (
+                        num(words.get(2).copied()),
+                        num(words.get(3).copied()),
+                        num(words.get(4).copied()),
+                        num(words.get(5).copied()),
+                    ) {
+                        encounters.push(Encounter::new(id, Rect::new(x, y, w, h)));
+                    }
+                }
+                "gate" if words.len() == 4 => {
+                    let enc = encounters.iter_mut().find(|e| e.id == id);
+                    if let (Some(enc), Some(x), Some(y)) =
+                        (enc, num(words.get(2).copied()), num(words.get(3).copied()))
+                    {
+                        enc.gates.push(Rect::new(x, y, 64.0, 64.0));
+                    }
+                }
+                "wave" => {
+                    let wave: Vec<(EnemyKind, f32, f32)> = words[2..]
+                        .chunks(3)
+                        .filter_map(|c| {
+                            if c.len() < 3 {
+                                return None;
+                            }
+                            Some((
+                                EnemyKind::from_name(c[0])?,
+                                num(Some(c[1]))?,
+                                num(Some(c[2]))?,
+                            ))
+                        })
+                        .collect();
+                    if let Some(enc) = encounters.iter_mut().find(|e| e.id == id) {
+                        if !wave.is_empty() {
+                            enc.waves.push(wave);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        encounters
+    }
+
+    /// Returns true while the gates are shut.
+    pub fn locked(&self) -> bool {
+        self.state == EncounterState::Fighting
+    }
+
+    /// Moves the fight along. `target` is where the player is and `alive` is how many of
+    /// this encounter's enemies are still around. Returns the next wave to spawn (if it is time).
+    pub fn update(
+        &mut self,
+        target: (f32, f32),
+        alive: usize,
+    ) -> Option<Vec<(EnemyKind, f32, f32)>> {
+        match self.state {
+            EncounterState::Waiting => {
+                let inside = target.0 >= self.area.x
+                    && target.0 <= self.area.x + self.area.w
+                    && target.1 >= self.area.y
+                    && target.1 <= self.area.y + self.area.h;
+                if !inside {
+                    return None;
+                }
+                if self.waves.is_empty() {
+                    self.state = EncounterState::Cleared;
+                    return None;
+                }
+                self.state = EncounterState::Fighting;
+                self.wave = 0usize;
+                Some(self.waves[0].clone())
+            }
+            EncounterState::Fighting => {
+                if alive > 0 {
+                    return None;
+                }
+                self.wave += 1usize;
+                if self.wave < self.waves.len() {
+                    Some(self.waves[self.wave].clone())
+                } else {
+                    self.state = EncounterState::Cleared;
+                    None
+                }
+            }
+            EncounterState::Cleared => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod testspawner {
+    use super::*;
+
+    #[test]
+    fn test_spawner_parse_and_update() {
+        assert!(Spawner::parse("spawner dragon 0 0 1 100 50").is_none());
+        assert!(Spawner::parse("10 20 3").is_none());
+
+        let mut sp = Spawner::parse("spawner blob 100 100 2 1000 200").unwrap();
+        assert_eq!(sp.kind, EnemyKind::Blob);
+        // player too far away
+        assert!(!sp.update(Duration::from_millis(16), (500.0, 500.0), 0));
+        // player close, first one comes right away
+        assert!(sp.update(Duration::from_millis(16), (150.0, 100.0), 0));
+        // have to wait for the interval
+        assert!(!sp.update(Duration::from_millis(500), (150.0, 100.0), 1));
+        assert!(!sp.update(Duration::from_millis(600), (150.0, 100.0), 1));
+        assert!(sp.update(Duration::from_millis(16), (150.0, 100.0), 1));
+        // too many alive
+        sp.update(Duration::from_millis(2000), (150.0, 100.0), 2);
+        assert!(!sp.update(Duration::from_millis(16), (150.0, 100.0), 2));
+    }
+
+    #[test]
+    fn test_encounter_waves() {
+        let lines: Vec<String> = vec![
+            "encounter 1 0 0 100 100",
+            "gate 1 100 0",
+            "wave 1 blob 10 10 blob 20 20",
+            "wave 1 skeleton 30 30",
+            "spawner ghost 0 0 1 1 1",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let mut encounters = Encounter::parse_all(&lines);
+        assert_eq!(encounters.len(), 1usize);
+        let enc = &mut encounters[0];
+        assert_eq!(enc.gates.len(), 1usize);
+        assert_eq!(enc.waves.len(), 2usize);
+
+        assert!(enc.update((500.0, 500.0), 0).is_none());
+        assert!(!enc.locked());
+        assert_eq!(enc.update((50.0, 50.0), 0).unwrap().len(), 2usize);
+        assert!(enc.locked());
+        assert!(enc.update((50.0, 50.0), 2).is_none());
+        assert_eq!(
+            enc.update((50.0, 50.0), 0).unwrap()[0].0,
+            EnemyKind::Skeleton
+        );
+        assert!(enc.update((50.0, 50.0), 0).is_none());
+        assert_eq!(enc.state, EncounterState::Cleared);
+        assert!(!enc.locked());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::entities::items::loot::{LootTable, Pile};
+use ggez::graphics::{Color, DrawMode, Rect};
+use ggez::nalgebra as na;
+use ggez::*;
+use rand::Rng;
+use std::time::Duration;
+
+use super::super::{in_reach, CollideEntity, DrawableEntity, EntityId, REACH};
+
+/// How long a chest lid takes to swing open.
+const OPEN_TIME: Duration = Duration::from_millis(400);
+/// How many times a chest rolls its loot table.
+const CHEST_ROLLS: usize = 3;
+/// Spilled contents land this far apart.
+const SPILL_SPACING: f32 = 48.0;
+
+/// Where contents spilling out of a container land (in a row centred on x).
+fn spill(x: f32, y: f32, contents: Vec<Pile>) -> Vec<(Pile, f32, f32)> {
+    let start = x - (contents.len() as f32 - 1.0) * SPILL_SPACING / 2.0;
+    contents
+        .into_iter()
+        .enumerate()
+        .map(|(i, pile)| (pile, start + i as f32 * SPILL_SPACING, y))
+        .collect()
+}
+
+/// Reads the containers of one kind out of level file lines (the first word names the kind).
+fn parse_lines<'a>(
+    lines: &'a [String],
+    word: &'a str,
+) -> impl Iterator<Item = (f32, f32, Option<&'a str>)> + 'a {
+    lines.iter().filter_map(move |line| {
+        let mut parse = line.split_whitespace();
+        if parse.next()? != word {
+            return None;
+        }
+        let x = parse.next()?.parse::<f32>().ok()?;
+        let y = parse.next()?.parse::<f32>().ok()?;
+        Some((x, y, parse.next()))
+    })
+}
+
+/// The stages of a chest being opened.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Lid {
+    Closed,
+    // time spent opening so far
+    Opening(Duration),
+    Open,
+}
+
+/// A chest that opens with the interact key (locked ones use up a key).
+/// Level file line: `chest <x> <y> [locked]` (a 64x64 tile)
+pub struct Chest {
+    pub x: f32,
+    pub y: f32,
+    pub locked: bool,
+    pub lid: Lid,
+    // what spills out once it is open
+    pub contents: Vec<Pile>,
+}
+
+/// Functions for the Chest struct
+impl Chest {
+    /// News up an empty closed Chest.
+    pub fn new(xpos: f32, ypos: f32, locked: bool) -> Chest {
+        Chest {
+            x: xpos,
+            y: ypos,
+            locked,
+            lid: Lid::Closed,
+            contents: Vec::new(),
+        }
+    }
+
+    /// Reads all chests out of level file lines (lines that aren't chests are ignored).
+    pub fn parse_all(lines: &[String]) -> Vec<Chest> {
+        parse_lines(lines, "chest")
+            .map(|(x, y, lock)| Chest::new(x, y, lock == Some("locked")))
+            .collect()
+    }
+
+    /// Fills the chest from its loot table.
+    pub fn fill<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let table = LootTable::chest();
+        self.contents
+            .extend((0..CHEST_ROLLS).filter_map(|_| table.roll(rng)));
+    }
+
+    /// Returns true if (x, y) is on the chest's tile (things placed there go inside).
+    pub fn holds(&self, x: f32, y: f32) -> bool {
+        Rect::new(self.x, self.y, 64.0, 64.0).contains(na::Point2::new(x, y))
+    }
+
+    /// Returns true if the player is close enough to open the chest.
+    pub fn in_reach(&self, player: Rect) -> bool {
+        in_reach(self.get_hitbox(), REACH, player)
+    }
+
+    /// Starts opening the chest (it has to have been unlocked first).
+    pub fn open(&mut self) {
+        if self.lid == Lid::Closed && !self.locked {
+            self.lid = Lid::Opening(Duration::new(0, 0));
+        }
+    }
+
+    /// Swings the lid open. Returns the contents (and where they land) once it is all the way open.
+    pub fn update(&mut self, delta: Duration) -> Vec<(Pile, f32, f32)> {
+        if let Lid::Opening(time) = self.lid {
+            let time = time + delta;
+            if time < OPEN_TIME {
+                self.lid = Lid::Opening(time);
+            } else {
+                self.lid = Lid::Open;
+                let contents = std::mem::take(&mut self.contents);
+                // out over the lid (chests are usually pushed up against a wall)
+                return spill(self.x + 8.0, self.y - 48.0, contents);
+            }
+        }
+        Vec::new()
+    }
+
+    /// How far open the lid is (0 closed to 1 open).
+    fn progress(&self) -> f32 {
+        match self.lid {
+            Lid::Closed => 0.0,
+            Lid::Opening(time) => time.as_secs_f32() / OPEN_TIME.as_secs_f32(),
+            Lid::Open => 1.0,
+        }
+    }
+}
+
+/// Draw trait for the Chest
+impl DrawableEntity for Chest {
+    /// Draws the Chest (the lid tips back as it opens, showing the dark inside)
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let wood = Color::from_rgb(120u8, 72u8, 32u8);
+        let body = self.get_hitbox();
+        let open = self.progress();
+        let mut mb = graphics::MeshBuilder::new();
+        mb.rectangle(DrawMode::fill(), body, wood);
+        mb.rectangle(DrawMode::stroke(2.0), body, graphics::BLACK);
+        if open > 0.0 {
+            let inside = Rect::new(body.x + 4.0, body.y, body.w - 8.0, 10.0 * open);
+            mb.rectangle(DrawMode::fill(), inside, Color::from_rgb(24u8, 16u8, 8u8));
+        }
+        // the lid gets shorter and higher as it swings back
+        let lid_h = 16.0 - 10.0 * open;
+        let lid = Rect::new(
+            body.x - 2.0,
+            body.y - lid_h - 8.0 * open,
+            body.w + 4.0,
+            lid_h,
+        );
+        mb.rectangle(DrawMode::fill(), lid, Color::from_rgb(150u8, 96u8, 48u8));
+        mb.rectangle(DrawMode::stroke(2.0), lid, graphics::BLACK);
+        if self.locked {
+            let lock = Rect::new(body.x + body.w / 2.0 - 5.0, body.y + 4.0, 10.0, 12.0);
+            mb.rectangle(DrawMode::fill(), lock, Color::from_rgb(255u8, 215u8, 0u8));
+        }
+        let mesh = mb.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+/// Collide trait for the Chest
+impl CollideEntity for Chest {
+    /// The body of the chest (the bottom of its tile)
+    fn get_hitbox(&self) -> Rect {
+        Rect::new(self.x + 8.0, self.y + 24.0, 48.0, 36.0)
+    }
+}
+
+/// The kinds of things that break when hit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BreakableKind {
+    Pot,
+    Crate,
+}
+
+/// A pot or crate that the player can smash open.
+/// Level file lines: `pot <x> <y>` and `crate <x> <y>` (a 64x64 tile)
+pub struct Breakable {
+    pub id: EntityId,
+    pub x: f32,
+    pub y: f32,
+    pub kind: BreakableKind,
+    pub broken: bool,
+    pub contents: Vec<Pile>,
+}
+
+/// Functions for the Breakable struct
+impl Breakable {
+    /// News up an empty Breakable.
+    pub fn new(xpos: f32, ypos: f32, kind: BreakableKind) -> Breakable {
+        Breakable {
+            id: EntityId::fresh(),
+            x: xpos,
+            y: ypos,
+            kind,
+            broken: false,
+            contents: Vec::new(),
+        }
+    }
+
+    /// Reads all pots and crates out of level file lines.
+    pub fn parse_all(lines: &[String]) -> Vec<Breakable> {
+        parse_lines(lines, "pot")
+            .map(|(x, y, _)| Breakable::new(x, y, BreakableKind::Pot))
+            .chain(
+                parse_lines(lines, "crate")
+                    .map(|(x, y, _)| Breakable::new(x, y, BreakableKind::Crate)),
+            )
+            .collect()
+    }
+
+    /// Fills the pot or crate from its loot table.
+    pub fn fill<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.contents.extend(LootTable::pot().roll(rng));
+    }
+
+    /// Breaks it open. Returns the contents (and where they land).
+    pub fn smash(&mut self) -> Vec<(Pile, f32, f32)> {
+        if self.broken {
+            return Vec::new();
+        }
+        self.broken = true;
+        spill(
+            self.x + 8.0,
+            self.y + 8.0,
+            std::mem::take(&mut self.contents),
+        )
+    }
+}
+
+/// Draw trait for the Breakable
+impl DrawableEntity for Breakable {
+    /// Draws the pot or crate (or a few pieces of it once broken)
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let hb = self.get_hitbox();
+        let (fill, edge) = match self.kind {
+            BreakableKind::Pot => (
+                Color::from_rgb(176u8, 96u8, 64u8),
+                Color::from_rgb(96u8, 48u8, 32u8),
+            ),
+            BreakableKind::Crate => (
+                Color::from_rgb(160u8, 120u8, 64u8),
+                Color::from_rgb(88u8, 64u8, 32u8),
+            ),
+        };
+        let mut mb = graphics::MeshBuilder::new();
+        if self.broken {
+            for (dx, dy) in [(4.0, 20.0), (20.0, 28.0), (30.0, 10.0), (12.0, 4.0)].iter() {
+                mb.rectangle(
+                    DrawMode::fill(),
+                    Rect::new(hb.x + dx, hb.y + dy, 8.0, 6.0),
+                    edge,
+                );
+            }
+        } else {
+            match self.kind {
+                BreakableKind::Pot => {
+                    let center = na::Point2::new(hb.x + hb.w / 2.0, hb.y + hb.h / 2.0);
+                    mb.circle(DrawMode::fill(), center, hb.w / 2.0, 0.5, fill);
+                    mb.circle(DrawMode::stroke(2.0), center, hb.w / 2.0, 0.5, edge);
+                }
+                BreakableKind::Crate => {
+                    mb.rectangle(DrawMode::fill(), hb, fill);
+                    mb.rectangle(DrawMode::stroke(3.0), hb, edge);
+                    mb.line(
+                        &[
+                            na::Point2::new(hb.x, hb.y),
+                            na::Point2::new(hb.x + hb.w, hb.y + hb.h),
+                        ],
+                        3.0,
+                        edge,
+                    )?;
+                }
+            }
+        }
+        let mesh = mb.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+/// Collide trait for the Breakable
+impl CollideEntity for Breakable {
+    /// The middle of the tile
+    fn get_hitbox(&self) -> Rect {
+        Rect::new(self.x + 12.0, self.y + 12.0, 40.0, 40.0)
+    }
+}
+
+#[cfg(test)]
+mod testcontainer {
+    use super::*;
+    use crate::entities::items::inventory::Item;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_and_open_chest() {
+        let lines = vec![
+            "chest 128 128 locked".to_string(),
+            "chest 256 128".to_string(),
+            "pot 64 64".to_string(),
+            "crate 0 64".to_string(),
+        ];
+        let mut chests = Chest::parse_all(&lines);
+        assert_eq!(chests.len(), 2);
+        assert!(chests[0].locked && !chests[1].locked);
+        assert_eq!(Breakable::parse_all(&lines).len(), 2);
+
+        // locked chests stay shut
+        chests[0].open();
+        assert_eq!(chests[0].lid, Lid::Closed);
+
+        let chest = &mut chests[1];
+        assert!(chest.holds(300.0, 150.0) && !chest.holds(100.0, 150.0));
+        chest.contents.push(Pile::one(Item::MacGuffin));
+        chest.fill(&mut StdRng::seed_from_u64(4));
+        let count = chest.contents.len();
+        chest.open();
+        assert!(chest.update(OPEN_TIME / 2).is_empty());
+        let spilled = chest.update(OPEN_TIME / 2);
+        assert_eq!(spilled.len(), count);
+        assert_eq!(spilled[0].0, Pile::one(Item::MacGuffin));
+        // stays open (and empty) for good
+        chest.open();
+        assert_eq!(chest.lid, Lid::Open);
+        assert!(chest.update(OPEN_TIME).is_empty());
+    }
+
+    #[test]
+    fn test_smash_once() {
+        let mut pot = Breakable::new(0.0, 0.0, BreakableKind::Pot);
+        pot.contents.push(Pile::gold(2));
+        assert_eq!(pot.smash().len(), 1);
+        assert!(pot.broken);
+        assert!(pot.smash().is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::entities::items::loot::KeyColour;
+use ggez::graphics::{Color, DrawMode, Rect};
+use ggez::nalgebra as na;
+use ggez::*;
+
+use super::super::{in_reach, CollideEntity, DrawableEntity, REACH};
+
+/// Size of a door (one level tile).
+const SIZE: f32 = 64.0;
+
+/// A locked door filling one tile. It blocks like a wall until the player
+/// interacts with it while holding a key of the same colour.
+/// Level file line: `door <colour> <x> <y> [keep]` (keep means the key isn't used up)
+/// Doors of the same colour on neighbouring tiles open together, so wide doorways
+/// only need one key.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Door {
+    pub x: f32,
+    pub y: f32,
+    pub colour: KeyColour,
+    // the key is used up when the door opens
+    pub consume: bool,
+    pub open: bool,
+}
+
+/// Functions for the Door struct
+impl Door {
+    /// News up a closed Door.
+    pub fn new(xpos: f32, ypos: f32, colour: KeyColour, consume: bool) -> Door {
+        Door {
+            x: xpos,
+            y: ypos,
+            colour,
+            consume,
+            open: false,
+        }
+    }
+
+    /// Reads all doors out of level file lines (lines that aren't doors are ignored).
+    pub fn parse_all(lines: &[String]) -> Vec<Door> {
+        lines
+            .iter()
+            .filter_map(|line| {
+                let mut parse = line.split_whitespace();
+                if parse.next()? != "door" {
+                    return None;
+                }
+                let colour = KeyColour::from_name(parse.next()?)?;
+                let x = parse.next()?.parse::<f32>().ok()?;
+                let y = parse.next()?.parse::<f32>().ok()?;
+                Some(Door::new(x, y, colour, parse.next() != Some("keep")))
+            })
+            .collect()
+    }
+
+    /// Returns true if the player is close enough to unlock the door.
+    pub fn in_reach(&self, player: Rect) -> bool {
+        in_reach(Rect::new(self.x, self.y, SIZE, SIZE), REACH, player)
+    }
+
+    /// Returns true if the other door is on a tile next to this one (not diagonally).
+    fn touches(&self, other: &Door) -> bool {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        (dx == SIZE && dy == 0.0) || (dx == 0.0 && dy == SIZE)
+    }
+}
+
+/// Opens a door along with every closed door of the same colour joined to it.
+pub fn open_joined(doors: &mut [Door], index: usize) {
+    let mut opening = vec![index];
+    while let Some(i) = opening.pop() {
+        if doors[i].open {
+            continue;
+        }
+        doors[i].open = true;
+        let door = doors[i].clone();
+        opening.extend(
+            doors
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| !d.open && d.colour == door.colour && d.touches(&door))
+                .map(|(j, _)| j),
+        );
+    }
+}
+
+/// Draw trait for the Door
+impl DrawableEntity for Door {
+    /// Draws the Door (just the frame once it is open)
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let colour = self.colour.color();
+        let mut mb = graphics::MeshBuilder::new();
+        if self.open {
+            for x in &[self.x, self.x + SIZE - 8.0] {
+                mb.rectangle(
+                    DrawMode::fill(),
+                    Rect::new(*x, self.y, 8.0, SIZE),
+                    Color::new(colour.r * 0.5, colour.g * 0.5, colour.b * 0.5, 1.0),
+                );
+            }
+        } else {
+            let tile = self.get_hitbox();
+            mb.rectangle(DrawMode::fill(), tile, colour);
+            mb.rectangle(DrawMode::stroke(4.0), tile, graphics::BLACK);
+            // keyhole
+            let centre = na::Point2::new(self.x + SIZE / 2.0, self.y + SIZE / 2.0 - 4.0);
+            mb.circle(DrawMode::fill(), centre, 6.0, 0.5, graphics::BLACK);
+            mb.rectangle(
+                DrawMode::fill(),
+                Rect::new(centre.x - 3.0, centre.y, 6.0, 14.0),
+                graphics::BLACK,
+            );
+        }
+        let mesh = mb.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+/// Collide trait for the Door
+impl CollideEntity for Door {
+    /// The whole tile
+    fn get_hitbox(&self) -> Rect {
+        Rect::new(self.x, self.y, SIZE, SIZE)
+    }
+}
+
+#[cfg(test)]
+mod testdoor {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_open_joined() {
+        let lines = vec![
+            "door red 960 -64".to_string(),
+            "door red 1024 -64".to_string(),
+            "door red 1088 -64 keep".to_string(),
+            "door blue 1152 -64".to_string(),
+            "door red 0 0".to_string(),
+            "door green 64 64".to_string(),
+            "chest 576 128".to_string(),
+        ];
+        let mut doors = Door::parse_all(&lines);
+        assert_eq!(doors.len(), 5);
+        assert!(doors[0].consume && !doors[2].consume);
+        assert_eq!(doors[3].colour, KeyColour::Blue);
+        assert!(doors[1].in_reach(Rect::new(1000.0, 0.0, 32.0, 32.0)));
+        assert!(!doors[1].in_reach(Rect::new(1000.0, 32.0, 32.0, 32.0)));
+
+        // the whole red doorway opens, but not the blue door next to it or the red door far away
+        open_joined(&mut doors, 2);
+        let open: Vec<bool> = doors.iter().map(|d| d.open).collect();
+        assert_eq!(open, vec![true, true, true, false, false]);
+    }
+}
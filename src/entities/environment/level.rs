@@ -1,17 +1,42 @@
 use super::super::{CollideEntity, DrawableEntity};
+use super::door::Door;
 use super::tile::Tile;
-use ggez::graphics::Rect;
+use ggez::graphics::{self, Rect};
 use ggez::{Context, GameResult};
 
 pub struct Level {
     tiles: Vec<Tile>,
+    // lines from the level file that describe things other than tiles (ie spawners)
+    pub extras: Vec<String>,
+    // temporary walls (ie gates of a locked encounter room)
+    barriers: Vec<Rect>,
+    // solid things standing in the level that draw themselves (ie chests and crates)
+    props: Vec<Rect>,
+    // locked doors (block like walls until they are opened)
+    pub doors: Vec<Door>,
 }
 
 impl Level {
     // should a new() really be provided?
     // instead for level to come from level_builder
     pub fn new(tiles: Vec<Tile>) -> Level {
-        Level { tiles }
+        Level {
+            tiles,
+            extras: Vec::new(),
+            barriers: Vec::new(),
+            props: Vec::new(),
+            doors: Vec::new(),
+        }
+    }
+
+    // replaces the temporary walls blocking the level
+    pub fn set_barriers(&mut self, barriers: Vec<Rect>) {
+        self.barriers = barriers;
+    }
+
+    // replaces the solid things standing in the level
+    pub fn set_props(&mut self, props: Vec<Rect>) {
+        self.props = props;
     }
 }
 
@@ -20,6 +45,22 @@ impl DrawableEntity for Level {
         for w in &self.tiles {
             w.draw(ctx)?;
         }
+        for d in &self.doors {
+            d.draw(ctx)?;
+        }
+        if !self.barriers.is_empty() {
+            let mut mb = graphics::MeshBuilder::new();
+            for b in &self.barriers {
+                mb.rectangle(
+                    graphics::DrawMode::fill(),
+                    *b,
+                    graphics::Color::from_rgb(96u8, 16u8, 16u8),
+                );
+                mb.rectangle(graphics::DrawMode::stroke(4.0), *b, graphics::BLACK);
+            }
+            let mesh = mb.build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
         Ok(())
     }
 }
@@ -34,6 +75,17 @@ impl CollideEntity for Level {
     }
 
     fn get_sub_hitboxs(&self) -> Vec<Rect> {
-        self.tiles.iter().map(|w| w.get_hitbox()).collect()
+        self.tiles
+            .iter()
+            .map(|w| w.get_hitbox())
+            .chain(self.barriers.iter().cloned())
+            .chain(self.props.iter().cloned())
+            .chain(
+                self.doors
+                    .iter()
+                    .filter(|d| !d.open)
+                    .map(|d| d.get_hitbox()),
+            )
+            .collect()
     }
 }
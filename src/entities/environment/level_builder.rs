@@ -4,6 +4,7 @@ use std::io::{BufRead, BufReader};
 use ggez::graphics::{Image, Rect, WHITE};
 use ggez::{error::GameError, Context, GameResult};
 
+use super::door::Door;
 use super::level::Level;
 use super::tile::Tile;
 use crate::sprites::Sprite;
@@ -142,29 +143,43 @@ impl LevelBuilder {
         self.generate_level(w)
     }
 
+    // lines are either tiles "x y tile" or start with a word naming something
+    // else placed in the level (ie "spawner ..."), those are kept in Level.extras
     pub fn fromfile(&mut self, ctx: &mut Context, sheet: &Image, path: &str) -> Level {
         let mut w = Vec::new();
+        let mut extras = Vec::new();
         self.tileize(sheet);
 
-        if !ggez::filesystem::exists(ctx, &path) {
+        if !ggez::filesystem::exists(ctx, path) {
             println!("unable to find {} loading empty level", path);
             return self.generate_level(w);
         }
 
-        let file = ggez::filesystem::open(ctx, &path).unwrap();
+        let file = ggez::filesystem::open(ctx, path).unwrap();
         let reader = BufReader::new(file);
         for (i, line) in reader.lines().enumerate() {
             let line = line.unwrap();
             let mut parse = line.split_whitespace();
+            let first = match parse.next() {
+                Some(word) => word,
+                None => continue,
+            };
+            if first.parse::<i64>().is_err() {
+                extras.push(line.clone());
+                continue;
+            }
             let message = format!("Error on line {}, unable to parse valid value", i + 1);
-            let x = parse.next().unwrap().parse::<i64>().expect(&message) as f32;
+            let x = first.parse::<i64>().expect(&message) as f32;
             let y = parse.next().unwrap().parse::<i64>().expect(&message) as f32;
             let t = parse.next().unwrap().parse::<usize>().expect(&message);
             //println!("{}, ({},{}), {}", i, x, y, t);
             w.push(((x, y), t));
         }
 
-        self.generate_level(w)
+        let mut level = self.generate_level(w);
+        level.doors = Door::parse_all(&extras);
+        level.extras = extras;
+        level
     }
 
     pub fn generate_level(&self, points: Vec<((f32, f32), usize)>) -> Level {
@@ -175,7 +190,7 @@ impl LevelBuilder {
                     Some(image) => image,
                     None => &self.default,
                 };
-                Tile::new(&image, (p.0).0, (p.0).1)
+                Tile::new(image, (p.0).0, (p.0).1)
             })
             .collect();
         Level::new(tiles)
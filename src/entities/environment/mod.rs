@@ -11,3 +11,12 @@ pub mod level_builder;
 
 // Namespace of basic tile that is used to form a level
 pub mod tile;
+
+// Namespace of floor traps that inflict status effects
+pub mod trap;
+
+// Namespace of chests and breakable pots and crates
+pub mod container;
+
+// Namespace of locked doors opened with coloured keys
+pub mod door;
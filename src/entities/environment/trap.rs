@@ -0,0 +1,99 @@
+use crate::entities::status::StatusKind;
+use ggez::graphics::{Color, Rect};
+use ggez::*;
+use std::time::Duration;
+
+use super::super::{CollideEntity, DrawableEntity};
+
+/// How long a trap takes to reset after going off.
+const REARM: Duration = Duration::from_millis(2000);
+
+/// A floor trap that inflicts a status effect on whoever steps on it.
+/// Level file line: `trap <poison|slow|stun|burn> <x> <y>` (a 64x64 tile)
+pub struct Trap {
+    pub x: f32,
+    pub y: f32,
+    pub kind: StatusKind,
+    timer: Duration,
+}
+
+/// Functions for the Trap struct
+impl Trap {
+    /// News up an armed Trap.
+    pub fn new(xpos: f32, ypos: f32, kind: StatusKind) -> Trap {
+        Trap {
+            x: xpos,
+            y: ypos,
+            kind,
+            timer: REARM,
+        }
+    }
+
+    /// Reads all traps out of level file lines (lines that aren't traps are ignored).
+    pub fn parse_all(lines: &[String]) -> Vec<Trap> {
+        lines
+            .iter()
+            .filter_map(|line| {
+                let mut parse = line.split_whitespace();
+                if parse.next()? != "trap" {
+                    return None;
+                }
+                let kind = StatusKind::from_name(parse.next()?)?;
+                let x = parse.next()?.parse::<f32>().ok()?;
+                let y = parse.next()?.parse::<f32>().ok()?;
+                Some(Trap::new(x, y, kind))
+            })
+            .collect()
+    }
+
+    /// Counts down to the trap being armed again.
+    pub fn update(&mut self, delta: Duration) {
+        if !self.armed() {
+            self.timer += delta;
+        }
+    }
+
+    /// Returns true if the trap will go off when stepped on.
+    pub fn armed(&self) -> bool {
+        self.timer >= REARM
+    }
+
+    /// Sets the trap off. Returns the effect to apply if it was armed.
+    pub fn spring(&mut self) -> Option<StatusKind> {
+        if !self.armed() {
+            return None;
+        }
+        self.timer = Duration::new(0, 0);
+        Some(self.kind)
+    }
+}
+
+/// Draw trait for the Trap
+impl DrawableEntity for Trap {
+    /// Draws the Trap (faded out while it resets)
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let tint = self.kind.tint();
+        let alpha = if self.armed() { 0.6 } else { 0.2 };
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                self.get_hitbox(),
+                Color::new(tint.r, tint.g, tint.b, alpha),
+            )
+            .rectangle(
+                graphics::DrawMode::stroke(2.0),
+                self.get_hitbox(),
+                Color::new(tint.r, tint.g, tint.b, 1.0),
+            )
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+}
+
+/// Collide trait for the Trap
+impl CollideEntity for Trap {
+    /// The pressure plate in the middle of the tile
+    fn get_hitbox(&self) -> Rect {
+        Rect::new(self.x + 8.0, self.y + 8.0, 48.0, 48.0)
+    }
+}
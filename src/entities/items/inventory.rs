@@ -0,0 +1,317 @@
+use super::loot::{Gear, KeyColour};
+use super::registry::{self, Pickup};
+
+/// How many slots the inventory has.
+pub const SLOTS: usize = 16;
+/// The first few slots are the hotbar (usable without opening the inventory).
+pub const HOTBAR: usize = 3;
+
+/// Every type of item (see the registry for what each one looks like and does).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Item {
+    Potion,
+    ManaPotion,
+    SpeedElixir,
+    Bomb,
+    TeleportScroll,
+    Gold,
+    Key,
+    // opens doors of its colour (plain keys are for chests)
+    ColouredKey(KeyColour),
+    Equipment(Gear),
+    MacGuffin,
+    Decoy,
+}
+
+/// Functions for the Item enum
+impl Item {
+    /// Name shown in the inventory.
+    pub fn name(self) -> String {
+        registry::lookup(self).name.to_string()
+    }
+
+    /// How many fit in one slot.
+    pub fn max_stack(self) -> u32 {
+        registry::lookup(self).max_stack
+    }
+
+    /// Quest items can't be dropped or used up.
+    pub fn quest(self) -> bool {
+        registry::lookup(self).pickup == Pickup::Quest
+    }
+}
+
+/// A stack of the same item in one inventory slot.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Slot {
+    pub item: Item,
+    pub count: u32,
+}
+
+/// Things the player carries around.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Inventory {
+    slots: Vec<Option<Slot>>,
+    // coins don't take up a slot
+    gold: u32,
+    // all the gold picked up or given this run (spending it doesn't take it back off)
+    found: u32,
+    // items used or dropped from the inventory screen, waiting for the game to deal with them
+    // (with the slot used ones came from, None for dropped ones)
+    pending: Vec<(Item, Option<usize>)>,
+}
+
+/// Functions for the Inventory struct
+impl Inventory {
+    /// News up an empty Inventory.
+    pub fn new() -> Inventory {
+        Inventory {
+            slots: vec![None; SLOTS],
+            gold: 0,
+            found: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// What is in a slot.
+    pub fn slot(&self, index: usize) -> Option<Slot> {
+        self.slots.get(index).cloned().flatten()
+    }
+
+    /// Adds an item, topping up existing stacks before using an empty slot.
+    /// Returns false if there is no room.
+    pub fn add(&mut self, item: Item) -> bool {
+        let stack = self
+            .slots
+            .iter_mut()
+            .flatten()
+            .find(|s| s.item == item && s.count < item.max_stack());
+        if let Some(s) = stack {
+            s.count += 1;
+            return true;
+        }
+        match self.slots.iter_mut().find(|s| s.is_none()) {
+            Some(empty) => {
+                *empty = Some(Slot { item, count: 1 });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds an item, or if there is no room drops it at the player's feet (see `take_pending`).
+    pub fn give(&mut self, item: Item) {
+        if !self.add(item) {
+            self.pending.push((item, None));
+        }
+    }
+
+    /// How many of an item are carried (across all slots).
+    pub fn count(&self, item: Item) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|s| s.item == item)
+            .map(|s| s.count)
+            .sum()
+    }
+
+    /// Returns true if at least one of an item is carried.
+    pub fn has(&self, item: Item) -> bool {
+        self.count(item) > 0
+    }
+
+    /// Takes one item out of a slot.
+    pub fn take(&mut self, index: usize) -> Option<Item> {
+        let slot = self.slots.get_mut(index)?;
+        let s = slot.as_mut()?;
+        let item = s.item;
+        s.count -= 1;
+        if s.count == 0 {
+            *slot = None;
+        }
+        Some(item)
+    }
+
+    /// Takes one of an item out of whichever slot has it.
+    pub fn remove(&mut self, item: Item) -> bool {
+        match self
+            .slots
+            .iter()
+            .position(|s| s.is_some_and(|s| s.item == item))
+        {
+            Some(index) => self.take(index).is_some(),
+            None => false,
+        }
+    }
+
+    /// How much gold is carried.
+    pub fn gold(&self) -> u32 {
+        self.gold
+    }
+
+    /// How much gold has been found this run (whether or not it has been spent).
+    pub fn gold_found(&self) -> u32 {
+        self.found
+    }
+
+    /// Puts some gold in the purse (ie paid by a merchant).
+    pub fn add_gold(&mut self, amount: u32) {
+        self.gold += amount;
+    }
+
+    /// Puts gold that was found (picked up or given) in the purse and counts it towards the run.
+    pub fn find_gold(&mut self, amount: u32) {
+        self.add_gold(amount);
+        self.found += amount;
+    }
+
+    /// Pays out some gold. Returns false (and pays nothing) if there isn't enough.
+    pub fn spend_gold(&mut self, amount: u32) -> bool {
+        if self.gold < amount {
+            return false;
+        }
+        self.gold -= amount;
+        true
+    }
+
+    /// Swaps two slots (ie putting something on the hotbar).
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a < SLOTS && b < SLOTS {
+            self.slots.swap(a, b);
+        }
+    }
+
+    /// Takes one item out of a slot to be used by the game (see `take_pending`).
+    pub fn use_slot(&mut self, index: usize) {
+        if let Some(item) = self.slot(index).map(|s| s.item) {
+            if !item.quest() {
+                self.take(index);
+                self.pending.push((item, Some(index)));
+            }
+        }
+    }
+
+    /// Takes one item out of a slot to be dropped on the ground by the game (quest items stay put).
+    pub fn drop_slot(&mut self, index: usize) {
+        if let Some(item) = self.slot(index).map(|s| s.item) {
+            if !item.quest() {
+                self.take(index);
+                self.pending.push((item, None));
+            }
+        }
+    }
+
+    /// Items used or dropped since last time, with the slot used ones came from (None for dropped ones).
+    pub fn take_pending(&mut self) -> Vec<(Item, Option<usize>)> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Puts an item that couldn't be used back in the slot it came from
+    /// (or wherever it fits if that slot has been filled since).
+    pub fn put_back(&mut self, index: usize, item: Item) {
+        match self.slots.get_mut(index) {
+            Some(slot @ None) => *slot = Some(Slot { item, count: 1 }),
+            Some(Some(s)) if s.item == item && s.count < item.max_stack() => s.count += 1,
+            _ => self.give(item),
+        }
+    }
+}
+
+impl Default for Inventory {
+    fn default() -> Inventory {
+        Inventory::new()
+    }
+}
+
+#[cfg(test)]
+mod testinventory {
+    use super::*;
+
+    #[test]
+    fn test_stacking_and_full() {
+        let mut inv = Inventory::new();
+        for _ in 0..7 {
+            assert!(inv.add(Item::Potion));
+        }
+        // five to a stack
+        assert_eq!(
+            inv.slot(0),
+            Some(Slot {
+                item: Item::Potion,
+                count: 5
+            })
+        );
+        assert_eq!(
+            inv.slot(1),
+            Some(Slot {
+                item: Item::Potion,
+                count: 2
+            })
+        );
+        assert_eq!(inv.count(Item::Potion), 7);
+
+        for _ in 2..SLOTS {
+            assert!(inv.add(Item::Equipment(Gear::Ring)));
+        }
+        assert!(!inv.add(Item::Key));
+        // given things that don't fit get dropped
+        inv.give(Item::Key);
+        assert_eq!(inv.take_pending(), vec![(Item::Key, None)]);
+        // still room on the second potion stack
+        assert!(inv.add(Item::Potion));
+    }
+
+    #[test]
+    fn test_use_drop_and_quest_items() {
+        let mut inv = Inventory::new();
+        inv.add(Item::MacGuffin);
+        inv.add(Item::Potion);
+        inv.add(Item::Potion);
+
+        inv.use_slot(0);
+        inv.drop_slot(0);
+        assert!(inv.has(Item::MacGuffin));
+
+        inv.use_slot(1);
+        inv.drop_slot(1);
+        assert_eq!(inv.slot(1), None);
+        assert_eq!(
+            inv.take_pending(),
+            vec![(Item::Potion, Some(1)), (Item::Potion, None)]
+        );
+        assert!(inv.take_pending().is_empty());
+
+        // things that couldn't be used go back where they were, even with an empty slot before it
+        inv.add(Item::Potion);
+        inv.add(Item::Key);
+        inv.take(1);
+        inv.use_slot(2);
+        assert_eq!(inv.take_pending(), vec![(Item::Key, Some(2))]);
+        inv.put_back(2, Item::Key);
+        assert_eq!(inv.slot(1), None);
+        assert_eq!(inv.take(2), Some(Item::Key));
+
+        inv.add(Item::Key);
+        inv.swap(0, 1);
+        assert_eq!(inv.slot(0).map(|s| s.item), Some(Item::Key));
+        assert!(inv.remove(Item::Key));
+        assert!(!inv.has(Item::Key));
+    }
+
+    #[test]
+    fn test_gold() {
+        let mut inv = Inventory::new();
+        inv.add_gold(10);
+        assert!(!inv.spend_gold(11));
+        assert!(inv.spend_gold(4));
+        assert_eq!(inv.gold(), 6);
+        // only found gold counts towards the run, and spending it doesn't take it back off
+        assert_eq!(inv.gold_found(), 0);
+        inv.find_gold(5);
+        assert!(inv.spend_gold(11));
+        assert_eq!(inv.gold_found(), 5);
+        // coins never use a slot
+        assert_eq!(inv.slot(0), None);
+    }
+}
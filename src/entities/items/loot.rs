@@ -0,0 +1,325 @@
+use crate::sprites::*;
+use ggez::graphics::Image;
+use ggez::nalgebra as na;
+use ggez::*;
+use rand::prelude::*;
+use std::collections::HashMap;
+
+use super::super::{Animations, CollideEntity, Direction, DrawableEntity};
+use super::inventory::Item;
+use super::registry;
+
+/// Pieces of gear that can be found (they all live on items.png)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Gear {
+    Dagger,
+    Spear,
+    Hammer,
+    Armour,
+    Helmet,
+    Ring,
+}
+
+/// Colours of door keys (a key only opens doors of the same colour).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyColour {
+    Red,
+    Blue,
+    Gold,
+}
+
+/// Functions for the KeyColour enum
+impl KeyColour {
+    /// Every colour (in the order the level builder cycles through them).
+    pub const ALL: [KeyColour; 3] = [KeyColour::Red, KeyColour::Blue, KeyColour::Gold];
+
+    /// Looks up a KeyColour from the name used in level files.
+    pub fn from_name(name: &str) -> Option<KeyColour> {
+        match name {
+            "red" => Some(KeyColour::Red),
+            "blue" => Some(KeyColour::Blue),
+            "gold" => Some(KeyColour::Gold),
+            _ => None,
+        }
+    }
+
+    /// Name used in level files.
+    pub fn level_name(self) -> &'static str {
+        match self {
+            KeyColour::Red => "red",
+            KeyColour::Blue => "blue",
+            KeyColour::Gold => "gold",
+        }
+    }
+
+    /// What the key (and its doors) are tinted.
+    pub fn color(self) -> graphics::Color {
+        match self {
+            KeyColour::Red => graphics::Color::from_rgb(220, 40, 40),
+            KeyColour::Blue => graphics::Color::from_rgb(50, 90, 230),
+            KeyColour::Gold => graphics::Color::from_rgb(240, 200, 40),
+        }
+    }
+}
+
+/// Some number of one item, as dropped by enemies or lying on the ground.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pile {
+    pub item: Item,
+    pub count: u32,
+}
+
+/// Functions for the Pile struct
+impl Pile {
+    /// A single item.
+    pub fn one(item: Item) -> Pile {
+        Pile { item, count: 1 }
+    }
+
+    /// Some gold coins.
+    pub fn gold(amount: u32) -> Pile {
+        Pile {
+            item: Item::Gold,
+            count: amount,
+        }
+    }
+
+    /// Text shown when the player picks this up.
+    pub fn describe(self) -> String {
+        if self.count > 1 {
+            format!("{} {}", self.count, self.item.name())
+        } else {
+            self.item.name()
+        }
+    }
+}
+
+/// A weighted list of what might drop. `None` entries are the chance of dropping nothing.
+pub struct LootTable {
+    entries: Vec<(u32, Option<Pile>)>,
+}
+
+/// Functions for the LootTable struct
+impl LootTable {
+    /// News up a LootTable from (weight, drop) pairs.
+    pub fn new(entries: Vec<(u32, Option<Pile>)>) -> LootTable {
+        LootTable { entries }
+    }
+
+    /// A table that never drops anything.
+    pub fn empty() -> LootTable {
+        LootTable::new(Vec::new())
+    }
+
+    /// Loot table for blobs (mostly a bit of gold)
+    pub fn blob() -> LootTable {
+        LootTable::new(vec![
+            (50, None),
+            (15, Some(Pile::one(Item::Potion))),
+            (30, Some(Pile::gold(3))),
+            (5, Some(Pile::one(Item::Key))),
+        ])
+    }
+
+    /// Loot table for skeletons (they carry old gear around)
+    pub fn skeleton() -> LootTable {
+        LootTable::new(vec![
+            (35, None),
+            (15, Some(Pile::one(Item::Potion))),
+            (30, Some(Pile::gold(8))),
+            (10, Some(Pile::one(Item::Key))),
+            (5, Some(Pile::one(Item::Equipment(Gear::Dagger)))),
+            (5, Some(Pile::one(Item::Equipment(Gear::Helmet)))),
+        ])
+    }
+
+    /// Loot table for ghosts
+    pub fn ghost() -> LootTable {
+        LootTable::new(vec![
+            (40, None),
+            (20, Some(Pile::one(Item::Potion))),
+            (30, Some(Pile::gold(5))),
+            (10, Some(Pile::one(Item::Equipment(Gear::Ring)))),
+        ])
+    }
+
+    /// Loot table for the boss (always drops something good)
+    pub fn boss() -> LootTable {
+        LootTable::new(vec![
+            (60, Some(Pile::gold(50))),
+            (20, Some(Pile::one(Item::Equipment(Gear::Spear)))),
+            (20, Some(Pile::one(Item::Equipment(Gear::Armour)))),
+        ])
+    }
+
+    /// Loot table for chests (rolled a few times, so usually more than one thing)
+    pub fn chest() -> LootTable {
+        LootTable::new(vec![
+            (20, None),
+            (25, Some(Pile::gold(20))),
+            (15, Some(Pile::one(Item::Potion))),
+            (10, Some(Pile::one(Item::ManaPotion))),
+            (8, Some(Pile::one(Item::SpeedElixir))),
+            (8, Some(Pile::one(Item::Bomb))),
+            (6, Some(Pile::one(Item::TeleportScroll))),
+            (4, Some(Pile::one(Item::Equipment(Gear::Hammer)))),
+            (4, Some(Pile::one(Item::Equipment(Gear::Armour)))),
+        ])
+    }
+
+    /// Loot table for pots and crates (usually empty)
+    pub fn pot() -> LootTable {
+        LootTable::new(vec![
+            (60, None),
+            (30, Some(Pile::gold(2))),
+            (10, Some(Pile::one(Item::Potion))),
+        ])
+    }
+
+    /// Picks what drops (if anything) using rng.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Pile> {
+        let total: u32 = self.entries.iter().map(|e| e.0).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0, total);
+        for (weight, kind) in &self.entries {
+            if pick < *weight {
+                return *kind;
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
+
+/// Loot lying on the ground waiting to be picked up.
+pub struct Loot {
+    pub x: f32,
+    pub y: f32,
+    pub pile: Pile,
+    pub used: bool,
+    pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
+    pub animation: (Animations, Direction),
+    pub direction: Direction,
+}
+
+/// Functions for the Loot struct
+impl Loot {
+    /// News up a piece of Loot (it looks like whatever the registry says the item looks like)
+    pub fn new(ctx: &mut Context, xpos: f32, ypos: f32, pile: Pile) -> Loot {
+        let mut sprite = HashMap::new();
+        let sheet = Image::new(ctx, "/items.png").unwrap();
+        let builder = AnimatedBuilder::new(&sheet);
+
+        sprite.insert(
+            (Animations::Stand, Direction::Down),
+            builder
+                .create_animated(registry::lookup(pile.item).sprite_clip(), 1usize)
+                .unwrap(),
+        );
+
+        Loot {
+            x: xpos,
+            y: ypos,
+            pile,
+            used: false,
+            sprite,
+            animation: (Animations::Stand, Direction::Down),
+            direction: Direction::Down,
+        }
+    }
+
+    /// Finds the items placed in a level file (lines like "item potion 1800 350" or "item gold 500 100 25").
+    pub fn parse_all(lines: &[String]) -> Vec<(Pile, f32, f32)> {
+        lines
+            .iter()
+            .filter_map(|line| {
+                let mut parse = line.split_whitespace();
+                if parse.next()? != "item" {
+                    return None;
+                }
+                let item = registry::from_name(parse.next()?)?;
+                let x = parse.next()?.parse::<f32>().ok()?;
+                let y = parse.next()?.parse::<f32>().ok()?;
+                let count = parse
+                    .next()
+                    .and_then(|c| c.parse::<u32>().ok())
+                    .unwrap_or(1);
+                Some((Pile { item, count }, x, y))
+            })
+            .collect()
+    }
+}
+
+/// The draw trait for the Loot
+impl DrawableEntity for Loot {
+    /// Draws the Loot
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let dp = graphics::DrawParam::default()
+            .dest(na::Point2::new(self.x, self.y))
+            .color(registry::lookup(self.pile.item).tint);
+        graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
+
+        Ok(())
+    }
+}
+
+/// Collide trait for the Loot
+impl CollideEntity for Loot {
+    /// Finds where the hitbox is for the Loot
+    fn get_hitbox(&self) -> graphics::Rect {
+        let mut r = self
+            .sprite
+            .get(&self.animation)
+            .unwrap()
+            .dimensions()
+            .unwrap();
+        r.x = self.x;
+        r.y = self.y;
+        r
+    }
+}
+
+#[cfg(test)]
+mod testloot {
+    use super::*;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_empty_table_drops_nothing() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(LootTable::empty().roll(&mut rng), None);
+    }
+
+    #[test]
+    fn test_roll_follows_weights() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let table = LootTable::new(vec![(0, None), (1, Some(Pile::one(Item::Key)))]);
+        for _ in 0..20 {
+            assert_eq!(table.roll(&mut rng), Some(Pile::one(Item::Key)));
+        }
+
+        let boss = LootTable::boss();
+        for _ in 0..100 {
+            assert!(boss.roll(&mut rng).is_some());
+        }
+    }
+
+    #[test]
+    fn test_parse_placed_items() {
+        let lines = vec![
+            "item bomb 320 448".to_string(),
+            "item gold 500 100 25".to_string(),
+            "item nothing 1 1".to_string(),
+            "trap poison 320 448".to_string(),
+        ];
+        assert_eq!(
+            Loot::parse_all(&lines),
+            vec![
+                (Pile::one(Item::Bomb), 320.0, 448.0),
+                (Pile::gold(25), 500.0, 100.0)
+            ]
+        );
+    }
+}
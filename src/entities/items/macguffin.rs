@@ -1,76 +1,116 @@
-use crate::sprites::*;
-use ggez::graphics::{Image, Rect};
-use ggez::nalgebra as na;
-use ggez::*;
+use super::super::enemies::spawner::EnemyKind;
 use rand::prelude::*;
-use std::collections::HashMap;
 
-use super::super::{Animations, CollideEntity, Direction, DrawableEntity};
-
-/// Struct for the Macguffin
-pub struct Macguffin {
-    pub x: f32,
-    pub y: f32,
-    pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
-    pub animation: (Animations, Direction),
-    pub direction: Direction,
+/// Where the MacGuffin could be hidden in a level, and what goes in the spots it isn't in.
+/// Read from level file lines like "macguffin 1050 -650" (one per candidate spot)
+/// and "decoys 2 skeleton 3" (two decoys, each hiding three skeletons).
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Hiding {
+    pub spots: Vec<(f32, f32)>,
+    pub decoys: usize,
+    // what jumps out when a decoy is picked up (and how many of them)
+    pub ambush: Option<(EnemyKind, usize)>,
 }
 
-/// Functions implemented for the Macguffin struct
-impl Macguffin {
-    /// News up a new Macguffin. The sprite will be a random locaiton on the items spritesheet
-    pub fn new(ctx: &mut Context, xpos: f32, ypos: f32) -> Macguffin {
-        let mut sprite = HashMap::new();
-        let sheet = Image::new(ctx, "/items.png").unwrap();
-        let builder = AnimatedBuilder::new(&sheet);
-
-        let mut rng = thread_rng();
-        let random_x_item = rng.gen_range(0, 5) as f32 * 64.0;
-        let random_y_item = rng.gen_range(0, 4) as f32 * 64.0;
-
-        sprite.insert(
-            (Animations::Stand, Direction::Down),
-            builder
-                .create_animated(
-                    Rect::new(random_x_item, random_y_item, 64f32, 64f32),
-                    1usize,
-                )
-                .unwrap(),
-        );
+/// Where the MacGuffin ended up for a run.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Placement {
+    pub real: (f32, f32),
+    pub decoys: Vec<(f32, f32)>,
+}
 
-        Macguffin {
-            x: xpos,
-            y: ypos,
-            sprite,
-            animation: (Animations::Stand, Direction::Down),
-            direction: Direction::Down,
+/// Functions for the Hiding struct
+impl Hiding {
+    /// Finds the candidate spots and decoy settings in a level file.
+    pub fn parse(lines: &[String]) -> Hiding {
+        let mut hiding = Hiding::default();
+        for line in lines {
+            let mut parse = line.split_whitespace();
+            match parse.next() {
+                Some("macguffin") => {
+                    let spot = parse
+                        .next()
+                        .and_then(|x| x.parse::<f32>().ok())
+                        .zip(parse.next().and_then(|y| y.parse::<f32>().ok()));
+                    if let Some(spot) = spot {
+                        hiding.spots.push(spot);
+                    }
+                }
+                Some("decoys") => {
+                    let count = parse.next().and_then(|c| c.parse::<usize>().ok());
+                    let kind = parse.next().and_then(EnemyKind::from_name);
+                    let amount = parse.next().and_then(|a| a.parse::<usize>().ok());
+                    if let (Some(count), Some(kind), Some(amount)) = (count, kind, amount) {
+                        hiding.decoys = count;
+                        hiding.ambush = Some((kind, amount));
+                    }
+                }
+                _ => (),
+            }
         }
+        hiding
+    }
+
+    /// Picks where the MacGuffin really is and where the decoys go (never the same spot twice).
+    /// Returns None if the level has no spots at all.
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Placement> {
+        let mut spots = self.spots.clone();
+        spots.shuffle(rng);
+        let mut spots = spots.into_iter();
+        let real = spots.next()?;
+        Some(Placement {
+            real,
+            decoys: spots.take(self.decoys).collect(),
+        })
     }
 }
 
-/// The draw trait for the Macguffin
-impl DrawableEntity for Macguffin {
-    /// Draws up the Macguffin
-    fn draw(&self, ctx: &mut Context) -> GameResult {
-        let dp = graphics::DrawParam::default().dest(na::Point2::new(self.x, self.y));
-        graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
+#[cfg(test)]
+mod testmacguffin {
+    use super::*;
+    use rand::rngs::StdRng;
 
-        Ok(())
+    fn lines() -> Vec<String> {
+        vec![
+            "macguffin 1050 -650".to_string(),
+            "macguffin 768 -512".to_string(),
+            "macguffin 1856 512".to_string(),
+            "macguffin nowhere".to_string(),
+            "decoys 1 skeleton 3".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_parse() {
+        let hiding = Hiding::parse(&lines());
+        assert_eq!(hiding.spots.len(), 3);
+        assert_eq!(hiding.decoys, 1);
+        assert_eq!(hiding.ambush, Some((EnemyKind::Skeleton, 3)));
+        assert_eq!(Hiding::parse(&[]).choose(&mut thread_rng()), None);
     }
-}
 
-/// The collide trait for the Macguffin
-impl CollideEntity for Macguffin {
-    /// Function to determine the hitbox to collide with the Macguffin
-    fn get_hitbox(&self) -> graphics::Rect {
-        let mut r = self
-            .sprite
-            .get(&self.animation)
-            .unwrap()
-            .dimensions()
-            .unwrap();
-        r.x = self.x;
-        r.y = self.y;
-        r
+    #[test]
+    fn test_seeded_choice() {
+        let hiding = Hiding::parse(&lines());
+        let placed = hiding.choose(&mut StdRng::seed_from_u64(11)).unwrap();
+        assert_eq!(placed.decoys.len(), 1);
+        assert_ne!(placed.real, placed.decoys[0]);
+        // the same seed always hides it in the same place
+        for _ in 0..10 {
+            let again = hiding.choose(&mut StdRng::seed_from_u64(11)).unwrap();
+            assert_eq!(again, placed);
+        }
+        // every spot gets used by some seed
+        let picked: Vec<(f32, f32)> = (0..50)
+            .map(|seed| {
+                hiding
+                    .choose(&mut StdRng::seed_from_u64(seed))
+                    .unwrap()
+                    .real
+            })
+            .collect();
+        for spot in &hiding.spots {
+            assert!(picked.contains(spot));
+        }
     }
 }
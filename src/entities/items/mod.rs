@@ -1,2 +1,5 @@
+pub mod inventory;
+pub mod loot;
 pub mod macguffin;
-pub mod potions;
+pub mod registry;
+pub mod shop;
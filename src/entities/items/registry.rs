@@ -0,0 +1,199 @@
+use super::inventory::Item;
+use super::loot::{Gear, KeyColour};
+use ggez::graphics::{self, Color, Rect};
+
+/// Size of one cell on the items.png sprite sheet.
+pub const CELL_SIZE: f32 = 64.0;
+
+/// What happens when an item is walked over.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pickup {
+    // goes in the inventory
+    Carry,
+    // counted towards the player's gold instead of taking up a slot
+    Purse,
+    // goes in the inventory, but can't be dropped or used up
+    Quest,
+    // never makes it to the inventory, something nasty jumps out instead
+    Decoy,
+}
+
+/// What using an item does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UseEffect {
+    // back to full hp
+    Heal,
+    // back to full mp
+    RestoreMp,
+    // runs faster for a while
+    Haste,
+    // blows up everything around the player
+    Explode,
+    // back to where the level started
+    Teleport,
+    // put on (whatever was in its slot goes back in the inventory)
+    Equip,
+}
+
+/// Everything about one type of item.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ItemDef {
+    pub name: &'static str,
+    // column and row on items.png
+    pub cell: (u32, u32),
+    pub pickup: Pickup,
+    pub effect: Option<UseEffect>,
+    // how many fit in one inventory slot
+    pub max_stack: u32,
+    // drawn over the sprite (white leaves it as it is)
+    pub tint: Color,
+}
+
+/// Functions for the ItemDef struct
+impl ItemDef {
+    /// Where the item is on the items.png sprite sheet.
+    pub fn sprite_clip(&self) -> Rect {
+        Rect::new(
+            self.cell.0 as f32 * CELL_SIZE,
+            self.cell.1 as f32 * CELL_SIZE,
+            CELL_SIZE,
+            CELL_SIZE,
+        )
+    }
+}
+
+/// The registry of every type of item. Adding an item means adding it to
+/// Item and giving it a definition here.
+pub fn lookup(item: Item) -> ItemDef {
+    let (name, cell, pickup, effect, max_stack) = match item {
+        Item::Potion => ("Potion", (1, 2), Pickup::Carry, Some(UseEffect::Heal), 5),
+        Item::ManaPotion => (
+            "Mana Potion",
+            (1, 1),
+            Pickup::Carry,
+            Some(UseEffect::RestoreMp),
+            5,
+        ),
+        Item::SpeedElixir => (
+            "Speed Elixir",
+            (3, 1),
+            Pickup::Carry,
+            Some(UseEffect::Haste),
+            3,
+        ),
+        Item::Bomb => ("Bomb", (2, 4), Pickup::Carry, Some(UseEffect::Explode), 5),
+        Item::TeleportScroll => (
+            "Scroll Of Teleport",
+            (1, 0),
+            Pickup::Carry,
+            Some(UseEffect::Teleport),
+            3,
+        ),
+        Item::Gold => ("Gold", (4, 4), Pickup::Purse, None, 1),
+        Item::Key => ("Key", (2, 2), Pickup::Carry, None, 9),
+        Item::ColouredKey(colour) => {
+            let name = match colour {
+                KeyColour::Red => "Red Key",
+                KeyColour::Blue => "Blue Key",
+                KeyColour::Gold => "Gold Key",
+            };
+            (name, (2, 2), Pickup::Carry, None, 9)
+        }
+        Item::Equipment(gear) => {
+            let (name, cell) = match gear {
+                Gear::Dagger => ("Dagger", (0, 3)),
+                Gear::Spear => ("Spear", (1, 4)),
+                Gear::Hammer => ("Hammer", (0, 0)),
+                Gear::Armour => ("Armour", (2, 1)),
+                Gear::Helmet => ("Helmet", (5, 1)),
+                Gear::Ring => ("Ring", (4, 1)),
+            };
+            (name, cell, Pickup::Carry, Some(UseEffect::Equip), 1)
+        }
+        Item::MacGuffin => ("The MacGuffin", (3, 0), Pickup::Quest, None, 1),
+        // looks just like the real thing
+        Item::Decoy => ("The MacGuffin?", (3, 0), Pickup::Decoy, None, 1),
+    };
+    let tint = match item {
+        Item::ColouredKey(colour) => colour.color(),
+        _ => graphics::WHITE,
+    };
+    ItemDef {
+        name,
+        cell,
+        pickup,
+        effect,
+        max_stack,
+        tint,
+    }
+}
+
+/// Looks up an item from the name used in level files (ie "item bomb 320 448").
+/// Door keys are named after their colour (ie "red_key").
+pub fn from_name(name: &str) -> Option<Item> {
+    if let Some(colour) = name.strip_suffix("_key").and_then(KeyColour::from_name) {
+        return Some(Item::ColouredKey(colour));
+    }
+    let item = match name {
+        "potion" => Item::Potion,
+        "mana_potion" => Item::ManaPotion,
+        "speed_elixir" => Item::SpeedElixir,
+        "bomb" => Item::Bomb,
+        "teleport_scroll" => Item::TeleportScroll,
+        "gold" => Item::Gold,
+        "key" => Item::Key,
+        "dagger" => Item::Equipment(Gear::Dagger),
+        "spear" => Item::Equipment(Gear::Spear),
+        "hammer" => Item::Equipment(Gear::Hammer),
+        "armour" => Item::Equipment(Gear::Armour),
+        "helmet" => Item::Equipment(Gear::Helmet),
+        "ring" => Item::Equipment(Gear::Ring),
+        "macguffin" => Item::MacGuffin,
+        _ => return None,
+    };
+    Some(item)
+}
+
+#[cfg(test)]
+mod testregistry {
+    use super::*;
+
+    #[test]
+    fn test_every_item_is_on_the_sheet() {
+        let names = [
+            "potion",
+            "mana_potion",
+            "speed_elixir",
+            "bomb",
+            "teleport_scroll",
+            "gold",
+            "key",
+            "red_key",
+            "blue_key",
+            "gold_key",
+            "dagger",
+            "spear",
+            "hammer",
+            "armour",
+            "helmet",
+            "ring",
+            "macguffin",
+        ];
+        for name in names.iter() {
+            let def = lookup(from_name(name).unwrap());
+            // items.png is six cells across and five down
+            assert!(def.cell.0 < 6 && def.cell.1 < 5);
+            assert!(def.max_stack > 0);
+        }
+        assert_eq!(from_name("sandwich"), None);
+        assert_eq!(lookup(Item::Gold).pickup, Pickup::Purse);
+        assert!(Item::MacGuffin.quest());
+        assert_eq!(lookup(Item::Decoy).cell, lookup(Item::MacGuffin).cell);
+        assert_eq!(
+            from_name("blue_key"),
+            Some(Item::ColouredKey(KeyColour::Blue))
+        );
+        assert_eq!(from_name("green_key"), None);
+        assert_eq!(lookup(Item::Key).tint, graphics::WHITE);
+    }
+}
@@ -0,0 +1,180 @@
+use super::inventory::{Inventory, Item};
+use super::registry;
+use ggez::Context;
+use std::io::Read;
+
+/// Why a merchant won't go through with a trade.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Refusal {
+    // the merchant doesn't sell it
+    NotForSale,
+    // the merchant doesn't buy it (ie quest items)
+    WontBuy,
+    NotEnoughGold,
+    NoRoom,
+    // nothing in that slot
+    Nothing,
+}
+
+/// Functions for the Refusal enum
+impl Refusal {
+    /// What the merchant says instead.
+    pub fn text(self) -> &'static str {
+        match self {
+            Refusal::NotForSale => "I Don't Sell That",
+            Refusal::WontBuy => "I Don't Want That",
+            Refusal::NotEnoughGold => "Not Enough Gold",
+            Refusal::NoRoom => "No Room In Your Inventory",
+            Refusal::Nothing => "Nothing To Sell",
+        }
+    }
+}
+
+/// What a merchant pays for and charges for each item.
+/// Loaded from a data file with lines of `item buy sell` using level file item names
+/// (# starts a comment). A buy price of 0 means the merchant doesn't stock the item,
+/// and anything not listed can't be sold.
+#[derive(Clone, Default, Debug)]
+pub struct Prices {
+    // (item, buy price, sell price) in file order
+    items: Vec<(Item, u32, u32)>,
+}
+
+/// Functions for the Prices struct
+impl Prices {
+    /// Reads a price list out of text. Lines that can't be read are skipped.
+    pub fn parse(text: &str) -> Prices {
+        let items = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.split('#').next()?;
+                let mut parse = line.split_whitespace();
+                let item = registry::from_name(parse.next()?)?;
+                let buy = parse.next()?.parse::<u32>().ok()?;
+                let sell = parse.next()?.parse::<u32>().ok()?;
+                Some((item, buy, sell))
+            })
+            .collect();
+        Prices { items }
+    }
+
+    /// Loads the price list from a file (nothing for sale if it can't be found).
+    pub fn load(ctx: &mut Context, path: &str) -> Prices {
+        if !ggez::filesystem::exists(ctx, path) {
+            println!("unable to find {} the merchant has nothing to trade", path);
+            return Prices::default();
+        }
+        let mut file = ggez::filesystem::open(ctx, path).unwrap();
+        let mut text = String::new();
+        file.read_to_string(&mut text).unwrap();
+        Prices::parse(&text)
+    }
+
+    /// What the merchant sells and for how much (in file order).
+    pub fn stock(&self) -> Vec<(Item, u32)> {
+        self.items
+            .iter()
+            .filter(|(_, buy, _)| *buy > 0)
+            .map(|(item, buy, _)| (*item, *buy))
+            .collect()
+    }
+
+    /// What the merchant pays for an item (None if they won't take it).
+    pub fn sell_price(&self, item: Item) -> Option<u32> {
+        if item.quest() {
+            return None;
+        }
+        self.items
+            .iter()
+            .find(|(i, _, _)| *i == item)
+            .map(|(_, _, sell)| *sell)
+    }
+
+    /// Buys one of an item. Returns what it cost.
+    pub fn buy(&self, inventory: &mut Inventory, item: Item) -> Result<u32, Refusal> {
+        let price = self
+            .stock()
+            .into_iter()
+            .find(|(i, _)| *i == item)
+            .map(|(_, price)| price)
+            .ok_or(Refusal::NotForSale)?;
+        if inventory.gold() < price {
+            return Err(Refusal::NotEnoughGold);
+        }
+        if !inventory.add(item) {
+            return Err(Refusal::NoRoom);
+        }
+        inventory.spend_gold(price);
+        Ok(price)
+    }
+
+    /// Sells one item out of an inventory slot. Returns what the merchant paid.
+    pub fn sell(&self, inventory: &mut Inventory, index: usize) -> Result<u32, Refusal> {
+        let item = inventory.slot(index).ok_or(Refusal::Nothing)?.item;
+        let price = self.sell_price(item).ok_or(Refusal::WontBuy)?;
+        inventory.take(index);
+        inventory.add_gold(price);
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod testshop {
+    use super::*;
+    use crate::entities::items::inventory::SLOTS;
+    use crate::entities::items::loot::Gear;
+
+    fn prices() -> Prices {
+        Prices::parse(
+            "# item buy sell\n\
+             potion 15 5\n\
+             ring 0 30 # only bought\n\
+             macguffin 0 100\n\
+             sandwich 3 1\n\
+             bomb twelve 4\n",
+        )
+    }
+
+    #[test]
+    fn test_parse_and_stock() {
+        let prices = prices();
+        assert_eq!(prices.stock(), vec![(Item::Potion, 15)]);
+        assert_eq!(prices.sell_price(Item::Equipment(Gear::Ring)), Some(30));
+        // quest items are never for sale to the merchant
+        assert_eq!(prices.sell_price(Item::MacGuffin), None);
+        assert_eq!(prices.sell_price(Item::Bomb), None);
+    }
+
+    #[test]
+    fn test_buy_and_sell() {
+        let prices = prices();
+        let mut inv = Inventory::new();
+        inv.add_gold(20);
+        assert_eq!(
+            prices.buy(&mut inv, Item::Equipment(Gear::Ring)),
+            Err(Refusal::NotForSale)
+        );
+        assert_eq!(prices.buy(&mut inv, Item::Potion), Ok(15));
+        assert_eq!(
+            prices.buy(&mut inv, Item::Potion),
+            Err(Refusal::NotEnoughGold)
+        );
+        assert_eq!(inv.gold(), 5);
+        assert_eq!(inv.count(Item::Potion), 1);
+
+        assert_eq!(prices.sell(&mut inv, 0), Ok(5));
+        assert_eq!(inv.gold(), 10);
+        assert_eq!(prices.sell(&mut inv, 0), Err(Refusal::Nothing));
+        inv.add(Item::Bomb);
+        assert_eq!(prices.sell(&mut inv, 0), Err(Refusal::WontBuy));
+        assert!(inv.has(Item::Bomb));
+
+        // a full inventory keeps its gold
+        inv.add_gold(10);
+        for _ in 1..SLOTS {
+            inv.add(Item::Equipment(Gear::Dagger));
+        }
+        assert_eq!(prices.buy(&mut inv, Item::Potion), Err(Refusal::NoRoom));
+        assert_eq!(inv.gold(), 20);
+    }
+}
@@ -0,0 +1,123 @@
+use super::environment::level::Level;
+use super::movement::slide;
+use super::CollideEntity;
+use ggez::graphics::Rect;
+
+/// How quickly knockback slows down (per second, about 80% of the speed is kept every 60th of a second).
+const DRAG: f32 = 13.4;
+/// Knockback slower than this (pixels per second) is considered over.
+const STOPPED: f32 = 6.0;
+
+/// Knockback impulse that shoves an entity away from whatever hit it
+/// and slows down until it stops. Speeds are in pixels per second.
+#[derive(Default)]
+pub struct Knockback {
+    pub dx: f32,
+    pub dy: f32,
+}
+
+/// Functions for the Knockback struct
+impl Knockback {
+    /// News up a Knockback that is not moving anything.
+    pub fn new() -> Knockback {
+        Knockback { dx: 0.0, dy: 0.0 }
+    }
+
+    /// Pushes away from the point `from` towards the point `to` with speed `strength`.
+    pub fn hit(&mut self, from: (f32, f32), to: (f32, f32), strength: f32) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let dist = (dx * dx + dy * dy).sqrt();
+        if dist > 0.0 {
+            self.dx = dx / dist * strength;
+            self.dy = dy / dist * strength;
+        } else {
+            // right on top of each other, just pick a way
+            self.dx = strength;
+            self.dy = 0.0;
+        }
+    }
+
+    /// Returns true while the entity is still being pushed around.
+    pub fn active(&self) -> bool {
+        self.dx.abs() > STOPPED || self.dy.abs() > STOPPED
+    }
+
+    /// Returns how far to move over `dt` seconds and slows the knockback down.
+    pub fn step(&mut self, dt: f32) -> (f32, f32) {
+        if !self.active() {
+            self.stop();
+            return (0.0, 0.0);
+        }
+        let step = (self.dx * dt, self.dy * dt);
+        let slow = (-DRAG * dt).exp();
+        self.dx *= slow;
+        self.dy *= slow;
+        step
+    }
+
+    /// Returns how far an entity with `hitbox` gets knocked over `dt` seconds without going
+    /// through walls. Running into a wall stops the knockback on that axis.
+    pub fn resolve(&mut self, hitbox: Rect, level: &Level, dt: f32) -> (f32, f32) {
+        let (dx, dy) = self.step(dt);
+        let (mx, my) = slide(hitbox, dx, dy, |r| r.collision(level));
+        if mx != dx {
+            self.dx = 0.0;
+        }
+        if my != dy {
+            self.dy = 0.0;
+        }
+        (mx, my)
+    }
+
+    /// Stops the knockback (ie ran into a wall).
+    pub fn stop(&mut self) {
+        self.dx = 0.0;
+        self.dy = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod testknockback {
+    use super::*;
+
+    #[test]
+    fn test_knockback_pushes_away_and_stops() {
+        let mut kb = Knockback::new();
+        kb.hit((0.0, 0.0), (10.0, 0.0), 300.0);
+        assert!(kb.active());
+        let (dx, dy) = kb.step(0.5);
+        assert_eq!((dx, dy), (150.0, 0.0));
+        for _ in 0..100 {
+            kb.step(1.0 / 60.0);
+        }
+        assert!(!kb.active());
+        assert_eq!(kb.step(1.0 / 60.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_knockback_stops_at_walls() {
+        let mut level = Level::new(Vec::new());
+        level.set_barriers(vec![Rect::new(100.0, 0.0, 64.0, 200.0)]);
+        let mut kb = Knockback::new();
+        kb.hit((0.0, 0.0), (1.0, 1.0), 480.0);
+        // the wall is just to the right, so only the push down happens
+        let (dx, dy) = kb.resolve(Rect::new(30.0, 0.0, 64.0, 64.0), &level, 0.1);
+        assert_eq!(dx, 0.0);
+        assert!(dy > 0.0);
+        assert_eq!(kb.dx, 0.0);
+        assert!(kb.active());
+    }
+
+    #[test]
+    fn test_knockback_same_distance_at_any_frame_rate() {
+        let distance = |frames: u32| {
+            let mut kb = Knockback::new();
+            kb.hit((0.0, 0.0), (1.0, 0.0), 480.0);
+            (0..frames)
+                .map(|_| kb.step(1.0 / frames as f32).0)
+                .sum::<f32>()
+        };
+        // one second at 30 and 120 fps end up in about the same place
+        assert!((distance(30) - distance(120)).abs() < 10.0);
+    }
+}
@@ -20,11 +20,24 @@ pub mod items;
 // Namespace for things related to npcs
 pub mod npcs;
 
+// Knockback shared by the player and enemies
+pub mod knockback;
+
+// Status effects (poison, slow, ...) shared by the player and enemies
+pub mod status;
+
+// Movement (diagonals and sliding along walls) shared by the player and enemies
+pub mod movement;
+
+// Damage types, resistances and crits shared by the player and enemies
+pub mod combat;
+
 // required by traits
 use ggez::graphics::Rect;
 use ggez::{Context, GameResult};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Direction {
     Up,
     Left,
@@ -32,15 +45,29 @@ pub enum Direction {
     Right,
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Animations {
     Stand,
     Walking,
     Cast,
     Slash,
+    Roll,
     Die,
 }
 
+/// Tells entities apart for as long as the game runs (ie who a swing has already hit).
+/// Unlike where an entity lives in memory, an id is never handed out again once it is gone.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EntityId(usize);
+
+impl EntityId {
+    /// An id no other entity has had.
+    pub fn fresh() -> EntityId {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        EntityId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 // trait used to mark drawable entities
 // must implement ggez::graphics::Drawable (but not right now)
 pub trait DrawableEntity /*: Drawable */ {
@@ -72,3 +99,17 @@ pub trait CollideEntity {
         false
     }
 }
+
+/// How close the player has to be to use something (pixels from its edge).
+pub const REACH: f32 = 16.0;
+
+/// Returns true if the player is within `reach` pixels of `hitbox` (ie close enough to talk to or open it).
+pub fn in_reach(hitbox: Rect, reach: f32, player: Rect) -> bool {
+    let area = Rect::new(
+        hitbox.x - reach,
+        hitbox.y - reach,
+        hitbox.w + reach * 2.0,
+        hitbox.h + reach * 2.0,
+    );
+    area.overlaps(&player)
+}
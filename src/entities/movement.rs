@@ -0,0 +1,143 @@
+use super::{CollideEntity, Direction};
+use ggez::graphics::Rect;
+
+/// Lets a plain rectangle be checked against anything else that collides
+/// (ie where a hitbox would end up after moving).
+impl CollideEntity for Rect {
+    fn get_hitbox(&self) -> Rect {
+        *self
+    }
+}
+
+/// Scales (dx, dy) to a length of one so moving diagonally isn't faster.
+/// Returns (0, 0) if there is nowhere to go.
+pub fn normalise(dx: f32, dy: f32) -> (f32, f32) {
+    let len = (dx * dx + dy * dy).sqrt();
+    if len < f32::EPSILON {
+        return (0.0, 0.0);
+    }
+    (dx / len, dy / len)
+}
+
+/// Step of at most `step` pixels from `from` towards `to` (never past it).
+pub fn towards(from: (f32, f32), to: (f32, f32), step: f32) -> (f32, f32) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let dist = (dx * dx + dy * dy).sqrt();
+    let (nx, ny) = normalise(dx, dy);
+    let step = step.min(dist);
+    (nx * step, ny * step)
+}
+
+/// Direction of length one pointing the way `direction` faces.
+pub fn unit(direction: Direction) -> (f32, f32) {
+    match direction {
+        Direction::Up => (0.0, -1.0),
+        Direction::Down => (0.0, 1.0),
+        Direction::Left => (-1.0, 0.0),
+        Direction::Right => (1.0, 0.0),
+    }
+}
+
+/// Which way to face when moving by (dx, dy). Left and right win on diagonals.
+pub fn facing(dx: f32, dy: f32) -> Option<Direction> {
+    if dx > 0.0 {
+        Some(Direction::Right)
+    } else if dx < 0.0 {
+        Some(Direction::Left)
+    } else if dy < 0.0 {
+        Some(Direction::Up)
+    } else if dy > 0.0 {
+        Some(Direction::Down)
+    } else {
+        None
+    }
+}
+
+/// Moves the hitbox `hb` by (dx, dy) one axis at a time, dropping the part of the move on any
+/// axis that runs into something `blocked`. This lets things slide along walls instead of sticking.
+/// Returns how far the hitbox can actually move.
+pub fn slide<F: Fn(&Rect) -> bool>(hb: Rect, dx: f32, dy: f32, blocked: F) -> (f32, f32) {
+    let mut r = hb;
+
+    r.x += dx;
+    let mx = if dx != 0.0 && blocked(&r) {
+        r.x -= dx;
+        0.0
+    } else {
+        dx
+    };
+
+    r.y += dy;
+    let my = if dy != 0.0 && blocked(&r) { 0.0 } else { dy };
+
+    (mx, my)
+}
+
+/// How far the hitbox `hb` can travel (up to `max` pixels) in the direction (dx, dy) of length one
+/// before running into something `blocked`. The path is checked in steps no bigger than `step` (or the
+/// hitbox) so nothing thinner than the hitbox gets skipped over, and it stops at the last clear step.
+pub fn clear_distance<F: Fn(&Rect) -> bool>(
+    hb: Rect,
+    dx: f32,
+    dy: f32,
+    max: f32,
+    step: f32,
+    blocked: F,
+) -> f32 {
+    let step = step.min(hb.w).min(hb.h).max(1.0);
+    let mut travelled = 0.0;
+    while travelled < max {
+        let next = (travelled + step).min(max);
+        let mut r = hb;
+        r.translate([dx * next, dy * next]);
+        if blocked(&r) {
+            break;
+        }
+        travelled = next;
+    }
+    travelled
+}
+
+#[cfg(test)]
+mod testmovement {
+    use super::*;
+
+    #[test]
+    fn test_diagonal_is_not_faster() {
+        let (x, y) = normalise(1.0, -1.0);
+        assert!(((x * x + y * y).sqrt() - 1.0).abs() < 0.0001);
+        assert_eq!(normalise(0.0, 0.0), (0.0, 0.0));
+        assert_eq!(towards((0.0, 0.0), (3.0, 4.0), 10.0), (3.0, 4.0));
+        assert_eq!(facing(1.0, 1.0), Some(Direction::Right));
+        assert_eq!(facing(0.0, -1.0), Some(Direction::Up));
+    }
+
+    #[test]
+    fn test_slide_along_wall() {
+        // wall along the top of the hitbox
+        let wall = Rect::new(-100.0, -10.0, 200.0, 10.0);
+        let hb = Rect::new(0.0, 1.0, 32.0, 32.0);
+        let blocked = |r: &Rect| r.overlaps(&wall);
+
+        // moving up and right keeps the right
+        assert_eq!(slide(hb, 2.0, -2.0, blocked), (2.0, 0.0));
+        // straight into the wall goes nowhere
+        assert_eq!(slide(hb, 0.0, -2.0, blocked), (0.0, 0.0));
+        // away from the wall is fine
+        assert_eq!(slide(hb, -2.0, 2.0, blocked), (-2.0, 2.0));
+    }
+
+    #[test]
+    fn test_clear_distance_stops_before_wall() {
+        // a one tile wall between where I start and where I would land
+        let wall = Rect::new(96.0, 0.0, 64.0, 64.0);
+        let hb = Rect::new(0.0, 0.0, 32.0, 32.0);
+        let blocked = |r: &Rect| r.overlaps(&wall);
+
+        let dist = clear_distance(hb, 1.0, 0.0, 160.0, 16.0, blocked);
+        assert!(dist < 96.0 - 32.0 + 1.0);
+        assert!(dist >= 96.0 - 32.0 - 16.0);
+        // nothing in the way goes the whole way
+        assert_eq!(clear_distance(hb, 0.0, 1.0, 160.0, 16.0, blocked), 160.0);
+    }
+}
@@ -0,0 +1,55 @@
+use ggez::Context;
+use std::time::Duration;
+
+use crate::ui::FloatingText;
+
+/// How long an npc waits before saying something again.
+const TALK_COOLDOWN: Duration = Duration::from_millis(1000);
+
+/// Floating text said by an npc, with a cooldown so it isn't said every frame.
+pub struct Chatter {
+    floating_text: Vec<FloatingText>,
+    cooldown: Duration,
+    color: &'static str,
+}
+
+/// Functions for the Chatter struct
+impl Chatter {
+    /// News up a Chatter that can talk straight away.
+    pub fn new(color: &'static str) -> Chatter {
+        Chatter {
+            floating_text: Vec::new(),
+            cooldown: TALK_COOLDOWN,
+            color,
+        }
+    }
+
+    /// Keeps current floating text and counts down the cooldown.
+    pub fn update(&mut self, delta: Duration) {
+        self.floating_text.retain(|t| t.live());
+        self.floating_text.iter_mut().for_each(|t| t.update(delta));
+
+        if self.cooling_down() {
+            self.cooldown += delta;
+        }
+    }
+
+    /// Shows the floating text at (x, y) if the cooldown is done.
+    pub fn talk(&mut self, ctx: &mut Context, x: f32, y: f32, text: String) {
+        if !self.cooling_down() {
+            self.cooldown = Duration::new(0, 0);
+            self.floating_text
+                .push(FloatingText::new(ctx, x, y, text, self.color));
+        }
+    }
+
+    /// Draws the floating text.
+    pub fn draw(&self, ctx: &mut Context) {
+        self.floating_text.iter().for_each(|t| t.draw(ctx));
+    }
+
+    /// Check to see if something was said in the last TALK_COOLDOWN
+    fn cooling_down(&self) -> bool {
+        self.cooldown < TALK_COOLDOWN
+    }
+}
@@ -0,0 +1,360 @@
+use crate::entities::items::inventory::{Inventory, Item};
+use crate::entities::items::loot::Pile;
+use crate::entities::items::registry;
+use ggez::Context;
+use std::collections::HashSet;
+use std::io::Read;
+
+/// Most gotos followed in a row before giving up (so a loop in a file can't hang the game).
+const MAX_JUMPS: usize = 16;
+
+/// Things dialogue remembers between conversations, and whether it has asked for the victory sequence.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Story {
+    flags: HashSet<String>,
+    pub victory: bool,
+}
+
+/// Functions for the Story struct
+impl Story {
+    /// News up a Story with no flags set.
+    pub fn new() -> Story {
+        Story::default()
+    }
+
+    /// Returns true if a flag has been set.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+}
+
+/// What dialogue can see of (and change about) the player.
+pub struct Listener<'a> {
+    pub level: u32,
+    pub inventory: &'a mut Inventory,
+    pub story: &'a mut Story,
+}
+
+/// Something that has to be true for a goto or choice to be used.
+/// Written `has:<item>`, `level:<at least>` or `flag:<name>`, with a leading ! to turn it around.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Condition {
+    Has(Item),
+    Level(u32),
+    Flag(String),
+    Not(Box<Condition>),
+}
+
+/// Functions for the Condition enum
+impl Condition {
+    /// Reads one condition (None if it can't be read).
+    pub fn parse(word: &str) -> Option<Condition> {
+        if let Some(rest) = word.strip_prefix('!') {
+            return Condition::parse(rest).map(|c| Condition::Not(Box::new(c)));
+        }
+        let (kind, value) = word.split_once(':')?;
+        match kind {
+            "has" => registry::from_name(value).map(Condition::Has),
+            "level" => value.parse::<u32>().ok().map(Condition::Level),
+            "flag" => Some(Condition::Flag(value.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the condition holds for the listener.
+    pub fn holds(&self, who: &Listener) -> bool {
+        match self {
+            Condition::Has(item) => who.inventory.has(*item),
+            Condition::Level(lv) => who.level >= *lv,
+            Condition::Flag(name) => who.story.flag(name),
+            Condition::Not(c) => !c.holds(who),
+        }
+    }
+}
+
+/// Something that happens when a node is reached.
+/// Written `do set <flag>`, `do clear <flag>`, `do give <item> [count]` or `do victory`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DialogueAction {
+    SetFlag(String),
+    ClearFlag(String),
+    // gold goes in the purse, anything that doesn't fit is dropped at the player's feet
+    Give(Pile),
+    // ends the game in victory once the conversation is over
+    Victory,
+}
+
+/// Functions for the DialogueAction enum
+impl DialogueAction {
+    /// Reads an action out of the words after `do` (None if it can't be read).
+    pub fn parse(words: &[&str]) -> Option<DialogueAction> {
+        match *words.first()? {
+            "set" => Some(DialogueAction::SetFlag(words.get(1)?.to_string())),
+            "clear" => Some(DialogueAction::ClearFlag(words.get(1)?.to_string())),
+            "give" => {
+                let item = registry::from_name(words.get(1)?)?;
+                let count = words
+                    .get(2)
+                    .and_then(|c| c.parse::<u32>().ok())
+                    .unwrap_or(1);
+                Some(DialogueAction::Give(Pile { item, count }))
+            }
+            "victory" => Some(DialogueAction::Victory),
+            _ => None,
+        }
+    }
+
+    /// Makes it happen.
+    pub fn apply(&self, who: &mut Listener) {
+        match self {
+            DialogueAction::SetFlag(name) => {
+                who.story.flags.insert(name.clone());
+            }
+            DialogueAction::ClearFlag(name) => {
+                who.story.flags.remove(name);
+            }
+            DialogueAction::Give(pile) if pile.item == Item::Gold => {
+                who.inventory.find_gold(pile.count)
+            }
+            DialogueAction::Give(pile) => {
+                for _ in 0..pile.count {
+                    who.inventory.give(pile.item);
+                }
+            }
+            DialogueAction::Victory => who.story.victory = true,
+        }
+    }
+}
+
+/// Something the player can say back.
+/// Written `choice <node|end> [conditions] | <text>` (only shown if every condition holds).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Choice {
+    pub text: String,
+    // None ends the conversation
+    pub target: Option<String>,
+    pub conditions: Vec<Condition>,
+}
+
+/// One step of a conversation.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Node {
+    pub id: String,
+    // what the NPC says (one entry per `say` line)
+    pub lines: Vec<String>,
+    pub actions: Vec<DialogueAction>,
+    // `goto <node|end> [conditions]` jumps straight on to another node (first one that holds is used)
+    pub gotos: Vec<(Option<String>, Vec<Condition>)>,
+    pub choices: Vec<Choice>,
+}
+
+/// What is shown for a node: what the NPC says and the choices the player has.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Page {
+    pub text: String,
+    pub choices: Vec<Choice>,
+}
+
+/// A dialogue tree for an NPC.
+/// Loaded from a data file made of `node <id>` lines, each followed by the `say`, `do`,
+/// `goto` and `choice` lines belonging to it (# starts a comment). Conversations start at
+/// the first node.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Dialogue {
+    nodes: Vec<Node>,
+}
+
+/// Reads a goto or choice target (end finishes the conversation).
+fn target(word: &str) -> Option<String> {
+    if word == "end" {
+        None
+    } else {
+        Some(word.to_string())
+    }
+}
+
+/// Functions for the Dialogue struct
+impl Dialogue {
+    /// Reads a dialogue tree out of text. Lines that can't be read are skipped.
+    pub fn parse(text: &str) -> Dialogue {
+        let mut nodes: Vec<Node> = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let (keyword, rest) = match line.find(char::is_whitespace) {
+                Some(i) => (&line[..i], line[i..].trim()),
+                None => (line, ""),
+            };
+            if keyword == "node" {
+                nodes.push(Node {
+                    id: rest.to_string(),
+                    ..Node::default()
+                });
+                continue;
+            }
+            // everything else belongs to the node above it
+            let node = match nodes.last_mut() {
+                Some(node) => node,
+                None => continue,
+            };
+            let words: Vec<&str> = rest.split_whitespace().collect();
+            match keyword {
+                "say" => node.lines.push(rest.to_string()),
+                "do" => node.actions.extend(DialogueAction::parse(&words)),
+                "goto" if !words.is_empty() => {
+                    let conditions = words[1..].iter().filter_map(|w| Condition::parse(w));
+                    node.gotos.push((target(words[0]), conditions.collect()));
+                }
+                "choice" => {
+                    let mut halves = rest.splitn(2, '|');
+                    let mut head = halves.next().unwrap_or("").split_whitespace();
+                    let text = halves.next().map(str::trim);
+                    if let (Some(to), Some(text)) = (head.next(), text) {
+                        node.choices.push(Choice {
+                            text: text.to_string(),
+                            target: target(to),
+                            conditions: head.filter_map(Condition::parse).collect(),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        Dialogue { nodes }
+    }
+
+    /// Loads a dialogue tree from a file (nothing to say if it can't be found).
+    pub fn load(ctx: &mut Context, path: &str) -> Dialogue {
+        if !ggez::filesystem::exists(ctx, path) {
+            println!("unable to find {} this npc has nothing to say", path);
+            return Dialogue::default();
+        }
+        let mut file = ggez::filesystem::open(ctx, path).unwrap();
+        let mut text = String::new();
+        file.read_to_string(&mut text).unwrap();
+        Dialogue::parse(&text)
+    }
+
+    /// Where conversations start (None if there is nothing to say).
+    pub fn start(&self) -> Option<&str> {
+        self.nodes.first().map(|n| n.id.as_str())
+    }
+
+    /// Moves to a node: follows its gotos, does what the node it ends up at says to do
+    /// and returns what to show. Returns None once the conversation is over.
+    pub fn enter(&self, id: &str, who: &mut Listener) -> Option<Page> {
+        let mut node = self.nodes.iter().find(|n| n.id == id)?;
+        for _ in 0..MAX_JUMPS {
+            let jump = node
+                .gotos
+                .iter()
+                .find(|(_, conditions)| conditions.iter().all(|c| c.holds(who)));
+            match jump {
+                Some((Some(to), _)) => node = self.nodes.iter().find(|n| n.id == *to)?,
+                Some((None, _)) => return None,
+                None => break,
+            }
+        }
+        for action in &node.actions {
+            action.apply(who);
+        }
+        let choices = node
+            .choices
+            .iter()
+            .filter(|c| c.conditions.iter().all(|cond| cond.holds(who)))
+            .cloned()
+            .collect();
+        Some(Page {
+            text: node.lines.join("\n"),
+            choices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod testdialogue {
+    use super::*;
+
+    const TEXT: &str = "
+        # a small tree
+        node start
+        goto thanks has:macguffin
+        goto again flag:met
+        say Hello!
+        do set met
+        choice help | Help me?
+        choice secret level:5 | Tell me a secret.
+        choice end | Bye.
+
+        node again
+        say You again.
+        choice help !flag:helped | Help me?
+        choice end | Bye.
+
+        node help
+        say Take these.
+        do give potion 2
+        do give gold 10
+        do set helped
+        choice end | Thanks!
+
+        node thanks
+        say You found it!
+        do victory
+        do nonsense
+        choice end | Here.
+    ";
+
+    #[test]
+    fn test_parse() {
+        let dialogue = Dialogue::parse(TEXT);
+        assert_eq!(dialogue.start(), Some("start"));
+        assert_eq!(dialogue.nodes.len(), 4);
+        let start = &dialogue.nodes[0];
+        assert_eq!(start.gotos.len(), 2);
+        assert_eq!(start.choices[1].conditions, vec![Condition::Level(5)]);
+        assert_eq!(start.choices[2].target, None);
+        // the unknown action is skipped
+        assert_eq!(dialogue.nodes[3].actions, vec![DialogueAction::Victory]);
+        assert_eq!(
+            Condition::parse("!has:macguffin"),
+            Some(Condition::Not(Box::new(Condition::Has(Item::MacGuffin))))
+        );
+        assert_eq!(Condition::parse("mood:happy"), None);
+    }
+
+    #[test]
+    fn test_conditions_choices_and_actions() {
+        let dialogue = Dialogue::parse(TEXT);
+        let mut inventory = Inventory::new();
+        let mut story = Story::new();
+        let mut who = Listener {
+            level: 1,
+            inventory: &mut inventory,
+            story: &mut story,
+        };
+
+        // the secret needs a higher level
+        let page = dialogue.enter("start", &mut who).unwrap();
+        assert_eq!(page.text, "Hello!");
+        assert_eq!(page.choices.len(), 2);
+        assert!(who.story.flag("met"));
+
+        // met before, so start jumps on
+        let page = dialogue.enter("start", &mut who).unwrap();
+        assert_eq!(page.text, "You again.");
+        assert_eq!(page.choices[0].target, Some("help".to_string()));
+
+        dialogue.enter("help", &mut who).unwrap();
+        assert_eq!(who.inventory.count(Item::Potion), 2);
+        assert_eq!(who.inventory.gold(), 10);
+        assert_eq!(who.inventory.gold_found(), 10);
+        let page = dialogue.enter("again", &mut who).unwrap();
+        assert_eq!(page.choices.len(), 1);
+
+        who.inventory.add(Item::MacGuffin);
+        let page = dialogue.enter("start", &mut who).unwrap();
+        assert_eq!(page.text, "You found it!");
+        assert!(who.story.victory);
+        assert_eq!(dialogue.enter("nowhere", &mut who), None);
+    }
+}
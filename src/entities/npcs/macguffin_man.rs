@@ -5,8 +5,8 @@ use ggez::*;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use super::super::{Animations, CollideEntity, Direction, DrawableEntity};
-use crate::ui::FloatingText;
+use super::super::{in_reach, Animations, CollideEntity, Direction, DrawableEntity, REACH};
+use super::chatter::Chatter;
 
 /// The MacguffinMan struct
 pub struct MacguffinMan {
@@ -15,8 +15,7 @@ pub struct MacguffinMan {
     pub hp: f32,
     pub atk: f32,
     pub def: f32,
-    floating_text: Vec<FloatingText>,
-    pub cooldown: Duration,
+    chatter: Chatter,
     pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
     pub animation: (Animations, Direction),
     pub direction: Direction,
@@ -26,8 +25,6 @@ pub struct MacguffinMan {
 impl MacguffinMan {
     /// News up a MacguffinMan
     pub fn new(ctx: &mut Context, xpos: f32, ypos: f32) -> MacguffinMan {
-        let floating_text = Vec::new();
-
         let mut sprite = HashMap::new();
         let sheet = Image::new(ctx, "/macguffin-man.png").unwrap();
         let builder = AnimatedBuilder::new(&sheet);
@@ -45,8 +42,7 @@ impl MacguffinMan {
             hp: 10.0,
             atk: 3.0,
             def: 1.0,
-            floating_text,
-            cooldown: Duration::new(1u64, 0u32),
+            chatter: Chatter::new("White"),
             sprite,
             animation: (Animations::Stand, Direction::Down),
             direction: Direction::Down,
@@ -55,27 +51,17 @@ impl MacguffinMan {
 
     /// Keeps current floating text and resets the cooldown if needed for the text.
     pub fn update(&mut self, delta: Duration) {
-        self.floating_text.retain(|t| t.live());
-        self.floating_text.iter_mut().for_each(|t| t.update(delta));
-
-        // cooldown
-        if self.talk_cooldown() {
-            self.cooldown += delta;
-        }
+        self.chatter.update(delta);
     }
 
     /// Shows the floating text if the cooldown is done.
     pub fn talk(&mut self, ctx: &mut Context, text: String) {
-        if !self.talk_cooldown() {
-            self.cooldown = Duration::new(0u64, 0u32);
-            self.floating_text
-                .push(FloatingText::new(ctx, self.x, self.y, text, "White"));
-        }
+        self.chatter.talk(ctx, self.x, self.y, text);
     }
 
-    /// Check to see if the cooldown if less than 1000 milliseconds
-    fn talk_cooldown(&self) -> bool {
-        self.cooldown < Duration::from_millis(1000u64)
+    /// Returns true if the player is close enough to talk to.
+    pub fn in_reach(&self, player: Rect) -> bool {
+        in_reach(self.get_hitbox(), REACH, player)
     }
 }
 
@@ -86,7 +72,7 @@ impl DrawableEntity for MacguffinMan {
         let dp = graphics::DrawParam::default().dest(na::Point2::new(self.x, self.y));
         graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
 
-        self.floating_text.iter().for_each(|t| t.draw(ctx));
+        self.chatter.draw(ctx);
 
         Ok(())
     }
@@ -0,0 +1,109 @@
+use crate::sprites::*;
+use ggez::graphics::{Image, Rect};
+use ggez::nalgebra as na;
+use ggez::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::super::{in_reach, Animations, CollideEntity, Direction, DrawableEntity, REACH};
+use super::chatter::Chatter;
+
+/// The Merchant struct
+/// Trades with the player when they interact next to them.
+/// Level file line: `merchant <x> <y>`
+pub struct Merchant {
+    pub x: f32,
+    pub y: f32,
+    chatter: Chatter,
+    pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
+    pub animation: (Animations, Direction),
+    pub direction: Direction,
+}
+
+/// Functions for the Merchant
+impl Merchant {
+    /// News up a Merchant
+    pub fn new(ctx: &mut Context, xpos: f32, ypos: f32) -> Merchant {
+        let mut sprite = HashMap::new();
+        let sheet = Image::new(ctx, "/macguffin-man.png").unwrap();
+        let builder = AnimatedBuilder::new(&sheet);
+
+        sprite.insert(
+            (Animations::Stand, Direction::Down),
+            builder
+                .create_animated(Rect::new(0f32, 640f32, 64f32, 64f32), 1usize)
+                .unwrap(),
+        );
+
+        Merchant {
+            x: xpos,
+            y: ypos,
+            chatter: Chatter::new("Yellow"),
+            sprite,
+            animation: (Animations::Stand, Direction::Down),
+            direction: Direction::Down,
+        }
+    }
+
+    /// Finds where merchants stand in a level file (lines that aren't merchants are ignored).
+    pub fn parse_all(lines: &[String]) -> Vec<(f32, f32)> {
+        lines
+            .iter()
+            .filter_map(|line| {
+                let mut parse = line.split_whitespace();
+                if parse.next()? != "merchant" {
+                    return None;
+                }
+                let x = parse.next()?.parse::<f32>().ok()?;
+                let y = parse.next()?.parse::<f32>().ok()?;
+                Some((x, y))
+            })
+            .collect()
+    }
+
+    /// Keeps current floating text and resets the cooldown if needed for the text.
+    pub fn update(&mut self, delta: Duration) {
+        self.chatter.update(delta);
+    }
+
+    /// Shows the floating text if the cooldown is done.
+    pub fn talk(&mut self, ctx: &mut Context, text: String) {
+        self.chatter.talk(ctx, self.x, self.y, text);
+    }
+
+    /// Returns true if the player is close enough to trade.
+    pub fn in_reach(&self, player: Rect) -> bool {
+        in_reach(self.get_hitbox(), REACH, player)
+    }
+}
+
+/// The draw trait for the Merchant
+impl DrawableEntity for Merchant {
+    /// Draws the Merchant (tinted so they don't look like the MacguffinMan) and the floating text
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let dp = graphics::DrawParam::default()
+            .dest(na::Point2::new(self.x, self.y))
+            .color(graphics::Color::new(1.0, 0.85, 0.5, 1.0));
+        graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
+
+        self.chatter.draw(ctx);
+
+        Ok(())
+    }
+}
+
+/// Collide trait for the Merchant
+impl CollideEntity for Merchant {
+    /// Find the hitbox for the Merchant to check for collision.
+    fn get_hitbox(&self) -> graphics::Rect {
+        let mut r = self
+            .sprite
+            .get(&self.animation)
+            .unwrap()
+            .dimensions()
+            .unwrap();
+        r.x = self.x;
+        r.y = self.y;
+        r
+    }
+}
@@ -1 +1,4 @@
+pub mod chatter;
+pub mod dialogue;
 pub mod macguffin_man;
+pub mod merchant;
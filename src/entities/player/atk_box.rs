@@ -1,7 +1,20 @@
 use ggez::*;
 use std::mem;
+use std::time::Duration;
 
-use super::super::{CollideEntity, Direction, DrawableEntity};
+use super::super::combat::DamageType;
+use super::super::environment::level::Level;
+use super::super::status::StatusKind;
+use super::super::{CollideEntity, Direction, DrawableEntity, EntityId};
+
+/// What an attack does to whatever it hits.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Hit {
+    // scales the player's atk
+    pub power: f32,
+    pub kind: DamageType,
+    pub effect: Option<StatusKind>,
+}
 
 /// AtkBox struct
 pub struct AtkBox {
@@ -9,6 +22,18 @@ pub struct AtkBox {
     pub y: f32,
     pub hitbox: graphics::Rect,
     pub shape: graphics::Mesh,
+    pub effect: Option<StatusKind>,
+    pub kind: DamageType,
+    pub power: f32,
+    pub color: graphics::Color,
+    // pixels per second (only for attacks that fly, like fireballs)
+    pub velocity: (f32, f32),
+    // how long a flying (or lingering) attack has left before it fizzles out
+    pub life: Duration,
+    pub spent: bool,
+    // lingering attacks (ie frost novas) stay put, go through everything and hit each thing once
+    pub lingers: bool,
+    hit: Vec<EntityId>,
 }
 
 /// Functions for the AtkBox struct
@@ -58,15 +83,98 @@ impl AtkBox {
             y: ypos,
             hitbox: hb,
             shape: square,
+            effect: None,
+            kind: DamageType::Physical,
+            power: 1.0,
+            color: graphics::WHITE,
+            velocity: (0.0, 0.0),
+            life: Duration::new(0, 0),
+            spent: false,
+            lingers: false,
+            hit: Vec::new(),
         }
     }
+
+    /// Makes anything this AtkBox hits suffer from a status effect.
+    pub fn with_effect(mut self, kind: StatusKind) -> AtkBox {
+        self.effect = Some(kind);
+        self
+    }
+
+    /// Changes the type of damage the AtkBox does.
+    pub fn with_kind(mut self, kind: DamageType) -> AtkBox {
+        self.kind = kind;
+        self
+    }
+
+    /// Makes the AtkBox hit harder (or softer) than a normal swing.
+    pub fn with_power(mut self, power: f32) -> AtkBox {
+        self.power = power;
+        self
+    }
+
+    /// Draws the AtkBox in a colour.
+    pub fn with_color(mut self, color: graphics::Color) -> AtkBox {
+        self.color = color;
+        self
+    }
+
+    /// Makes the AtkBox fly at `velocity` (pixels per second) for `life`.
+    pub fn with_velocity(mut self, velocity: (f32, f32), life: Duration) -> AtkBox {
+        self.velocity = velocity;
+        self.life = life;
+        self
+    }
+
+    /// Keeps the AtkBox out for `life`, hitting everything it touches once (ie a frost nova).
+    pub fn lingering(mut self, life: Duration) -> AtkBox {
+        self.life = life;
+        self.lingers = true;
+        self
+    }
+
+    /// Returns true the first time a lingering AtkBox touches the target with `id`.
+    pub fn first_hit(&mut self, id: EntityId) -> bool {
+        if self.hit.contains(&id) {
+            return false;
+        }
+        self.hit.push(id);
+        true
+    }
+
+    /// What the AtkBox does to whatever it hits.
+    pub fn hit(&self) -> Hit {
+        Hit {
+            power: self.power,
+            kind: self.kind,
+            effect: self.effect,
+        }
+    }
+
+    /// Moves a flying AtkBox, which fizzles out when it hits a wall or runs out of time
+    /// (lingering ones don't mind walls).
+    pub fn update(&mut self, delta: Duration, level: &Level) {
+        self.x += self.velocity.0 * delta.as_secs_f32();
+        self.y += self.velocity.1 * delta.as_secs_f32();
+        self.life = self.life.checked_sub(delta).unwrap_or_default();
+        if self.life == Duration::new(0, 0) || (!self.lingers && self.collision(level)) {
+            self.spent = true;
+        }
+    }
+
+    /// Returns true while a flying (or lingering) AtkBox can still hit things.
+    pub fn live(&self) -> bool {
+        !self.spent
+    }
 }
 
 /// Draw trait for the AtkBox
 impl DrawableEntity for AtkBox {
     /// Draws a new AtkBox
     fn draw(&self, ctx: &mut Context) -> GameResult {
-        let dp = graphics::DrawParam::default().dest(nalgebra::Point2::new(self.x, self.y));
+        let dp = graphics::DrawParam::default()
+            .dest(nalgebra::Point2::new(self.x, self.y))
+            .color(self.color);
         graphics::draw(ctx, &self.shape, dp)
     }
 }
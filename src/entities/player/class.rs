@@ -0,0 +1,141 @@
+use super::super::items::loot::Gear;
+use super::progression::Growth;
+use super::spells::Spell;
+use super::stats::Stats;
+
+/// Something only one class can do (all of them work on their own, no button needed).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Ability {
+    // runs faster than anyone else
+    FleetFooted,
+    // shrugs off the first blow that would have killed them
+    Undying,
+}
+
+/// Functions for the Ability enum
+impl Ability {
+    /// Name shown on the main menu.
+    pub fn name(self) -> &'static str {
+        match self {
+            Ability::FleetFooted => "Fleet Footed",
+            Ability::Undying => "Undying",
+        }
+    }
+
+    /// What the ability does.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Ability::FleetFooted => "runs half again as fast",
+            Ability::Undying => "survives the first killing blow with 1 hp",
+        }
+    }
+}
+
+/// The adventurers that can be picked on the main menu.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Class {
+    ElfFighter,
+    DapperSkeleton,
+}
+
+/// Functions for the Class enum
+impl Class {
+    /// Every class (in the order they are listed on the main menu).
+    pub const ALL: [Class; 2] = [Class::ElfFighter, Class::DapperSkeleton];
+
+    /// Name shown on the main menu and in the UI.
+    pub fn name(self) -> &'static str {
+        match self {
+            Class::ElfFighter => "Elf Fighter",
+            Class::DapperSkeleton => "Dapper Skeleton",
+        }
+    }
+
+    /// One line about how the class plays.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Class::ElfFighter => "Tough and quick, fights up close with a spear",
+            Class::DapperSkeleton => "Frail, but knows every spell and has mp to spare",
+        }
+    }
+
+    /// Sprite sheet for the class.
+    pub fn sprite(self) -> &'static str {
+        match self {
+            Class::ElfFighter => "/elf_fighter.png",
+            Class::DapperSkeleton => "/dapper-skeleton-sheet.png",
+        }
+    }
+
+    /// Stats at level 1.
+    pub fn starting_stats(self) -> Stats {
+        let mut stats = match self {
+            Class::ElfFighter => Stats::new(1, 0, 60.0, 100, 3.5, 1.0, 1.0),
+            Class::DapperSkeleton => Stats::new(1, 0, 40.0, 200, 2.5, 0.5, 1.0),
+        };
+        stats.growth = self.growth();
+        stats
+    }
+
+    /// How quickly stats grow on levelling up.
+    pub fn growth(self) -> Growth {
+        match self {
+            Class::ElfFighter => Growth {
+                hp: 1.5,
+                mp: 0.5,
+                atk: 1.25,
+                def: 1.5,
+                spd: 1.0,
+            },
+            Class::DapperSkeleton => Growth {
+                hp: 0.75,
+                mp: 1.5,
+                atk: 0.75,
+                def: 1.0,
+                spd: 1.0,
+            },
+        }
+    }
+
+    /// Spells known at the start.
+    pub fn spells(self) -> Vec<Spell> {
+        match self {
+            Class::ElfFighter => vec![Spell::Heal, Spell::Blink],
+            Class::DapperSkeleton => Spell::ALL.to_vec(),
+        }
+    }
+
+    /// Gear the class starts with on.
+    pub fn starting_gear(self) -> Vec<Gear> {
+        match self {
+            Class::ElfFighter => vec![Gear::Spear],
+            Class::DapperSkeleton => vec![Gear::Ring],
+        }
+    }
+
+    /// The class's ability.
+    pub fn ability(self) -> Ability {
+        match self {
+            Class::ElfFighter => Ability::FleetFooted,
+            Class::DapperSkeleton => Ability::Undying,
+        }
+    }
+}
+
+#[cfg(test)]
+mod testclass {
+    use super::*;
+
+    #[test]
+    fn test_classes_differ() {
+        let elf = Class::ElfFighter.starting_stats();
+        let skeleton = Class::DapperSkeleton.starting_stats();
+        assert!(elf.max_hp > skeleton.max_hp);
+        assert!(skeleton.max_mp > elf.max_mp);
+        assert_eq!(skeleton.growth, Class::DapperSkeleton.growth());
+        // every class can cast something
+        for class in Class::ALL.iter() {
+            assert!(!class.spells().is_empty());
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+/// How long a dodge roll lasts (the player can't be hurt for all of it).
+pub const DODGE_TIME: Duration = Duration::from_millis(250);
+/// How fast the player rolls (pixels per second).
+pub const DODGE_SPEED: f32 = 640.0;
+/// Stamina a dodge roll takes.
+pub const DODGE_COST: f32 = 30.0;
+
+/// A quick roll that moves the player and keeps them from being hurt while it lasts.
+#[derive(Default)]
+pub struct Dodge {
+    // direction of length one the roll is going
+    pub dx: f32,
+    pub dy: f32,
+    left: Duration,
+    // the dodge key was pressed since the last update
+    requested: bool,
+}
+
+/// Functions for the Dodge struct
+impl Dodge {
+    /// News up a Dodge that isn't rolling.
+    pub fn new() -> Dodge {
+        Dodge::default()
+    }
+
+    /// Starts rolling in the direction (dx, dy) (which should have a length of one).
+    pub fn start(&mut self, dx: f32, dy: f32) {
+        self.dx = dx;
+        self.dy = dy;
+        self.left = DODGE_TIME;
+    }
+
+    /// Asks for a roll (once per press of the dodge key, so holding it doesn't chain rolls).
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Returns true if a roll was asked for since last time (presses while rolling are dropped).
+    pub fn take_request(&mut self) -> bool {
+        std::mem::take(&mut self.requested)
+    }
+
+    /// Returns true while rolling (and so while the player can't be hurt).
+    pub fn active(&self) -> bool {
+        self.left > Duration::new(0, 0)
+    }
+
+    /// How far through the roll the player is (0 just started, 1 done).
+    pub fn progress(&self) -> f32 {
+        1.0 - self.left.as_secs_f32() / DODGE_TIME.as_secs_f32()
+    }
+
+    /// Returns how far to roll over delta (nothing once the roll is over).
+    pub fn step(&mut self, delta: Duration) -> (f32, f32) {
+        if !self.active() {
+            return (0.0, 0.0);
+        }
+        // the last bit of the roll only goes as far as the time that was left
+        let dt = delta.min(self.left).as_secs_f32();
+        self.left = self.left.checked_sub(delta).unwrap_or_default();
+        (self.dx * DODGE_SPEED * dt, self.dy * DODGE_SPEED * dt)
+    }
+
+    /// Ends the roll early (ie ran into a wall).
+    pub fn stop(&mut self) {
+        self.left = Duration::new(0, 0);
+    }
+}
+
+#[cfg(test)]
+mod testdodge {
+    use super::super::stats::Stats;
+    use super::*;
+
+    #[test]
+    fn test_roll_distance_and_end() {
+        let mut dodge = Dodge::new();
+        assert!(!dodge.active());
+        dodge.request();
+        assert!(dodge.take_request());
+        assert!(!dodge.take_request());
+        dodge.start(0.0, -1.0);
+        assert!(dodge.active());
+        assert_eq!(dodge.progress(), 0.0);
+
+        let mut total = 0.0;
+        for _ in 0..30 {
+            total += dodge.step(Duration::from_millis(1000 / 60)).1;
+        }
+        // the whole roll, and no further
+        assert!((total + DODGE_SPEED * DODGE_TIME.as_secs_f32()).abs() < 0.01);
+        assert!(!dodge.active());
+        assert_eq!(dodge.progress(), 1.0);
+        assert_eq!(dodge.step(Duration::from_millis(16)), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_stamina() {
+        let mut stats = Stats::new(1, 0, 50.0, 100, 3.0, 0.5, 1.0);
+        let rolls = (0..10).filter(|_| stats.spend_stamina(DODGE_COST)).count();
+        assert_eq!(rolls, (stats.max_stamina / DODGE_COST) as usize);
+        assert!(!stats.spend_stamina(DODGE_COST));
+
+        stats.regen_stamina(Duration::from_secs(1));
+        assert!(stats.spend_stamina(DODGE_COST));
+        stats.regen_stamina(Duration::from_secs(60));
+        assert_eq!(stats.stamina, stats.max_stamina);
+    }
+}
@@ -0,0 +1,234 @@
+use super::super::items::loot::Gear;
+use super::stats::Stats;
+use crate::entities::status::StatusEffects;
+use std::time::Duration;
+
+/// Places the player can wear or hold gear.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EquipSlot {
+    Weapon,
+    Armour,
+    Trinket,
+}
+
+/// Functions for the EquipSlot enum
+impl EquipSlot {
+    /// Every slot (in the order they are listed on the character screen).
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Weapon, EquipSlot::Armour, EquipSlot::Trinket];
+
+    /// Name shown on the character screen.
+    pub fn name(self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "Weapon",
+            EquipSlot::Armour => "Armour",
+            EquipSlot::Trinket => "Trinket",
+        }
+    }
+
+    /// Which slot a piece of gear goes in.
+    pub fn for_gear(gear: Gear) -> EquipSlot {
+        match gear {
+            Gear::Dagger | Gear::Spear | Gear::Hammer => EquipSlot::Weapon,
+            Gear::Armour | Gear::Helmet => EquipSlot::Armour,
+            Gear::Ring => EquipSlot::Trinket,
+        }
+    }
+}
+
+/// The stats that gear changes. Used both for what a piece of gear adds
+/// and for the player's stats once everything they have on is added up.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct StatBlock {
+    pub atk: f32,
+    pub def: f32,
+    pub spd: f32,
+}
+
+/// Functions for the StatBlock struct
+impl StatBlock {
+    /// The player's own stats (before any gear).
+    pub fn base(stats: &Stats) -> StatBlock {
+        StatBlock {
+            atk: stats.atk,
+            def: stats.def,
+            spd: stats.spd,
+        }
+    }
+
+    /// What a piece of gear adds to (or takes away from) the player's stats.
+    pub fn for_gear(gear: Gear) -> StatBlock {
+        let (atk, def, spd) = match gear {
+            Gear::Dagger => (1.0, 0.0, 0.0),
+            Gear::Spear => (2.0, 0.0, 0.0),
+            Gear::Hammer => (4.0, 0.0, -0.1),
+            Gear::Armour => (0.0, 2.0, -0.1),
+            Gear::Helmet => (0.0, 1.0, 0.0),
+            Gear::Ring => (0.5, 0.5, 0.1),
+        };
+        StatBlock { atk, def, spd }
+    }
+
+    /// Both sets of stats added together.
+    pub fn plus(self, other: StatBlock) -> StatBlock {
+        StatBlock {
+            atk: self.atk + other.atk,
+            def: self.def + other.def,
+            spd: self.spd + other.spd,
+        }
+    }
+
+    /// Defence with status effects taken into account (never below zero).
+    pub fn current_def(&self, status: &StatusEffects) -> f32 {
+        (self.def + status.def_modifier()).max(0.0)
+    }
+
+    /// Speed with status effects taken into account.
+    pub fn current_spd(&self, status: &StatusEffects) -> f32 {
+        self.spd * status.speed_scale()
+    }
+}
+
+/// The shape and speed of the player's swing, which depends on the weapon.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AttackShape {
+    // size of the AtkBox when facing left or right (turned on its side for up and down)
+    pub width: f32,
+    pub height: f32,
+    // how far in front of the player the AtkBox is
+    pub offset: f32,
+    // drawing back, the AtkBox being out, and following through
+    pub windup: Duration,
+    pub active: Duration,
+    pub recovery: Duration,
+    // rest after a swing before the next one
+    pub cooldown: Duration,
+}
+
+/// Functions for the AttackShape struct
+impl AttackShape {
+    /// How the player swings with a weapon (or the starting sword, a wide arc in front of them).
+    pub fn for_weapon(weapon: Option<Gear>) -> AttackShape {
+        let (width, height, offset, [windup, active, recovery, cooldown]) = match weapon {
+            // short, but quick
+            Some(Gear::Dagger) => (32.0, 48.0, 32.0, [50, 80, 70, 0]),
+            // long and narrow, hits from further away
+            Some(Gear::Spear) => (112.0, 24.0, 72.0, [150, 120, 180, 100]),
+            // big and slow
+            Some(Gear::Hammer) => (56.0, 96.0, 44.0, [250, 150, 300, 150]),
+            _ => (32.0, 80.0, 40.0, [100, 120, 130, 100]),
+        };
+        AttackShape {
+            width,
+            height,
+            offset,
+            windup: Duration::from_millis(windup),
+            active: Duration::from_millis(active),
+            recovery: Duration::from_millis(recovery),
+            cooldown: Duration::from_millis(cooldown),
+        }
+    }
+
+    /// How long a whole swing takes (not counting the cooldown after).
+    pub fn length(&self) -> Duration {
+        self.windup + self.active + self.recovery
+    }
+}
+
+/// Gear the player has on.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Equipment {
+    weapon: Option<Gear>,
+    armour: Option<Gear>,
+    trinket: Option<Gear>,
+}
+
+/// Functions for the Equipment struct
+impl Equipment {
+    /// News up Equipment with nothing on.
+    pub fn new() -> Equipment {
+        Equipment::default()
+    }
+
+    /// What is in a slot.
+    pub fn get(&self, slot: EquipSlot) -> Option<Gear> {
+        match slot {
+            EquipSlot::Weapon => self.weapon,
+            EquipSlot::Armour => self.armour,
+            EquipSlot::Trinket => self.trinket,
+        }
+    }
+
+    fn get_mut(&mut self, slot: EquipSlot) -> &mut Option<Gear> {
+        match slot {
+            EquipSlot::Weapon => &mut self.weapon,
+            EquipSlot::Armour => &mut self.armour,
+            EquipSlot::Trinket => &mut self.trinket,
+        }
+    }
+
+    /// Puts on a piece of gear. Returns whatever was in its slot before.
+    pub fn equip(&mut self, gear: Gear) -> Option<Gear> {
+        self.get_mut(EquipSlot::for_gear(gear)).replace(gear)
+    }
+
+    /// Takes off whatever is in a slot.
+    pub fn unequip(&mut self, slot: EquipSlot) -> Option<Gear> {
+        self.get_mut(slot).take()
+    }
+
+    /// Everything the gear adds up to.
+    pub fn modifiers(&self) -> StatBlock {
+        EquipSlot::ALL
+            .iter()
+            .filter_map(|s| self.get(*s))
+            .map(StatBlock::for_gear)
+            .fold(StatBlock::default(), StatBlock::plus)
+    }
+
+    /// The player's stats with the gear added on.
+    pub fn derive(&self, stats: &Stats) -> StatBlock {
+        StatBlock::base(stats).plus(self.modifiers())
+    }
+
+    /// How the player swings with the weapon they have on.
+    pub fn attack(&self) -> AttackShape {
+        AttackShape::for_weapon(self.weapon)
+    }
+}
+
+#[cfg(test)]
+mod testequipment {
+    use super::*;
+
+    #[test]
+    fn test_equip_swaps_and_derives() {
+        let stats = Stats::new(1, 0, 50.0, 100, 3.0, 0.5, 1.0);
+        let mut eq = Equipment::new();
+        assert_eq!(eq.derive(&stats), StatBlock::base(&stats));
+
+        assert_eq!(eq.equip(Gear::Dagger), None);
+        assert_eq!(eq.equip(Gear::Hammer), Some(Gear::Dagger));
+        eq.equip(Gear::Armour);
+        eq.equip(Gear::Ring);
+
+        let derived = eq.derive(&stats);
+        assert_eq!(derived.atk, 3.0 + 4.0 + 0.5);
+        assert_eq!(derived.def, 0.5 + 2.0 + 0.5);
+        assert!((derived.spd - 0.9).abs() < 0.0001);
+        // base stats are left alone
+        assert_eq!(stats.atk, 3.0);
+
+        assert_eq!(eq.unequip(EquipSlot::Weapon), Some(Gear::Hammer));
+        assert_eq!(eq.get(EquipSlot::Weapon), None);
+    }
+
+    #[test]
+    fn test_weapon_changes_attack() {
+        let sword = AttackShape::for_weapon(None);
+        let spear = AttackShape::for_weapon(Some(Gear::Spear));
+        let dagger = AttackShape::for_weapon(Some(Gear::Dagger));
+        assert!(spear.offset + spear.width / 2.0 > sword.offset + sword.width / 2.0);
+        assert!(dagger.length() + dagger.cooldown < sword.length() + sword.cooldown);
+        assert!(sword.height > spear.height);
+    }
+}
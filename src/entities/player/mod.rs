@@ -5,5 +5,11 @@
 
 // Namespace of current player code
 pub mod atk_box;
+pub mod class;
+pub mod dodge;
+pub mod equipment;
 pub mod playerstruct;
+pub mod progression;
+pub mod spells;
 pub mod stats;
+pub mod swing;
@@ -1,46 +1,103 @@
-use super::super::{Animations, CollideEntity, Direction, DrawableEntity};
-use super::atk_box::AtkBox;
+use super::super::combat::{self, Attacker, DamageType, Defender, Resistances, PLAYER_CRIT_CHANCE};
+use super::super::environment::level::Level;
+use super::super::items::inventory::{Inventory, Item};
+use super::super::items::loot::Pile;
+use super::super::items::registry::{self, Pickup, UseEffect};
+use super::super::knockback::Knockback;
+use super::super::movement::{clear_distance, facing, normalise, slide, unit};
+use super::super::status::{StatusEffect, StatusEffects, StatusKind};
+use super::super::{Animations, CollideEntity, Direction, DrawableEntity, EntityId};
+use super::atk_box::{AtkBox, Hit};
+use super::class::{Ability, Class};
+use super::dodge::{Dodge, DODGE_COST, DODGE_TIME};
+use super::equipment::{Equipment, StatBlock};
+use super::progression::Progression;
+use super::spells::{Spell, Spellbook};
 use super::stats::Stats;
+use super::swing::Swing;
+use crate::input::{self, Action};
 use crate::sprites::*;
-use ggez::event::{KeyCode, KeyMods};
 use ggez::graphics::{Image, Rect};
-use ggez::input::keyboard;
 use ggez::nalgebra as na;
 use ggez::*;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::Duration;
 
 use crate::ui::FloatingText;
 
-/// Constant values for keys used to determine movement
-const KEY_UP: KeyCode = KeyCode::W;
-const KEY_DOWN: KeyCode = KeyCode::S;
-const KEY_RIGHT: KeyCode = KeyCode::D;
-const KEY_LEFT: KeyCode = KeyCode::A;
+/// How fast the player walks at spd 1.0 (pixels per second).
+const WALK_SPEED: f32 = 150.0;
+/// Running is this many times faster than walking.
+const RUN_SCALE: f32 = 2.0;
+/// Fleet footed players run this many times faster again.
+const FLEET_SCALE: f32 = 1.5;
+
+/// Fireballs fly this fast (pixels per second) for this long, and hit this much harder than a swing.
+const FIREBALL_SPEED: f32 = 360.0;
+const FIREBALL_LIFE: Duration = Duration::from_millis(1500);
+const FIREBALL_POWER: f32 = 1.5;
+/// Frost nova hits everything in a square this big around the player (but not very hard),
+/// and stays out this long so it can be seen.
+const NOVA_SIZE: f32 = 192.0;
+const NOVA_POWER: f32 = 0.5;
+const NOVA_LIFE: Duration = Duration::from_millis(300);
+/// Hp a heal gives back.
+const HEAL_AMOUNT: f32 = 20.0;
+/// Furthest a blink can take the player (pixels).
+const BLINK_DISTANCE: f32 = 160.0;
+/// How often along the way a blink checks for walls (pixels).
+const BLINK_STEP: f32 = 16.0;
+/// Bombs hit everything in a square this big around the player (and hit hard).
+const BOMB_SIZE: f32 = 256.0;
+const BOMB_POWER: f32 = 3.0;
+const BOMB_LIFE: Duration = Duration::from_millis(400);
+
+/// Faded copies of the player left behind while rolling, and how far apart they are (pixels).
+const AFTERIMAGES: usize = 3;
+const AFTERIMAGE_GAP: f32 = 14.0;
+
+/// How long the game freezes when a hit lands (makes hits feel heavier).
+const HIT_STOP: Duration = Duration::from_millis(60);
 
 /// The player struct
 pub struct Player {
     pub x: f32,
     pub y: f32,
+    pub class: Class,
     pub stats: Stats,
     pub sprite: HashMap<(Animations, Direction), AnimatedSprite>,
     pub animation: (Animations, Direction),
     pub atk_box: Option<AtkBox>,
-    pub attacking: bool,
-    pub atk_cooldown: Duration,
+    pub swing: Swing,
     pub invulnerable: Duration,
     pub direction: Direction,
-    pub macguffin: bool,
+    // where the level started (scrolls of teleport go back here)
+    pub home: (f32, f32),
+    // shared with the inventory screen
+    pub inventory: Rc<RefCell<Inventory>>,
+    // shared with the character screen
+    pub equipment: Rc<RefCell<Equipment>>,
     pub cooldown: Duration,
+    pub knockback: Knockback,
+    pub hit_stop: Duration,
+    pub status: StatusEffects,
+    pub spellbook: Spellbook,
+    pub dodge: Dodge,
+    // fireballs still flying (and frost novas and bombs still going off)
+    pub missiles: Vec<AtkBox>,
     floating_text: Vec<FloatingText>,
+    // undying players only get to cheat death once
+    undying_used: bool,
 }
 
 /// Functions for the Player
 impl Player {
-    /// News up a new player struct.
-    pub fn new(ctx: &mut Context, chosen_player: String) -> Player {
+    /// News up a new player struct of a class (which decides the sprite, stats, spells and starting gear).
+    pub fn new(ctx: &mut Context, class: Class) -> Player {
         let mut sprite = HashMap::new();
-        let sheet = Image::new(ctx, chosen_player).unwrap();
+        let sheet = Image::new(ctx, class.sprite()).unwrap();
         let builder = AnimatedBuilder::new(&sheet);
         // standing
         sprite.insert(
@@ -92,6 +149,31 @@ impl Player {
                 .create_animated(Rect::new(64f32, 192f32, 64f32, 64f32), 8usize)
                 .unwrap(),
         );
+        // rolling (the walk cycle played once and quickly, drawn tumbling with afterimages behind it)
+        sprite.insert(
+            (Animations::Roll, Direction::Up),
+            builder
+                .create_animated_once(Rect::new(64f32, 0f32, 64f32, 64f32), 8usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Roll, Direction::Left),
+            builder
+                .create_animated_once(Rect::new(64f32, 64f32, 64f32, 64f32), 8usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Roll, Direction::Down),
+            builder
+                .create_animated_once(Rect::new(64f32, 128f32, 64f32, 64f32), 8usize)
+                .unwrap(),
+        );
+        sprite.insert(
+            (Animations::Roll, Direction::Right),
+            builder
+                .create_animated_once(Rect::new(64f32, 192f32, 64f32, 64f32), 8usize)
+                .unwrap(),
+        );
         // casting
         sprite.insert(
             (Animations::Cast, Direction::Up),
@@ -152,107 +234,182 @@ impl Player {
 
         let floating_text = Vec::new();
 
+        let mut stats = class.starting_stats();
+        stats.progression = Progression::load(ctx, "/progression.txt");
+
+        let mut equipment = Equipment::new();
+        for gear in class.starting_gear() {
+            equipment.equip(gear);
+        }
+
         Player {
             x: 10.0,
             y: 10.0,
-            stats: Stats::new(1, 0, 50.0, 150, 3.0, 0.5, 1.0),
+            class,
+            stats,
             sprite,
             animation: (Animations::Walking, Direction::Right),
             atk_box: None,
-            attacking: false,
-            atk_cooldown: Duration::new(0u64, 0u32),
+            swing: Swing::new(),
             invulnerable: Duration::new(0u64, 0u32),
             direction: Direction::Right, // Starting direction?
-            macguffin: false,
+            home: (10.0, 10.0),
+            inventory: Rc::new(RefCell::new(Inventory::new())),
+            equipment: Rc::new(RefCell::new(equipment)),
             cooldown: Duration::new(1u64, 0u32),
+            knockback: Knockback::new(),
+            hit_stop: Duration::new(0u64, 0u32),
+            status: StatusEffects::new(),
+            spellbook: Spellbook::knowing(class.spells()),
+            dodge: Dodge::new(),
+            missiles: Vec::new(),
             floating_text,
+            undying_used: false,
         }
     }
 
-    /// Increase or decrease `position_x` by 2.5, or by 5.0 if Shift is held.
+    /// Moves the player `WALK_SPEED` pixels per second (scaled by spd), twice that if Run is held.
     /// Checks for floating text, and animation and attacking.
-    pub fn update(&mut self, ctx: &mut Context, delta: Duration) {
+    pub fn update(&mut self, ctx: &mut Context, delta: Duration, level: &Level) {
         // private function to return correct speed
-        fn move_increment(ctx: &mut Context) -> f32 {
-            if keyboard::is_mod_active(ctx, KeyMods::SHIFT) {
-                return 5.0;
+        fn move_increment(ctx: &Context, ability: Ability) -> f32 {
+            if input::is_pressed(ctx, Action::Run) {
+                if ability == Ability::FleetFooted {
+                    return WALK_SPEED * RUN_SCALE * FLEET_SCALE;
+                }
+                return WALK_SPEED * RUN_SCALE;
             }
-            2.5
+            WALK_SPEED
         }
 
-        // clear attack box in case player is not attacking any more
-        self.atk_box = None;
+        // the swing moves through its phases, and its AtkBox only stays out while it is active
+        self.swing.update(delta);
+        if !self.swing.active() {
+            self.atk_box = None;
+        }
 
         // cool down invulnerable of player
         if self.invulnerable() {
             self.invulnerable += delta;
         }
 
-        if !self.atk_cooldown() {
-            self.atk_cooldown += delta;
+        // poison and burn tick away at hp (armour doesn't help)
+        if let Some((dmg, kind)) = self.status.update(delta) {
+            if self.stats.hp > 0f32 {
+                self.stats.hp = (self.stats.hp - dmg).max(0f32);
+                self.floating_text.push(FloatingText::new(
+                    ctx,
+                    self.x,
+                    self.y,
+                    dmg.to_string(),
+                    kind.text_color(),
+                ));
+                self.cheat_death(ctx);
+            }
         }
+        // how far to move this update (slowed or stunned players don't move as far)
+        let speed = self.derived_stats().current_spd(&self.status) * delta.as_secs_f32();
+        // dodge key pressed since last update (dropped if the player can't roll right now)
+        let roll = self.dodge.take_request();
 
-        // dead
+        // dead (the death itself plays out in update_dying)
         if self.stats.hp <= 0f32 {
+            self.spellbook.interrupt();
+            self.swing.interrupt();
             self.animation = (Animations::Die, Direction::Down);
         }
-        // casting
-        else if keyboard::is_key_pressed(ctx, KeyCode::Q)
-            && self.stats.hp > 0f32
-            && self.stats.mp > 0
+        // stunned (which breaks any spell being cast)
+        else if self.status.has(StatusKind::Stun) {
+            self.spellbook.interrupt();
+            self.animation.0 = Animations::Stand;
+        }
+        // dodge rolling (can't be hurt, walls stop the roll)
+        else if self.dodge.active() || (roll && self.start_dodge(ctx)) {
+            let (dx, dy) = self.dodge.step(delta);
+            let (mx, my) = slide(self.get_hitbox(), dx, dy, |r| level.collision(r));
+            if (mx, my) == (0.0, 0.0) {
+                self.dodge.stop();
+            }
+            self.x += mx;
+            self.y += my;
+            self.animation = (Animations::Roll, self.direction);
+        }
+        // in the middle of a swing (the AtkBox comes out for the active frames)
+        else if self.swing.swinging() {
+            if self.swing.active() && self.atk_box.is_none() {
+                let shape = self.swing.shape();
+                self.atk_box = Some(AtkBox::new(
+                    ctx,
+                    self.x,
+                    self.y,
+                    shape.width,
+                    shape.height,
+                    &self.direction,
+                    shape.offset,
+                ));
+            }
+            self.animation.0 = Animations::Slash;
+        }
+        // casting the selected spell (stand still until it goes off)
+        else if self.spellbook.casting()
+            || (input::is_pressed(ctx, Action::Cast) && self.start_cast())
         {
-            self.atk_box = Some(AtkBox::new(
-                ctx,
-                self.x,
-                self.y,
-                64.0,
-                100.0,
-                &self.direction,
-                40.0,
-            ));
             self.animation.0 = Animations::Cast;
-            self.stats.mp -= 1;
         }
-        // slashing
-        else if keyboard::is_key_pressed(ctx, KeyCode::Space) && self.stats.hp > 0f32 {
-            //&& self.atk_cooldown() {
-            self.atk_box = Some(AtkBox::new(
-                ctx,
-                self.x,
-                self.y,
-                32.0,
-                80.0,
-                &self.direction,
-                40.0,
-            ));
-            self.animation.0 = Animations::Slash;
-            self.atk_cooldown = Duration::new(0u64, 0u32);
-        }
-        // walking animations
-        else if keyboard::is_key_pressed(ctx, KEY_RIGHT) {
-            self.x += move_increment(ctx);
-            self.animation = (Animations::Walking, Direction::Right);
-            self.direction = Direction::Right;
-        } else if keyboard::is_key_pressed(ctx, KEY_LEFT) {
-            self.x -= move_increment(ctx);
-            self.animation = (Animations::Walking, Direction::Left);
-            self.direction = Direction::Left;
-        } else if keyboard::is_key_pressed(ctx, KEY_UP) {
-            self.y -= move_increment(ctx);
-            self.animation = (Animations::Walking, Direction::Up);
-            self.direction = Direction::Up;
-        } else if keyboard::is_key_pressed(ctx, KEY_DOWN) {
-            self.y += move_increment(ctx);
-            self.animation = (Animations::Walking, Direction::Down);
-            self.direction = Direction::Down;
-        }
-        // standing animation
+        // starting a swing (the weapon decides its shape and timing, the slash animation is played over the whole swing)
+        else if input::is_pressed(ctx, Action::Attack) && self.swing.ready() {
+            let shape = self.equipment.borrow().attack();
+            self.swing.start(shape);
+            self.sprite
+                .get_mut(&(Animations::Slash, self.direction))
+                .unwrap()
+                .play_over(shape.length());
+            self.animation = (Animations::Slash, self.direction);
+        }
+        // walking (any mix of directions, diagonals aren't faster and walls are slid along)
         else {
-            self.animation.0 = Animations::Stand;
+            let held = |action| {
+                if input::is_pressed(ctx, action) {
+                    1.0
+                } else {
+                    0.0
+                }
+            };
+            let (dx, dy) = normalise(
+                held(Action::Right) - held(Action::Left),
+                held(Action::Down) - held(Action::Up),
+            );
+            if let Some(direction) = facing(dx, dy) {
+                let step = move_increment(ctx, self.class.ability()) * speed;
+                let (mx, my) = slide(self.get_hitbox(), dx * step, dy * step, |r| {
+                    level.collision(r)
+                });
+                self.x += mx;
+                self.y += my;
+                self.animation = (Animations::Walking, direction);
+                self.direction = direction;
+            }
+            // standing animation
+            else {
+                self.animation.0 = Animations::Stand;
+            }
         }
 
         self.sprite.get_mut(&self.animation).unwrap().animate(delta);
 
+        // mp and stamina come back over time, and spells go off once they have been cast
+        self.stats.regen_mp(delta);
+        self.stats.regen_stamina(delta);
+        if let Some(spell) = self.spellbook.update(delta) {
+            self.cast(ctx, spell, level);
+        }
+
+        // fireballs keep flying (and novas and bombs fade away)
+        self.missiles
+            .iter_mut()
+            .for_each(|m| m.update(delta, level));
+        self.missiles.retain(|m| m.live());
+
         self.floating_text.retain(|t| t.live());
         self.floating_text.iter_mut().for_each(|t| t.update(delta));
 
@@ -262,14 +419,73 @@ impl Player {
         }
     }
 
-    /// Returns if player should be able to take damage
+    /// Returns true once the player has run out of hp.
+    pub fn dead(&self) -> bool {
+        self.stats.hp <= 0f32
+    }
+
+    /// Plays the die animation (nothing else happens while dying).
+    /// Returns true once the animation has finished.
+    pub fn update_dying(&mut self, delta: Duration) -> bool {
+        self.animation = (Animations::Die, Direction::Down);
+        self.atk_box = None;
+        self.missiles.clear();
+        let die = self.sprite.get_mut(&self.animation).unwrap();
+        die.animate(delta);
+
+        self.floating_text.retain(|t| t.live());
+        self.floating_text.iter_mut().for_each(|t| t.update(delta));
+
+        self.sprite.get(&self.animation).unwrap().finished()
+    }
+
+    /// Returns if player should be able to take damage (not just after being hit or while rolling)
     fn invulnerable(&self) -> bool {
-        self.invulnerable < Duration::from_millis(250u64)
+        self.invulnerable < Duration::from_millis(250u64) || self.dodge.active()
+    }
+
+    /// Starts a dodge roll the way the player is trying to move (or facing) if there is enough stamina.
+    /// Rolling breaks any spell being cast.
+    fn start_dodge(&mut self, ctx: &mut Context) -> bool {
+        if !self.stats.spend_stamina(DODGE_COST) {
+            return false;
+        }
+        let held = |action| {
+            if input::is_pressed(ctx, action) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        let (dx, dy) = match normalise(
+            held(Action::Right) - held(Action::Left),
+            held(Action::Down) - held(Action::Up),
+        ) {
+            (0.0, 0.0) => unit(self.direction),
+            held => held,
+        };
+        if let Some(direction) = facing(dx, dy) {
+            self.direction = direction;
+        }
+        self.dodge.start(dx, dy);
+        self.spellbook.interrupt();
+        self.swing.interrupt();
+        self.atk_box = None;
+        self.sprite
+            .get_mut(&(Animations::Roll, self.direction))
+            .unwrap()
+            .play_over(DODGE_TIME);
+        true
+    }
+
+    /// The player's stats with their gear added on (base stats are left alone).
+    pub fn derived_stats(&self) -> StatBlock {
+        self.equipment.borrow().derive(&self.stats)
     }
 
-    /// This is still in the works
-    fn atk_cooldown(&self) -> bool {
-        self.atk_cooldown > Duration::from_millis(350u64)
+    /// The player's side of a hit that landed (for combat to work out the damage).
+    pub fn attacker(&self, hit: Hit) -> Attacker {
+        Attacker::new(self.derived_stats().atk * hit.power, hit.kind).with_crit(PLAYER_CRIT_CHANCE)
     }
 
     /// Player pick_up item text.
@@ -281,6 +497,122 @@ impl Player {
         }
     }
 
+    /// Gains experience (ie for killing an enemy) and lets the player know about any level ups.
+    pub fn gain_exp(&mut self, ctx: &mut Context, exp: u32) {
+        if self.stats.check_for_level_up(exp) > 0 {
+            self.floating_text.push(FloatingText::new(
+                ctx,
+                self.x,
+                self.y - 20.0,
+                "Level Up!".to_string(),
+                "Yellow",
+            ));
+        }
+    }
+
+    /// Collects a piece of loot. Returns false if there is no room for it (and it should stay on the ground).
+    pub fn collect(&mut self, ctx: &mut Context, pile: Pile) -> bool {
+        match registry::lookup(pile.item).pickup {
+            Pickup::Purse => self.inventory.borrow_mut().find_gold(pile.count),
+            // the game deals with these (see MainState)
+            Pickup::Decoy => return false,
+            Pickup::Carry | Pickup::Quest => {
+                let mut inventory = self.inventory.borrow_mut();
+                for taken in 0..pile.count {
+                    if !inventory.add(pile.item) {
+                        // whatever doesn't fit stays on the ground
+                        for _ in 0..taken {
+                            inventory.remove(pile.item);
+                        }
+                        drop(inventory);
+                        self.pick_up(ctx, "Inventory Full".to_string());
+                        return false;
+                    }
+                }
+            }
+        }
+        self.pick_up(ctx, pile.describe());
+        true
+    }
+
+    /// Uses an item taken out of the inventory.
+    /// Returns false if it can't be used right now (and should go back in the inventory).
+    pub fn use_item(&mut self, ctx: &mut Context, item: Item) -> bool {
+        if self.dead() {
+            return false;
+        }
+        match registry::lookup(item).effect {
+            Some(UseEffect::Heal) => {
+                if self.stats.hp >= self.stats.max_hp {
+                    return false;
+                }
+                self.pick_up(ctx, (self.stats.max_hp - self.stats.hp).to_string());
+                self.stats.hp = self.stats.max_hp;
+                true
+            }
+            Some(UseEffect::RestoreMp) => {
+                if self.stats.mp >= self.stats.max_mp {
+                    return false;
+                }
+                self.pick_up(ctx, format!("{} mp", self.stats.max_mp - self.stats.mp));
+                self.stats.mp = self.stats.max_mp;
+                true
+            }
+            Some(UseEffect::Haste) => {
+                self.status.apply(StatusEffect::new(StatusKind::Haste));
+                self.pick_up(ctx, "Haste!".to_string());
+                true
+            }
+            // goes off around the player like a frost nova, but much harder and on fire
+            Some(UseEffect::Explode) => {
+                self.missiles.push(
+                    AtkBox::new(
+                        ctx,
+                        self.x,
+                        self.y,
+                        BOMB_SIZE,
+                        BOMB_SIZE,
+                        &self.direction,
+                        0.0,
+                    )
+                    .with_power(BOMB_POWER)
+                    .with_kind(DamageType::Fire)
+                    .with_effect(StatusKind::Burn)
+                    .with_color(graphics::Color::new(1.0, 0.5, 0.0, 0.5))
+                    .lingering(BOMB_LIFE),
+                );
+                true
+            }
+            Some(UseEffect::Teleport) => {
+                self.move_location(self.home.0, self.home.1);
+                self.knockback = Knockback::new();
+                self.pick_up(ctx, "Teleported".to_string());
+                true
+            }
+            // putting gear on takes off whatever was in its slot
+            Some(UseEffect::Equip) => {
+                if let Item::Equipment(gear) = item {
+                    if let Some(old) = self.equipment.borrow_mut().equip(gear) {
+                        self.inventory.borrow_mut().add(Item::Equipment(old));
+                    }
+                    self.pick_up(ctx, format!("Equipped {}", item.name()));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Uses whatever is in a hotbar slot.
+    pub fn use_hotbar(&mut self, index: usize) {
+        self.inventory.borrow_mut().use_slot(index);
+    }
+
+    /// Returns true once the MacGuffin has been picked up.
+    pub fn has_macguffin(&self) -> bool {
+        self.inventory.borrow().has(Item::MacGuffin)
+    }
+
     /// Checks if the pickup text cooldown is over
     fn pick_up_cooldown(&self) -> bool {
         self.cooldown < Duration::from_millis(250u64)
@@ -293,39 +625,187 @@ impl Player {
     }
 
     /// When the player takes damage, check how much they can and if they can.
-    pub fn take_dmg(&mut self, ctx: &mut Context, dmg_to_take: f32) {
-        let true_dmg = dmg_to_take - self.stats.def;
+    pub fn take_dmg(&mut self, ctx: &mut Context, attacker: Attacker) {
         if !self.invulnerable() {
-            if true_dmg > 0.0 {
-                self.stats.hp -= true_dmg;
+            let defender = Defender::new(
+                self.derived_stats().current_def(&self.status),
+                Resistances::NONE,
+            );
+            let damage = combat::roll(attacker, defender);
+            if !damage.blocked {
+                self.stats.hp -= damage.amount;
                 if self.stats.hp < 0f32 {
                     self.stats.hp = 0f32;
                 }
+                self.cheat_death(ctx);
                 self.invulnerable = Duration::new(0u64, 0u32);
+                self.start_hit_stop();
+            }
+            self.floating_text
+                .push(FloatingText::damage(ctx, self.x, self.y, &damage));
+        }
+    }
+
+    /// Undying players are left with 1 hp the first time they would have died.
+    fn cheat_death(&mut self, ctx: &mut Context) {
+        if self.stats.hp <= 0f32 && self.class.ability() == Ability::Undying && !self.undying_used {
+            self.undying_used = true;
+            self.stats.hp = 1f32;
+            self.floating_text.push(FloatingText::new(
+                ctx,
+                self.x,
+                self.y - 20.0,
+                "Undying!".to_string(),
+                "Yellow",
+            ));
+        }
+    }
+
+    /// Gets hit by something at `from`: takes damage and gets knocked away from it.
+    pub fn take_hit(
+        &mut self,
+        ctx: &mut Context,
+        dmg_to_take: f32,
+        from: (f32, f32),
+        strength: f32,
+    ) {
+        if !self.invulnerable() {
+            self.knockback.hit(from, (self.x, self.y), strength);
+        }
+        self.take_dmg(ctx, Attacker::new(dmg_to_take, DamageType::Physical));
+    }
+
+    /// Suffers from a status effect (following its stacking rules).
+    pub fn apply_status(&mut self, kind: StatusKind) {
+        if self.stats.hp > 0f32 && !self.dodge.active() {
+            self.status.apply(StatusEffect::new(kind));
+        }
+    }
+
+    /// Moves the player along with any knockback, stopping at walls one axis at a time.
+    pub fn resolve_knockback(&mut self, level: &Level, delta: Duration) {
+        let (dx, dy) = self
+            .knockback
+            .resolve(self.get_hitbox(), level, delta.as_secs_f32());
+        self.x += dx;
+        self.y += dy;
+    }
+
+    /// Freezes the action for a moment because a hit landed.
+    pub fn start_hit_stop(&mut self) {
+        self.hit_stop = HIT_STOP;
+    }
+
+    /// Counts down the hit stop. Returns true while the game should stay frozen.
+    pub fn hit_stopped(&mut self, delta: Duration) -> bool {
+        if self.hit_stop > delta {
+            self.hit_stop -= delta;
+            true
+        } else {
+            self.hit_stop = Duration::new(0u64, 0u32);
+            false
+        }
+    }
+
+    /// Starts casting the selected spell if it is ready and there is enough mp.
+    /// The cast animation is played over the spell's cast time.
+    fn start_cast(&mut self) -> bool {
+        let spell = self.spellbook.selected_spell();
+        if !self.spellbook.begin(spell, &mut self.stats.mp) {
+            return false;
+        }
+        self.sprite
+            .get_mut(&(Animations::Cast, self.animation.1))
+            .unwrap()
+            .play_over(spell.cast_time());
+        true
+    }
+
+    /// Makes a spell go off once it has been cast.
+    fn cast(&mut self, ctx: &mut Context, spell: Spell, level: &Level) {
+        let (dx, dy) = unit(self.direction);
+        match spell {
+            Spell::Fireball => {
+                let fireball = AtkBox::new(ctx, self.x, self.y, 16.0, 16.0, &self.direction, 32.0)
+                    .with_power(FIREBALL_POWER)
+                    .with_effect(StatusKind::Burn)
+                    .with_kind(DamageType::Fire)
+                    .with_color(graphics::Color::from_rgb(255u8, 140u8, 0u8))
+                    .with_velocity((dx * FIREBALL_SPEED, dy * FIREBALL_SPEED), FIREBALL_LIFE);
+                self.missiles.push(fireball);
+            }
+            Spell::FrostNova => {
+                self.missiles.push(
+                    AtkBox::new(
+                        ctx,
+                        self.x,
+                        self.y,
+                        NOVA_SIZE,
+                        NOVA_SIZE,
+                        &self.direction,
+                        0.0,
+                    )
+                    .with_power(NOVA_POWER)
+                    .with_effect(StatusKind::Slow)
+                    .with_kind(DamageType::Frost)
+                    .with_color(graphics::Color::new(0.5, 0.8, 1.0, 0.5))
+                    .lingering(NOVA_LIFE),
+                );
+            }
+            Spell::Heal => {
+                let healed = HEAL_AMOUNT.min(self.stats.max_hp - self.stats.hp);
+                self.stats.hp += healed;
                 self.floating_text.push(FloatingText::new(
                     ctx,
                     self.x,
                     self.y,
-                    true_dmg.to_string(),
-                    "Red",
-                ));
-            } else {
-                self.floating_text.push(FloatingText::new(
-                    ctx,
-                    self.x,
-                    self.y,
-                    "Blocked".to_string(),
-                    "Blue",
+                    format!("+{}", healed),
+                    "Green",
                 ));
             }
+            Spell::Blink => {
+                // as far as possible without passing through walls, doors or gates
+                let dist =
+                    clear_distance(self.get_hitbox(), dx, dy, BLINK_DISTANCE, BLINK_STEP, |r| {
+                        r.collision(level)
+                    });
+                self.x += dx * dist;
+                self.y += dy * dist;
+            }
         }
     }
 
-    /// Calls the atk_box struct draw function
+    /// Checks if any of the player's attacks (a swing, a spell, a bomb or a fireball) hit `target` (known by `id`).
+    /// Fireballs are used up by whatever they hit first; swings, novas and bombs hit each target once.
+    pub fn attack_hitting(&mut self, target: &dyn CollideEntity, id: EntityId) -> Option<Hit> {
+        if let Some(atk) = &self.atk_box {
+            if target.collision(atk) && self.swing.first_hit(id) {
+                return Some(atk.hit());
+            }
+        }
+        for m in self.missiles.iter_mut().filter(|m| m.live()) {
+            if !target.collision(&*m) {
+                continue;
+            }
+            if !m.lingers {
+                m.spent = true;
+                return Some(m.hit());
+            }
+            if m.first_hit(id) {
+                return Some(m.hit());
+            }
+        }
+        None
+    }
+
+    /// Calls the atk_box struct draw function (and draws any fireballs)
     pub fn draw_weapon(&self, ctx: &mut Context) {
         if let Some(atk) = &self.atk_box {
             atk.draw(ctx).expect("Failed to draw attack.");
         }
+        for m in &self.missiles {
+            m.draw(ctx).expect("Failed to draw spell.");
+        }
     }
 }
 
@@ -334,9 +814,35 @@ impl DrawableEntity for Player {
     /// Draws the weapon, the player, and the floating text.
     fn draw(&self, ctx: &mut Context) -> GameResult {
         self.draw_weapon(ctx);
+        // see-through while rolling (can't be hurt)
+        let mut tint = self.status.tint();
+        let mut spin = 0.0;
+        if self.dodge.active() {
+            tint.a = 0.5;
+            // one full tumble head over heels over the roll, the way it is going
+            let way = if self.dodge.dx < 0.0 { -1.0 } else { 1.0 };
+            spin = self.dodge.progress() * 2.0 * std::f32::consts::PI * way;
+            // fading copies trail behind
+            for i in (1..=AFTERIMAGES).rev() {
+                let back = i as f32 * AFTERIMAGE_GAP;
+                let mut faded = tint;
+                faded.a = 0.3 / i as f32;
+                let dp = graphics::DrawParam::default()
+                    .offset(nalgebra::Point2::new(0.5, 0.5))
+                    .dest(na::Point2::new(
+                        self.x - self.dodge.dx * back,
+                        self.y - self.dodge.dy * back,
+                    ))
+                    .rotation(spin - 0.5 * i as f32 * way)
+                    .color(faded);
+                graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
+            }
+        }
         let dp = graphics::DrawParam::default()
             .offset(nalgebra::Point2::new(0.5, 0.5))
-            .dest(na::Point2::new(self.x, self.y));
+            .dest(na::Point2::new(self.x, self.y))
+            .rotation(spin)
+            .color(tint);
         graphics::draw(ctx, self.sprite.get(&self.animation).unwrap(), dp)?;
 
         self.floating_text.iter().for_each(|t| t.draw(ctx));
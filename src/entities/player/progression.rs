@@ -0,0 +1,182 @@
+use ggez::Context;
+use std::io::Read;
+
+/// What it takes to get past a level and what the player gets for reaching it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LevelGain {
+    pub xp: u32,
+    pub hp: f32,
+    pub mp: u32,
+    pub atk: f32,
+    pub def: f32,
+    pub spd: f32,
+}
+
+/// Functions for the LevelGain struct
+impl LevelGain {
+    /// The stat gains scaled by a class's growth (experience needed is left alone).
+    pub fn scaled(self, growth: &Growth) -> LevelGain {
+        LevelGain {
+            xp: self.xp,
+            hp: self.hp * growth.hp,
+            mp: (self.mp as f32 * growth.mp).round() as u32,
+            atk: self.atk * growth.atk,
+            def: self.def * growth.def,
+            spd: self.spd * growth.spd,
+        }
+    }
+}
+
+/// How quickly each stat grows compared to the progression table (1.0 is as listed).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Growth {
+    pub hp: f32,
+    pub mp: f32,
+    pub atk: f32,
+    pub def: f32,
+    pub spd: f32,
+}
+
+/// Grows exactly as the progression table says.
+impl Default for Growth {
+    fn default() -> Growth {
+        Growth {
+            hp: 1.0,
+            mp: 1.0,
+            atk: 1.0,
+            def: 1.0,
+            spd: 1.0,
+        }
+    }
+}
+
+/// Used when a level isn't in the table at all (ie nothing was loaded).
+const DEFAULT_GAIN: LevelGain = LevelGain {
+    xp: 10,
+    hp: 5.0,
+    mp: 0,
+    atk: 0.0,
+    def: 0.0,
+    spd: 0.0,
+};
+
+/// The experience curve and stat gains for each level.
+/// Loaded from a data file with lines of `level xp hp mp atk def spd` (# starts a comment).
+#[derive(Clone, Default, Debug)]
+pub struct Progression {
+    levels: Vec<(u32, LevelGain)>,
+}
+
+/// Functions for the Progression struct
+impl Progression {
+    /// Reads a progression table out of text. Lines that can't be read are skipped.
+    pub fn parse(text: &str) -> Progression {
+        let mut levels: Vec<(u32, LevelGain)> = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.split('#').next()?;
+                let mut parse = line.split_whitespace();
+                let lv = parse.next()?.parse::<u32>().ok()?;
+                let gain = LevelGain {
+                    xp: parse.next()?.parse::<u32>().ok()?,
+                    hp: parse.next()?.parse::<f32>().ok()?,
+                    mp: parse.next()?.parse::<u32>().ok()?,
+                    atk: parse.next()?.parse::<f32>().ok()?,
+                    def: parse.next()?.parse::<f32>().ok()?,
+                    spd: parse.next()?.parse::<f32>().ok()?,
+                };
+                Some((lv, gain))
+            })
+            .collect();
+        levels.sort_by_key(|(lv, _)| *lv);
+        Progression { levels }
+    }
+
+    /// Loads the progression table from a file (empty table if it can't be found).
+    pub fn load(ctx: &mut Context, path: &str) -> Progression {
+        if !ggez::filesystem::exists(ctx, path) {
+            println!("unable to find {} using default progression", path);
+            return Progression::default();
+        }
+        let mut file = ggez::filesystem::open(ctx, path).unwrap();
+        let mut text = String::new();
+        file.read_to_string(&mut text).unwrap();
+        Progression::parse(&text)
+    }
+
+    /// The row for a level (levels past the end of the table use the last row).
+    fn gain(&self, lv: u32) -> LevelGain {
+        self.levels
+            .iter()
+            .rev()
+            .find(|(l, _)| *l <= lv)
+            .map_or(DEFAULT_GAIN, |(_, g)| *g)
+    }
+
+    /// Experience needed to get from lv to the next level.
+    pub fn xp_to_next(&self, lv: u32) -> u32 {
+        // never zero, or a single kill could level up forever
+        self.gain(lv).xp.max(1)
+    }
+
+    /// Stats gained on reaching lv.
+    pub fn gains(&self, lv: u32) -> LevelGain {
+        self.gain(lv)
+    }
+}
+
+#[cfg(test)]
+mod testprogression {
+    use super::super::stats::Stats;
+    use super::*;
+
+    const TABLE: &str = "# level xp hp mp atk def spd\n\
+                         1 10 0 0 0 0 0\n\
+                         2 20 5 10 0.5 0 0 # comment\n\
+                         not a line\n\
+                         3 30 5 0 1 0.5 0\n";
+
+    #[test]
+    fn test_parse_and_past_the_end() {
+        let p = Progression::parse(TABLE);
+        assert_eq!(p.xp_to_next(1), 10);
+        assert_eq!(p.xp_to_next(2), 20);
+        assert_eq!(p.gains(2).mp, 10);
+        assert_eq!(p.xp_to_next(7), 30);
+        assert_eq!(p.gains(7).def, 0.5);
+        assert_eq!(Progression::default().xp_to_next(4), 10);
+    }
+
+    #[test]
+    fn test_overflow_and_multi_level() {
+        let mut stats = Stats::new(1, 0, 50.0, 100, 3.0, 0.5, 1.0);
+        stats.progression = Progression::parse(TABLE);
+
+        // 12 xp is one level with 2 left over
+        assert_eq!(stats.check_for_level_up(12), 1);
+        assert_eq!((stats.lv, stats.exp), (2, 2));
+        assert_eq!(stats.max_hp, 55.0);
+        assert_eq!(stats.max_mp, 110);
+
+        // 18 + 30 + 3 jumps two levels with 3 left over
+        assert_eq!(stats.check_for_level_up(51), 2);
+        assert_eq!((stats.lv, stats.exp), (4, 3));
+        assert_eq!(stats.atk, 3.0 + 0.5 + 1.0 + 1.0);
+    }
+
+    #[test]
+    fn test_growth_scales_gains() {
+        let mut stats = Stats::new(1, 0, 50.0, 100, 3.0, 0.5, 1.0);
+        stats.progression = Progression::parse(TABLE);
+        stats.growth = Growth {
+            hp: 2.0,
+            mp: 0.5,
+            ..Growth::default()
+        };
+
+        stats.check_for_level_up(10);
+        assert_eq!(stats.max_hp, 60.0);
+        assert_eq!(stats.max_mp, 105);
+        assert_eq!(stats.atk, 3.5);
+    }
+}
@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+/// Spells the player can cast.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Spell {
+    Fireball,
+    FrostNova,
+    Heal,
+    Blink,
+}
+
+/// Functions for the Spell enum
+impl Spell {
+    /// Every spell in the spellbook (in the order of the spell keys).
+    pub const ALL: [Spell; 4] = [Spell::Fireball, Spell::FrostNova, Spell::Heal, Spell::Blink];
+
+    /// Name shown in the UI.
+    pub fn name(self) -> &'static str {
+        match self {
+            Spell::Fireball => "Fireball",
+            Spell::FrostNova => "Frost Nova",
+            Spell::Heal => "Heal",
+            Spell::Blink => "Blink",
+        }
+    }
+
+    /// How much mp the spell takes.
+    pub fn cost(self) -> u32 {
+        match self {
+            Spell::Fireball => 10,
+            Spell::FrostNova => 20,
+            Spell::Heal => 25,
+            Spell::Blink => 15,
+        }
+    }
+
+    /// How long before the spell can be cast again.
+    pub fn cooldown(self) -> Duration {
+        match self {
+            Spell::Fireball => Duration::from_millis(800),
+            Spell::FrostNova => Duration::from_millis(6000),
+            Spell::Heal => Duration::from_millis(8000),
+            Spell::Blink => Duration::from_millis(3000),
+        }
+    }
+
+    /// How long the player stands casting before the spell goes off
+    /// (the cast animation is played over this long).
+    pub fn cast_time(self) -> Duration {
+        match self {
+            Spell::Fireball => Duration::from_millis(400),
+            Spell::FrostNova => Duration::from_millis(600),
+            Spell::Heal => Duration::from_millis(800),
+            Spell::Blink => Duration::from_millis(200),
+        }
+    }
+}
+
+/// The spells the player knows, which one is selected, cooldowns and the spell being cast.
+pub struct Spellbook {
+    // spells that can be picked (in the order of the spell keys)
+    known: Vec<Spell>,
+    // index into known
+    pub selected: usize,
+    // time left before each spell in Spell::ALL is ready
+    cooldowns: Vec<Duration>,
+    // spell being cast and how long it has been going
+    casting: Option<(Spell, Duration)>,
+}
+
+/// Functions for the Spellbook struct
+impl Spellbook {
+    /// News up a Spellbook knowing every spell, with the first spell selected and everything ready.
+    pub fn new() -> Spellbook {
+        Spellbook::knowing(Spell::ALL.to_vec())
+    }
+
+    /// News up a Spellbook that only knows some spells (ie the ones a class starts with).
+    pub fn knowing(known: Vec<Spell>) -> Spellbook {
+        Spellbook {
+            known,
+            selected: 0,
+            cooldowns: vec![Duration::new(0, 0); Spell::ALL.len()],
+            casting: None,
+        }
+    }
+
+    /// The spell that gets cast when the cast button is pressed.
+    pub fn selected_spell(&self) -> Spell {
+        self.known[self.selected]
+    }
+
+    /// The spells that can be picked.
+    pub fn known(&self) -> &[Spell] {
+        &self.known
+    }
+
+    /// Selects a spell by its place in the spellbook (ignored if there is no such spell).
+    pub fn select(&mut self, index: usize) {
+        if index < self.known.len() {
+            self.selected = index;
+        }
+    }
+
+    /// Selects the next spell (wrapping around).
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.known.len();
+    }
+
+    /// Time left before a spell can be cast again.
+    pub fn cooldown_left(&self, spell: Spell) -> Duration {
+        self.cooldowns[Spell::ALL.iter().position(|s| *s == spell).unwrap()]
+    }
+
+    /// Returns true if a spell is being cast.
+    pub fn casting(&self) -> bool {
+        self.casting.is_some()
+    }
+
+    /// Starts casting a spell, paying for it out of mp.
+    /// Returns false (and takes nothing) if the spell isn't ready, mp is short or something is already being cast.
+    pub fn begin(&mut self, spell: Spell, mp: &mut u32) -> bool {
+        let i = Spell::ALL.iter().position(|s| *s == spell).unwrap();
+        if self.casting() || self.cooldowns[i] > Duration::new(0, 0) || *mp < spell.cost() {
+            return false;
+        }
+        *mp -= spell.cost();
+        self.cooldowns[i] = spell.cooldown();
+        self.casting = Some((spell, Duration::new(0, 0)));
+        true
+    }
+
+    /// Counts down cooldowns and the spell being cast.
+    /// Returns the spell once it has finished being cast.
+    pub fn update(&mut self, delta: Duration) -> Option<Spell> {
+        for cd in self.cooldowns.iter_mut() {
+            *cd = cd.checked_sub(delta).unwrap_or_default();
+        }
+
+        let (spell, elapsed) = self.casting.as_mut()?;
+        *elapsed += delta;
+        if *elapsed >= spell.cast_time() {
+            let spell = *spell;
+            self.casting = None;
+            return Some(spell);
+        }
+        None
+    }
+
+    /// Stops the spell being cast (the mp is lost, and the cooldown still runs).
+    pub fn interrupt(&mut self) {
+        self.casting = None;
+    }
+}
+
+impl Default for Spellbook {
+    fn default() -> Spellbook {
+        Spellbook::new()
+    }
+}
+
+#[cfg(test)]
+mod testspells {
+    use super::super::stats::Stats;
+    use super::*;
+
+    #[test]
+    fn test_cost_cooldown_and_cast_time() {
+        let mut book = Spellbook::new();
+        let mut mp = 15;
+
+        assert!(book.begin(Spell::Fireball, &mut mp));
+        assert_eq!(mp, 5);
+        // already casting
+        assert!(!book.begin(Spell::Blink, &mut mp));
+        assert_eq!(book.update(Duration::from_millis(200)), None);
+        assert_eq!(
+            book.update(Duration::from_millis(200)),
+            Some(Spell::Fireball)
+        );
+        assert!(!book.casting());
+
+        // still cooling down, and not enough mp for a frost nova
+        assert!(!book.begin(Spell::Fireball, &mut mp));
+        assert!(!book.begin(Spell::FrostNova, &mut mp));
+        book.update(Duration::from_millis(400));
+        mp = 10;
+        assert!(book.begin(Spell::Fireball, &mut mp));
+        assert_eq!(mp, 0);
+    }
+
+    #[test]
+    fn test_select() {
+        let mut book = Spellbook::new();
+        book.select(3);
+        assert_eq!(book.selected_spell(), Spell::Blink);
+        book.select(9);
+        assert_eq!(book.selected_spell(), Spell::Blink);
+        book.next();
+        assert_eq!(book.selected_spell(), Spell::Fireball);
+
+        // only the spells known can be picked
+        let mut book = Spellbook::knowing(vec![Spell::Heal, Spell::Blink]);
+        assert_eq!(book.selected_spell(), Spell::Heal);
+        book.select(2);
+        assert_eq!(book.selected_spell(), Spell::Heal);
+        book.next();
+        book.next();
+        assert_eq!(book.selected_spell(), Spell::Heal);
+    }
+
+    #[test]
+    fn test_mp_regen() {
+        let mut stats = Stats::new(1, 0, 50.0, 100, 3.0, 0.5, 1.0);
+        stats.mp = 90;
+        // 3 mp a second, a bit at a time
+        for _ in 0..60 {
+            stats.regen_mp(Duration::from_millis(1000 / 60));
+        }
+        assert!(stats.mp == 92 || stats.mp == 93);
+        stats.regen_mp(Duration::from_secs(10));
+        assert_eq!(stats.mp, 100);
+    }
+}
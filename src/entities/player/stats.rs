@@ -1,3 +1,6 @@
+use super::progression::{Growth, Progression};
+use std::time::Duration;
+
 /// Struct for the stats
 pub struct Stats {
     pub lv: u32,
@@ -9,8 +12,26 @@ pub struct Stats {
     pub atk: f32,
     pub def: f32,
     pub spd: f32,
+    // mp regained every second
+    pub mp_regen: f32,
+    // regen that hasn't added up to a whole point of mp yet
+    mp_partial: f32,
+    // spent on dodging, comes back quickly
+    pub stamina: f32,
+    pub max_stamina: f32,
+    // stamina regained every second
+    pub stamina_regen: f32,
+    pub progression: Progression,
+    // how quickly this player's stats grow on levelling up (depends on their class)
+    pub growth: Growth,
 }
 
+/// Default mp regained every second.
+const MP_REGEN: f32 = 3.0;
+/// Stamina everyone starts with, and how much comes back every second.
+const MAX_STAMINA: f32 = 100.0;
+const STAMINA_REGEN: f32 = 40.0;
+
 /// Functions for the Stats struct
 impl Stats {
     /// Returns a new Stats Struct
@@ -25,80 +46,71 @@ impl Stats {
             atk,
             def,
             spd,
+            mp_regen: MP_REGEN,
+            mp_partial: 0.0,
+            stamina: MAX_STAMINA,
+            max_stamina: MAX_STAMINA,
+            stamina_regen: STAMINA_REGEN,
+            progression: Progression::default(),
+            growth: Growth::default(),
         }
     }
 
-    /// Adds exp to the stats and check for a level up
-    pub fn check_for_level_up(&mut self, exp: u32) {
+    /// Regains mp over time (never past max mp).
+    pub fn regen_mp(&mut self, delta: Duration) {
+        if self.mp >= self.max_mp {
+            self.mp_partial = 0.0;
+            return;
+        }
+        self.mp_partial += self.mp_regen * delta.as_secs_f32();
+        let whole = self.mp_partial.floor();
+        self.mp_partial -= whole;
+        self.mp = (self.mp + whole as u32).min(self.max_mp);
+    }
+
+    /// Regains stamina over time (never past max stamina).
+    pub fn regen_stamina(&mut self, delta: Duration) {
+        self.stamina =
+            (self.stamina + self.stamina_regen * delta.as_secs_f32()).min(self.max_stamina);
+    }
+
+    /// Spends stamina. Returns false (and spends nothing) if there isn't enough.
+    pub fn spend_stamina(&mut self, cost: f32) -> bool {
+        if self.stamina < cost {
+            return false;
+        }
+        self.stamina -= cost;
+        true
+    }
+
+    /// Adds exp to the stats and levels up as many times as it covers (keeping whatever is left over).
+    /// Returns how many levels were gained.
+    pub fn check_for_level_up(&mut self, exp: u32) -> u32 {
         self.exp += exp;
-        if self.exp >= 10 {
+        let mut gained = 0;
+        while self.exp >= self.exp_to_next() {
+            self.exp -= self.exp_to_next();
             self.lv += 1;
             self.level_up();
-            self.exp = 0;
+            gained += 1;
         }
+        gained
     }
 
-    /// This is just a straight forward level_up system added stats for each level.
+    /// Experience needed to reach the next level.
+    pub fn exp_to_next(&self) -> u32 {
+        self.progression.xp_to_next(self.lv)
+    }
+
+    /// Adds the stats for reaching the current level (from the progression table, scaled by growth) and refills mp.
     pub fn level_up(&mut self) {
-        match self.lv {
-            2 => {
-                self.atk += 0.5;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-            }
-            3 => {
-                self.atk += 0.5;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.max_mp += 20;
-            }
-            4 => {
-                self.atk += 0.5;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.def += 0.5;
-            }
-            5 => {
-                self.atk += 1.0;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.max_mp += 20;
-            }
-            6 => {
-                self.atk += 0.5;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.max_mp += 20;
-            }
-            7 => {
-                self.atk += 1.0;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.def += 0.5;
-            }
-            8 => {
-                self.atk += 0.5;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.max_mp += 20;
-            }
-            9 => {
-                self.atk += 1.0;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.max_mp += 20;
-            }
-            10 => {
-                self.atk += 1.0;
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-                self.def += 1.0;
-            }
-            _ => {
-                self.hp += 5.0;
-                self.max_hp += 5.0;
-            }
-        }
+        let gain = self.progression.gains(self.lv).scaled(&self.growth);
+        self.hp += gain.hp;
+        self.max_hp += gain.hp;
+        self.max_mp += gain.mp;
+        self.atk += gain.atk;
+        self.def += gain.def;
+        self.spd += gain.spd;
 
         self.mp = self.max_mp;
     }
@@ -0,0 +1,156 @@
+use super::super::EntityId;
+use super::equipment::AttackShape;
+use std::time::Duration;
+
+/// The stages every swing goes through.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SwingPhase {
+    // not swinging (but maybe still cooling down)
+    Ready,
+    // drawing back, nothing is hit yet
+    Windup,
+    // the AtkBox is out and can hit things
+    Active,
+    // following through, can't do anything else yet
+    Recovery,
+}
+
+/// The player's melee swing. Handles the timing of each phase, the cooldown
+/// before the next swing, and who has been hit (so each is only hit once per swing).
+pub struct Swing {
+    pub phase: SwingPhase,
+    timer: Duration,
+    shape: AttackShape,
+    // time left before another swing can start
+    cooldown: Duration,
+    // everything hit by this swing
+    hit: Vec<EntityId>,
+}
+
+/// Functions for the Swing struct
+impl Swing {
+    /// News up a Swing that is ready to go.
+    pub fn new() -> Swing {
+        Swing {
+            phase: SwingPhase::Ready,
+            timer: Duration::new(0, 0),
+            shape: AttackShape::for_weapon(None),
+            cooldown: Duration::new(0, 0),
+            hit: Vec::new(),
+        }
+    }
+
+    /// Returns true in the middle of a swing.
+    pub fn swinging(&self) -> bool {
+        self.phase != SwingPhase::Ready
+    }
+
+    /// Returns true if a new swing can start.
+    pub fn ready(&self) -> bool {
+        !self.swinging() && self.cooldown == Duration::new(0, 0)
+    }
+
+    /// Returns true while the swing can hit things.
+    pub fn active(&self) -> bool {
+        self.phase == SwingPhase::Active
+    }
+
+    /// The weapon shape the current (or last) swing was started with.
+    pub fn shape(&self) -> AttackShape {
+        self.shape
+    }
+
+    /// Starts winding up a swing with a weapon.
+    pub fn start(&mut self, shape: AttackShape) {
+        self.shape = shape;
+        self.phase = SwingPhase::Windup;
+        self.timer = Duration::new(0, 0);
+        self.hit.clear();
+    }
+
+    /// Stops the swing (ie the player dodged out of it). The cooldown still has to run.
+    pub fn interrupt(&mut self) {
+        if self.swinging() {
+            self.phase = SwingPhase::Ready;
+            self.cooldown = self.shape.cooldown;
+        }
+    }
+
+    /// Moves the swing through its phases and counts down the cooldown.
+    pub fn update(&mut self, delta: Duration) {
+        if !self.swinging() {
+            self.cooldown = self.cooldown.checked_sub(delta).unwrap_or_default();
+            return;
+        }
+        self.timer += delta;
+        let (length, next) = match self.phase {
+            SwingPhase::Windup => (self.shape.windup, SwingPhase::Active),
+            SwingPhase::Active => (self.shape.active, SwingPhase::Recovery),
+            SwingPhase::Recovery => (self.shape.recovery, SwingPhase::Ready),
+            SwingPhase::Ready => return,
+        };
+        if self.timer >= length {
+            self.timer -= length;
+            self.phase = next;
+            if next == SwingPhase::Ready {
+                self.cooldown = self.shape.cooldown;
+            }
+        }
+    }
+
+    /// Returns true the first time this swing hits the target with id, false if it was already hit.
+    pub fn first_hit(&mut self, id: EntityId) -> bool {
+        if self.hit.contains(&id) {
+            return false;
+        }
+        self.hit.push(id);
+        true
+    }
+}
+
+impl Default for Swing {
+    fn default() -> Swing {
+        Swing::new()
+    }
+}
+
+#[cfg(test)]
+mod testswing {
+    use super::*;
+
+    #[test]
+    fn test_swing_phases_and_cooldown() {
+        let shape = AttackShape::for_weapon(None);
+        let mut swing = Swing::new();
+        assert!(swing.ready());
+        swing.start(shape);
+        assert_eq!(swing.phase, SwingPhase::Windup);
+        swing.update(shape.windup);
+        assert!(swing.active());
+        swing.update(shape.active);
+        assert_eq!(swing.phase, SwingPhase::Recovery);
+        swing.update(shape.recovery);
+        assert!(!swing.swinging());
+        // finished, but still cooling down
+        assert!(!swing.ready());
+        swing.update(shape.cooldown);
+        assert!(swing.ready());
+    }
+
+    #[test]
+    fn test_one_hit_per_swing() {
+        let a = EntityId::fresh();
+        let b = EntityId::fresh();
+        assert_ne!(a, b);
+        let mut swing = Swing::new();
+        swing.start(AttackShape::for_weapon(None));
+        assert!(swing.first_hit(a));
+        assert!(swing.first_hit(b));
+        assert!(!swing.first_hit(a));
+        // something new showing up mid swing can still be hit
+        assert!(swing.first_hit(EntityId::fresh()));
+        // a new swing can hit again
+        swing.start(AttackShape::for_weapon(None));
+        assert!(swing.first_hit(a));
+    }
+}
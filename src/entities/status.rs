@@ -0,0 +1,277 @@
+use ggez::graphics::{self, Color};
+use std::time::Duration;
+
+/// The different status effects the player and enemies can suffer from.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusKind {
+    Poison,
+    Slow,
+    Stun,
+    Burn,
+    // the only good one, runs faster
+    Haste,
+}
+
+/// What happens when an effect is applied to someone who already has it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Stacking {
+    // start the duration over
+    Refresh,
+    // add a stack (up to the max) and start the duration over
+    Intensify(u32),
+    // already have it, nothing happens
+    Ignore,
+}
+
+/// Functions for the StatusKind enum (default tuning for each effect)
+impl StatusKind {
+    /// Looks up a StatusKind from the name used in level files.
+    pub fn from_name(name: &str) -> Option<StatusKind> {
+        match name {
+            "poison" => Some(StatusKind::Poison),
+            "slow" => Some(StatusKind::Slow),
+            "stun" => Some(StatusKind::Stun),
+            "burn" => Some(StatusKind::Burn),
+            "haste" => Some(StatusKind::Haste),
+            _ => None,
+        }
+    }
+
+    /// How reapplying this effect works.
+    pub fn stacking(self) -> Stacking {
+        match self {
+            StatusKind::Poison => Stacking::Intensify(3),
+            StatusKind::Slow => Stacking::Refresh,
+            StatusKind::Stun => Stacking::Ignore,
+            StatusKind::Burn => Stacking::Refresh,
+            StatusKind::Haste => Stacking::Refresh,
+        }
+    }
+
+    /// Colour sprites are tinted while suffering from this effect.
+    pub fn tint(self) -> Color {
+        match self {
+            StatusKind::Poison => Color::new(0.5, 1.0, 0.5, 1.0),
+            StatusKind::Slow => Color::new(0.5, 0.7, 1.0, 1.0),
+            StatusKind::Stun => Color::new(1.0, 1.0, 0.4, 1.0),
+            StatusKind::Burn => Color::new(1.0, 0.6, 0.3, 1.0),
+            StatusKind::Haste => Color::new(0.7, 1.0, 1.0, 1.0),
+        }
+    }
+
+    /// FloatingText colour for damage dealt by this effect.
+    pub fn text_color(self) -> &'static str {
+        match self {
+            StatusKind::Poison => "Green",
+            StatusKind::Slow => "Blue",
+            StatusKind::Stun => "Yellow",
+            StatusKind::Burn => "Orange",
+            StatusKind::Haste => "White",
+        }
+    }
+
+    /// Short label used for the UI icon.
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusKind::Poison => "P",
+            StatusKind::Slow => "S",
+            StatusKind::Stun => "!",
+            StatusKind::Burn => "B",
+            StatusKind::Haste => "H",
+        }
+    }
+}
+
+/// A single status effect with how long it lasts and how often it ticks.
+#[derive(Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub remaining: Duration,
+    pub length: Duration,
+    pub tick: Option<Duration>,
+    pub power: f32,
+    pub stacks: u32,
+    tick_timer: Duration,
+}
+
+/// Functions for the StatusEffect struct
+impl StatusEffect {
+    /// News up a status effect with the default tuning for kind.
+    pub fn new(kind: StatusKind) -> StatusEffect {
+        let (length, tick, power) = match kind {
+            StatusKind::Poison => (5000, Some(1000), 1.0),
+            StatusKind::Slow => (3000, None, 0.0),
+            StatusKind::Stun => (1000, None, 0.0),
+            StatusKind::Burn => (3000, Some(500), 1.0),
+            StatusKind::Haste => (10000, None, 0.0),
+        };
+        StatusEffect {
+            kind,
+            remaining: Duration::from_millis(length),
+            length: Duration::from_millis(length),
+            tick: tick.map(Duration::from_millis),
+            power,
+            stacks: 1u32,
+            tick_timer: Duration::new(0, 0),
+        }
+    }
+
+    /// Counts down the effect. Returns the damage ticked this frame.
+    fn update(&mut self, delta: Duration) -> f32 {
+        let delta = delta.min(self.remaining);
+        self.remaining -= delta;
+
+        let mut dmg = 0.0;
+        if let Some(tick) = self.tick {
+            self.tick_timer += delta;
+            while self.tick_timer >= tick {
+                self.tick_timer -= tick;
+                dmg += self.power * self.stacks as f32;
+            }
+        }
+        dmg
+    }
+
+    /// Returns true while the effect has time left.
+    pub fn live(&self) -> bool {
+        self.remaining > Duration::new(0, 0)
+    }
+}
+
+/// All the status effects on the player or an enemy.
+#[derive(Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+/// Functions for the StatusEffects struct
+impl StatusEffects {
+    /// News up an empty set of status effects.
+    pub fn new() -> StatusEffects {
+        StatusEffects {
+            effects: Vec::new(),
+        }
+    }
+
+    /// Applies an effect following its stacking rules.
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            None => self.effects.push(effect),
+            Some(current) => match effect.kind.stacking() {
+                Stacking::Refresh => current.remaining = current.length.max(effect.length),
+                Stacking::Intensify(max) => {
+                    current.stacks = (current.stacks + effect.stacks).min(max);
+                    current.remaining = current.length.max(effect.length);
+                }
+                Stacking::Ignore => (),
+            },
+        }
+    }
+
+    /// Counts down all effects and clears any that ran out.
+    /// Returns the damage dealt this frame with the effect that dealt the most of it.
+    pub fn update(&mut self, delta: Duration) -> Option<(f32, StatusKind)> {
+        let mut total = 0.0;
+        let mut worst: Option<(f32, StatusKind)> = None;
+        for e in &mut self.effects {
+            let dmg = e.update(delta);
+            if dmg > 0.0 {
+                total += dmg;
+                if worst.map_or(0.0, |w| w.0) < dmg {
+                    worst = Some((dmg, e.kind));
+                }
+            }
+        }
+        self.effects.retain(|e| e.live());
+        worst.map(|(_, kind)| (total, kind))
+    }
+
+    /// Returns true if kind is active.
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|e| e.kind == kind)
+    }
+
+    /// Multiplier for movement speed (stunned can't move at all, slow and haste cancel out a bit).
+    pub fn speed_scale(&self) -> f32 {
+        if self.has(StatusKind::Stun) {
+            return 0.0;
+        }
+        let mut scale = 1.0;
+        if self.has(StatusKind::Slow) {
+            scale *= 0.5;
+        }
+        if self.has(StatusKind::Haste) {
+            scale *= 1.5;
+        }
+        scale
+    }
+
+    /// Change to defence (burning armour doesn't protect as well).
+    pub fn def_modifier(&self) -> f32 {
+        if self.has(StatusKind::Burn) {
+            -0.5
+        } else {
+            0.0
+        }
+    }
+
+    /// Colour to tint sprites with (the most recently applied effect wins).
+    pub fn tint(&self) -> Color {
+        match self.effects.last() {
+            Some(e) => e.kind.tint(),
+            None => graphics::WHITE,
+        }
+    }
+
+    /// Active effects and their stacks (ie for the UI).
+    pub fn active(&self) -> Vec<(StatusKind, u32)> {
+        self.effects.iter().map(|e| (e.kind, e.stacks)).collect()
+    }
+}
+
+#[cfg(test)]
+mod teststatus {
+    use super::*;
+
+    #[test]
+    fn test_poison_ticks_stacks_and_expires() {
+        let mut status = StatusEffects::new();
+        status.apply(StatusEffect::new(StatusKind::Poison));
+        status.apply(StatusEffect::new(StatusKind::Poison));
+        assert_eq!(status.active(), vec![(StatusKind::Poison, 2u32)]);
+        for _ in 0..5 {
+            status.apply(StatusEffect::new(StatusKind::Poison));
+        }
+        assert_eq!(status.active(), vec![(StatusKind::Poison, 3u32)]);
+
+        assert!(status.update(Duration::from_millis(500)).is_none());
+        let (dmg, kind) = status.update(Duration::from_millis(500)).unwrap();
+        assert_eq!(kind, StatusKind::Poison);
+        assert_eq!(dmg, 3.0);
+
+        status.update(Duration::from_millis(10000));
+        assert!(!status.has(StatusKind::Poison));
+        assert_eq!(status.tint(), graphics::WHITE);
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mut status = StatusEffects::new();
+        assert_eq!(status.speed_scale(), 1.0);
+        status.apply(StatusEffect::new(StatusKind::Slow));
+        assert_eq!(status.speed_scale(), 0.5);
+        status.apply(StatusEffect::new(StatusKind::Stun));
+        assert_eq!(status.speed_scale(), 0.0);
+        status.apply(StatusEffect::new(StatusKind::Burn));
+        assert!(status.def_modifier() < 0.0);
+
+        // stun can't be extended by stunning again
+        status.update(Duration::from_millis(900));
+        status.apply(StatusEffect::new(StatusKind::Stun));
+        status.update(Duration::from_millis(200));
+        assert!(!status.has(StatusKind::Stun));
+        assert!(status.has(StatusKind::Slow));
+        status.apply(StatusEffect::new(StatusKind::Haste));
+        assert_eq!(status.speed_scale(), 0.75);
+    }
+}
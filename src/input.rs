@@ -0,0 +1,532 @@
+use ggez::event::{Axis, Button, KeyCode};
+use ggez::input::keyboard;
+use ggez::Context;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+/// Where the controls are saved (in the game's user config folder).
+const CONFIG_PATH: &str = "/controls.txt";
+
+/// How far a thumb stick has to be pushed before it counts as moving.
+const DEADZONE: f32 = 0.5;
+
+/// Things the player can do. Keys and gamepad buttons get turned into these.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Attack,
+    Cast,
+    NextSpell,
+    Spell1,
+    Spell2,
+    Spell3,
+    Spell4,
+    Hotbar1,
+    Hotbar2,
+    Hotbar3,
+    Inventory,
+    Character,
+    Dodge,
+    Run,
+    Pause,
+    Interact,
+    Confirm,
+    Quit,
+    // level builder only
+    Save,
+}
+
+/// Functions for the Action enum
+impl Action {
+    /// Every action (in the order they are listed on the controls screen).
+    pub const ALL: [Action; 23] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Attack,
+        Action::Cast,
+        Action::NextSpell,
+        Action::Spell1,
+        Action::Spell2,
+        Action::Spell3,
+        Action::Spell4,
+        Action::Hotbar1,
+        Action::Hotbar2,
+        Action::Hotbar3,
+        Action::Inventory,
+        Action::Character,
+        Action::Dodge,
+        Action::Run,
+        Action::Pause,
+        Action::Interact,
+        Action::Confirm,
+        Action::Quit,
+        Action::Save,
+    ];
+
+    /// Name used in the controls file and on the controls screen.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Attack => "attack",
+            Action::Cast => "cast",
+            Action::NextSpell => "next_spell",
+            Action::Spell1 => "spell_1",
+            Action::Spell2 => "spell_2",
+            Action::Spell3 => "spell_3",
+            Action::Spell4 => "spell_4",
+            Action::Hotbar1 => "hotbar_1",
+            Action::Hotbar2 => "hotbar_2",
+            Action::Hotbar3 => "hotbar_3",
+            Action::Inventory => "inventory",
+            Action::Character => "character",
+            Action::Dodge => "dodge",
+            Action::Run => "run",
+            Action::Pause => "pause",
+            Action::Interact => "interact",
+            Action::Confirm => "confirm",
+            Action::Quit => "quit",
+            Action::Save => "save",
+        }
+    }
+
+    /// Looks up an action by the name used in the controls file.
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().cloned().find(|a| a.name() == name)
+    }
+}
+
+/// Keys that can be bound (anything else on the keyboard is ignored).
+const KEYS: [KeyCode; 58] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Space,
+    KeyCode::Return,
+    KeyCode::Tab,
+    KeyCode::Back,
+    KeyCode::LShift,
+    KeyCode::RShift,
+    KeyCode::LControl,
+    KeyCode::RControl,
+    KeyCode::LAlt,
+    KeyCode::RAlt,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Apostrophe,
+    KeyCode::LBracket,
+    KeyCode::RBracket,
+    KeyCode::Minus,
+];
+
+/// Gamepad buttons that can be bound.
+const BUTTONS: [Button; 17] = [
+    Button::South,
+    Button::East,
+    Button::North,
+    Button::West,
+    Button::LeftTrigger,
+    Button::LeftTrigger2,
+    Button::RightTrigger,
+    Button::RightTrigger2,
+    Button::Select,
+    Button::Start,
+    Button::Mode,
+    Button::LeftThumb,
+    Button::RightThumb,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+];
+
+/// A key or gamepad button that triggers an action.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Button(Button),
+}
+
+/// Functions for the Binding enum
+impl Binding {
+    /// Name shown on screen and saved in the controls file (ie "W" or "DPadUp").
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Button(btn) => format!("{:?}", btn),
+        }
+    }
+
+    /// Reads a binding back from the controls file (`key <name>` or `button <name>`).
+    pub fn parse(kind: &str, name: &str) -> Option<Binding> {
+        match kind {
+            "key" => KEYS
+                .iter()
+                .find(|k| format!("{:?}", k) == name)
+                .map(|k| Binding::Key(*k)),
+            "button" => BUTTONS
+                .iter()
+                .find(|b| format!("{:?}", b) == name)
+                .map(|b| Binding::Button(*b)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the key or button is one that can be bound.
+    pub fn bindable(self) -> bool {
+        match self {
+            Binding::Key(key) => KEYS.contains(&key),
+            Binding::Button(btn) => BUTTONS.contains(&btn),
+        }
+    }
+
+    /// Returns true if both bindings are keys or both are buttons.
+    fn same_device(self, other: Binding) -> bool {
+        matches!(
+            (self, other),
+            (Binding::Key(_), Binding::Key(_)) | (Binding::Button(_), Binding::Button(_))
+        )
+    }
+}
+
+/// Which keys and buttons do which actions.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Controls {
+    bindings: Vec<(Action, Binding)>,
+}
+
+/// Default controls (WASD to move, Space to attack, Q to cast...)
+impl Default for Controls {
+    fn default() -> Controls {
+        use Binding::{Button as B, Key as K};
+        Controls {
+            bindings: vec![
+                (Action::Up, K(KeyCode::W)),
+                (Action::Up, B(Button::DPadUp)),
+                (Action::Down, K(KeyCode::S)),
+                (Action::Down, B(Button::DPadDown)),
+                (Action::Left, K(KeyCode::A)),
+                (Action::Left, B(Button::DPadLeft)),
+                (Action::Right, K(KeyCode::D)),
+                (Action::Right, B(Button::DPadRight)),
+                (Action::Attack, K(KeyCode::Space)),
+                (Action::Attack, B(Button::West)),
+                (Action::Cast, K(KeyCode::Q)),
+                (Action::Cast, B(Button::North)),
+                (Action::NextSpell, K(KeyCode::Tab)),
+                (Action::NextSpell, B(Button::RightTrigger)),
+                (Action::Spell1, K(KeyCode::Key1)),
+                (Action::Spell2, K(KeyCode::Key2)),
+                (Action::Spell3, K(KeyCode::Key3)),
+                (Action::Spell4, K(KeyCode::Key4)),
+                (Action::Hotbar1, K(KeyCode::Z)),
+                (Action::Hotbar1, B(Button::LeftTrigger)),
+                (Action::Hotbar2, K(KeyCode::X)),
+                (Action::Hotbar3, K(KeyCode::C)),
+                (Action::Inventory, K(KeyCode::I)),
+                (Action::Inventory, B(Button::East)),
+                (Action::Character, K(KeyCode::G)),
+                (Action::Character, B(Button::LeftTrigger2)),
+                (Action::Dodge, K(KeyCode::F)),
+                (Action::Dodge, B(Button::LeftThumb)),
+                (Action::Run, K(KeyCode::LShift)),
+                (Action::Run, B(Button::RightTrigger2)),
+                (Action::Pause, K(KeyCode::P)),
+                (Action::Pause, B(Button::Start)),
+                (Action::Interact, K(KeyCode::E)),
+                (Action::Interact, B(Button::South)),
+                (Action::Confirm, K(KeyCode::Return)),
+                (Action::Confirm, B(Button::South)),
+                (Action::Quit, K(KeyCode::Q)),
+                (Action::Quit, B(Button::Select)),
+                (Action::Save, K(KeyCode::O)),
+            ],
+        }
+    }
+}
+
+/// Functions for the Controls struct
+impl Controls {
+    /// Reads controls from the text of a controls file (lines of `action key|button name`).
+    /// Actions missing from the file keep their default bindings.
+    pub fn parse(text: &str) -> Controls {
+        let read: Vec<(Action, Binding)> = text
+            .lines()
+            .filter_map(|line| {
+                let mut parse = line.split_whitespace();
+                let action = Action::from_name(parse.next()?)?;
+                let binding = Binding::parse(parse.next()?, parse.next()?)?;
+                Some((action, binding))
+            })
+            .collect();
+
+        let mut controls = Controls::default();
+        controls
+            .bindings
+            .retain(|(a, _)| !read.iter().any(|(r, _)| r == a));
+        controls.bindings.extend(read);
+        controls
+    }
+
+    /// Writes the controls out in the controls file format.
+    pub fn to_text(&self) -> String {
+        self.bindings
+            .iter()
+            .map(|(a, b)| {
+                let kind = match b {
+                    Binding::Key(_) => "key",
+                    Binding::Button(_) => "button",
+                };
+                format!("{} {} {}\n", a.name(), kind, b.name())
+            })
+            .collect()
+    }
+
+    /// All the actions a key or button triggers.
+    pub fn actions_for(&self, binding: Binding) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(_, b)| *b == binding)
+            .map(|(a, _)| *a)
+            .collect()
+    }
+
+    /// All the keys and buttons that trigger an action.
+    pub fn bindings_for(&self, action: Action) -> Vec<Binding> {
+        self.bindings
+            .iter()
+            .filter(|(a, _)| *a == action)
+            .map(|(_, b)| *b)
+            .collect()
+    }
+
+    /// Binds a key or button to an action, replacing whatever key (or button) it had before.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        self.bindings
+            .retain(|(a, b)| *a != action || !b.same_device(binding));
+        self.bindings.push((action, binding));
+    }
+
+    /// Readable list of what triggers an action (ie "W / DPadUp").
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.bindings_for(action).iter().map(|b| b.name()).collect();
+        if names.is_empty() {
+            "(unbound)".to_string()
+        } else {
+            names.join(" / ")
+        }
+    }
+}
+
+/// Current controls and what the gamepads are doing.
+#[derive(Default)]
+struct InputState {
+    controls: Controls,
+    buttons: HashSet<Button>,
+    stick: (f32, f32),
+}
+
+// Input works like ggez::input::keyboard, anything can ask about it without passing it around.
+thread_local! {
+    static INPUT: RefCell<InputState> = RefCell::new(InputState::default());
+}
+
+/// Loads saved controls (or the defaults if nothing has been saved).
+pub fn load(ctx: &mut Context) {
+    let controls = if ggez::filesystem::exists(ctx, CONFIG_PATH) {
+        let mut text = String::new();
+        let mut file = ggez::filesystem::open(ctx, CONFIG_PATH).unwrap();
+        file.read_to_string(&mut text).unwrap();
+        Controls::parse(&text)
+    } else {
+        Controls::default()
+    };
+    set_controls(controls);
+}
+
+/// Saves the current controls to the user's config folder.
+pub fn save(ctx: &mut Context) {
+    let text = controls().to_text();
+    match ggez::filesystem::create(ctx, CONFIG_PATH) {
+        Ok(mut file) => file.write_all(text.as_bytes()).unwrap(),
+        Err(e) => println!("unable to save controls: {}", e),
+    }
+}
+
+/// A copy of the current controls.
+pub fn controls() -> Controls {
+    INPUT.with(|i| i.borrow().controls.clone())
+}
+
+/// Replaces the current controls.
+pub fn set_controls(controls: Controls) {
+    INPUT.with(|i| i.borrow_mut().controls = controls);
+}
+
+/// Binds a key or button to an action in the current controls.
+pub fn rebind(action: Action, binding: Binding) {
+    INPUT.with(|i| i.borrow_mut().controls.rebind(action, binding));
+}
+
+/// Readable list of what triggers an action with the current controls.
+pub fn describe(action: Action) -> String {
+    INPUT.with(|i| i.borrow().controls.describe(action))
+}
+
+/// The keyboard key bound to an action (for short instructions).
+pub fn describe_key(action: Action) -> String {
+    INPUT.with(|i| {
+        i.borrow()
+            .controls
+            .bindings_for(action)
+            .iter()
+            .find(|b| b.same_device(Binding::Key(KeyCode::A)))
+            .map_or("(unbound)".to_string(), |b| b.name())
+    })
+}
+
+/// Actions triggered by a key.
+pub fn actions_for_key(key: KeyCode) -> Vec<Action> {
+    INPUT.with(|i| i.borrow().controls.actions_for(Binding::Key(key)))
+}
+
+/// Actions triggered by a gamepad button.
+pub fn actions_for_button(btn: Button) -> Vec<Action> {
+    INPUT.with(|i| i.borrow().controls.actions_for(Binding::Button(btn)))
+}
+
+/// Keeps track of gamepad buttons being held (call from gamepad button events).
+pub fn button_event(btn: Button, down: bool) {
+    INPUT.with(|i| {
+        let mut i = i.borrow_mut();
+        if down {
+            i.buttons.insert(btn);
+        } else {
+            i.buttons.remove(&btn);
+        }
+    });
+}
+
+/// Keeps track of the left thumb stick (call from gamepad axis events).
+pub fn axis_event(axis: Axis, value: f32) {
+    INPUT.with(|i| {
+        let mut i = i.borrow_mut();
+        match axis {
+            Axis::LeftStickX => i.stick.0 = value,
+            Axis::LeftStickY => i.stick.1 = value,
+            _ => (),
+        }
+    });
+}
+
+/// Returns true while anything bound to action is held down
+/// (the left thumb stick counts for movement).
+pub fn is_pressed(ctx: &Context, action: Action) -> bool {
+    INPUT.with(|i| {
+        let i = i.borrow();
+        let bound = i.controls.bindings_for(action).iter().any(|b| match b {
+            Binding::Key(key) => keyboard::is_key_pressed(ctx, *key),
+            Binding::Button(btn) => i.buttons.contains(btn),
+        });
+        // stick up is positive
+        let stick = match action {
+            Action::Up => i.stick.1 > DEADZONE,
+            Action::Down => i.stick.1 < -DEADZONE,
+            Action::Left => i.stick.0 < -DEADZONE,
+            Action::Right => i.stick.0 > DEADZONE,
+            _ => false,
+        };
+        bound || stick
+    })
+}
+
+#[cfg(test)]
+mod testinput {
+    use super::*;
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut controls = Controls::default();
+        controls.rebind(Action::Attack, Binding::Key(KeyCode::J));
+        let read = Controls::parse(&controls.to_text());
+        assert_eq!(
+            read.bindings_for(Action::Attack),
+            controls.bindings_for(Action::Attack)
+        );
+        assert_eq!(read.describe(Action::Up), "W / DPadUp");
+    }
+
+    #[test]
+    fn test_parse_keeps_defaults_for_missing_actions() {
+        let controls = Controls::parse("up key Up\nnonsense line\ncast key NotAKey\n");
+        assert_eq!(
+            controls.bindings_for(Action::Up),
+            vec![Binding::Key(KeyCode::Up)]
+        );
+        assert_eq!(
+            controls.bindings_for(Action::Cast),
+            Controls::default().bindings_for(Action::Cast)
+        );
+    }
+
+    #[test]
+    fn test_rebind_only_replaces_same_device() {
+        let mut controls = Controls::default();
+        controls.rebind(Action::Up, Binding::Key(KeyCode::Up));
+        assert_eq!(
+            controls.bindings_for(Action::Up),
+            vec![Binding::Button(Button::DPadUp), Binding::Key(KeyCode::Up)]
+        );
+        assert_eq!(controls.actions_for(Binding::Key(KeyCode::W)), vec![]);
+        // one button can do more than one thing
+        let south = controls.actions_for(Binding::Button(Button::South));
+        assert!(south.contains(&Action::Interact) && south.contains(&Action::Confirm));
+    }
+}
@@ -5,6 +5,9 @@ pub mod entities;
 /// Contains struct useful for building and drawing sprites from a source image.
 pub mod sprites;
 
+/// Turns keyboard and gamepad input into game actions (rebindable, saved to a config file).
+pub mod input;
+
 /// Contains state manager and various states the game can be in.
 pub mod states;
 
@@ -20,12 +20,16 @@ pub struct AnimatedSprite {
 }
 
 impl AnimatedSprite {
-    pub fn new(sheet: &Image, clips: Vec<Rect>, animation: Option<AnimationState>) -> GameResult<AnimatedSprite> {
+    pub fn new(
+        sheet: &Image,
+        clips: Vec<Rect>,
+        animation: Option<AnimationState>,
+    ) -> GameResult<AnimatedSprite> {
         let sheet = sheet.clone();
-        
+
         if clips.is_empty() {
             return Err(error::GameError::ResourceLoadError(
-                "No clips to add to animated sprite".to_string()
+                "No clips to add to animated sprite".to_string(),
             ));
         }
         let mut rect = Vec::new();
@@ -34,12 +38,10 @@ impl AnimatedSprite {
             if contains(&sheet.dimensions(), &c) {
                 rect.push(Rect::fraction(c.x, c.y, c.w, c.h, &sheet.dimensions()));
             } else {
-                return Err(error::GameError::ResourceLoadError(
-                    format!(
-                        "Clip {:?} not contained in sheet",
-                        c
-                    )
-                ));
+                return Err(error::GameError::ResourceLoadError(format!(
+                    "Clip {:?} not contained in sheet",
+                    c
+                )));
             }
         }
 
@@ -47,7 +49,7 @@ impl AnimatedSprite {
             Some(ani) => ani,
             None => AnimationState::Loop,
         };
-        
+
         Ok(AnimatedSprite {
             sheet,
             clips: rect,
@@ -89,6 +91,18 @@ impl AnimatedSprite {
         Some(dim)
     }
 
+    // plays the animation from the first frame, sped up or slowed down to last `length`
+    pub fn play_over(&mut self, length: Duration) {
+        self.frame_rate = length / self.clips.len() as u32;
+        self.current_frame = 0usize;
+        self.accumulated = Duration::new(0, 0);
+    }
+
+    // true once an animation that plays once has reached its last frame
+    pub fn finished(&self) -> bool {
+        self.state == AnimationState::Once && self.current_frame == self.clips.len() - 1
+    }
+
     pub fn once_animation(&mut self) {
         if let AnimationState::Paused(a) = self.state {
             self.accumulated = a;
@@ -99,7 +113,7 @@ impl AnimatedSprite {
     /*
     // may be useful one day ... just not today
     // good to have them though
-    
+
     fn set_frame_rate(&mut self, rate: Duration) {
         self.frame_rate = rate;
     }
@@ -152,4 +166,4 @@ impl Drawable for AnimatedSprite {
     fn blend_mode(&self) -> Option<BlendMode> {
         self.sheet.blend_mode()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,144 @@
+/// Character screen for game.
+/// Shows the player's stats (before and after gear) and what they have equipped.
+/// Gear can be taken off here, and is put on from the inventory.
+pub struct CharacterState {
+    text: graphics::Text,
+    // level, hp and mp at the time the screen was opened
+    header: String,
+    // the player's own stats, gear gets added on top of these
+    base: StatBlock,
+    // shared with the player
+    equipment: Rc<RefCell<Equipment>>,
+    inventory: Rc<RefCell<Inventory>>,
+    // index into EquipSlot::ALL
+    selected: usize,
+}
+
+/// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
+/// Allows the state machine to pass on information.
+impl CustomEventHandler for CharacterState {
+    /// Updates CharacterState.
+    /// Does not really do anything... Real magic happens in action_down_event.
+    fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
+        HandlerMessage::Keep
+    }
+
+    /// Draws CharacterState.
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        graphics::clear(ctx, graphics::BLACK);
+        let point = nalgebra::Point2::new(100.0, 50.0);
+
+        graphics::draw(
+            ctx,
+            &self.text,
+            graphics::DrawParam::default().dest(point),
+        )
+        .expect("ERROR drawing Character Text");
+
+        graphics::present(ctx)?;
+        timer::yield_now();
+        Ok(())
+    }
+
+    /// Processes an action (from a key or gamepad button).
+    /// Moves between slots, takes gear off (back into the inventory), or leaves the character screen.
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+            Action::Up if self.selected > 0 => self.selected -= 1,
+            Action::Down if self.selected < EquipSlot::ALL.len() - 1 => self.selected += 1,
+            Action::Confirm => self.unequip(),
+            Action::Character | Action::Pause => return HandlerMessage::Bail,
+            _ => return HandlerMessage::Keep,
+        }
+        self.refresh(ctx);
+        HandlerMessage::Keep
+    }
+}
+
+impl CharacterState {
+    /// Creates a new CharacterState for the player with the weapon slot selected
+    pub fn new(ctx: &mut Context, player: &Player) -> CharacterState {
+        let stats = &player.stats;
+        let header = format!(
+            "Level {}\nhp: {}/{}\nmp: {}/{}",
+            stats.lv, stats.hp, stats.max_hp, stats.mp, stats.max_mp
+        );
+        let mut state = CharacterState {
+            text: graphics::Text::default(),
+            header,
+            base: StatBlock::base(stats),
+            equipment: player.equipment.clone(),
+            inventory: player.inventory.clone(),
+            selected: 0,
+        };
+        state.refresh(ctx);
+        state
+    }
+
+    /// Takes off the gear in the selected slot if there is room for it in the inventory.
+    fn unequip(&mut self) {
+        let slot = EquipSlot::ALL[self.selected];
+        let mut equipment = self.equipment.borrow_mut();
+        if let Some(gear) = equipment.get(slot) {
+            if self.inventory.borrow_mut().add(Item::Equipment(gear)) {
+                equipment.unequip(slot);
+            }
+        }
+    }
+
+    /// Rebuilds the text showing stats and gear.
+    fn refresh(&mut self, ctx: &mut Context) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let equipment = self.equipment.borrow();
+        let derived = self.base.plus(equipment.modifiers());
+
+        let mut text = format!("Character\n\n{}\n\n", self.header);
+        text.push_str(&format!("atk: {} ({:+})\n", derived.atk, derived.atk - self.base.atk));
+        text.push_str(&format!("def: {} ({:+})\n", derived.def, derived.def - self.base.def));
+        text.push_str(&format!("spd: {:.1} ({:+.1})\n\n", derived.spd, derived.spd - self.base.spd));
+
+        for (i, slot) in EquipSlot::ALL.iter().enumerate() {
+            let cursor = if i == self.selected { "> " } else { "  " };
+            let gear = match equipment.get(*slot) {
+                Some(gear) => format!("{:?}", gear),
+                None if *slot == EquipSlot::Weapon => "Sword".to_string(),
+                None => "-".to_string(),
+            };
+            text.push_str(&format!("{}{}: {}\n", cursor, slot.name(), gear));
+        }
+        text.push_str(&format!(
+            "\n{} to take off, {} to go back",
+            input::describe_key(Action::Confirm),
+            input::describe_key(Action::Character),
+        ));
+        self.text = graphics::Text::new((text, font, 18.0));
+    }
+}
+
+#[cfg(test)]
+mod testcharacter {
+    use super::*;
+
+    fn create_character_state_and_context() -> (CharacterState, Context) {
+        let (mut ctx, _event_loop) =
+            ggez::ContextBuilder::new("macguffin_quest", "James M. & William O.")
+            .add_resource_path(std::path::PathBuf::from("./resources/texture"))
+            .add_resource_path(std::path::PathBuf::from("./resources/font"))
+            .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
+            .build()
+            .unwrap();
+        let player = Player::new(&mut ctx, Class::ElfFighter);
+        let cs = CharacterState::new(&mut ctx, &player);
+        (cs, ctx)
+    }
+
+    #[test]
+    fn test_update() {
+        let (ref mut cs, ref mut ctx) = create_character_state_and_context();
+        match cs.update(ctx) {
+            HandlerMessage::Keep => (),
+            _ => panic!("HandlerMessage was not Keep"),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+/// Controls screen for game.
+/// Lists what each action is bound to and lets the player rebind them.
+pub struct ControlsState {
+    text: graphics::Text,
+    // index into input::Action::ALL (the row after them resets to the defaults)
+    selected: usize,
+    // waiting for the next key or button to bind to the selected action
+    waiting: bool,
+}
+
+/// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
+/// Allows the state machine to pass on information.
+impl CustomEventHandler for ControlsState {
+    /// Updates ControlsState.
+    /// Does not really do anything... Real magic happens in key_down_event.
+    fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
+        HandlerMessage::Keep
+    }
+
+    /// Draws ControlsState.
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        graphics::clear(ctx, graphics::BLACK);
+        let point = nalgebra::Point2::new(100.0, 50.0);
+
+        graphics::draw(
+            ctx,
+            &self.text,
+            graphics::DrawParam::default().dest(point),
+        )
+        .expect("ERROR drawing Controls Text");
+
+        graphics::present(ctx)?;
+        timer::yield_now();
+        Ok(())
+    }
+
+    /// Processes a key down event.
+    /// Rebinds the selected action if waiting, otherwise moves around the list.
+    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, _repeat: bool) -> HandlerMessage {
+        if self.waiting {
+            self.bind(ctx, input::Binding::Key(key));
+            return HandlerMessage::Keep
+        }
+
+        self.navigate(ctx, input::actions_for_key(key))
+    }
+
+    /// Processes a gamepad button press.
+    /// Same as key_down_event.
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button) -> HandlerMessage {
+        if self.waiting {
+            self.bind(ctx, input::Binding::Button(btn));
+            return HandlerMessage::Keep
+        }
+
+        self.navigate(ctx, input::actions_for_button(btn))
+    }
+}
+
+impl ControlsState {
+    /// Creates a new ControlsState with the first action selected
+    pub fn new(ctx: &mut Context) -> ControlsState {
+        let mut state = ControlsState {
+            text: graphics::Text::default(),
+            selected: 0,
+            waiting: false,
+        };
+        state.refresh(ctx);
+        state
+    }
+
+    /// Moves around the list, starts rebinding (or resets to the defaults), or leaves the controls screen.
+    /// Handled here (not in action_down_event) so the key used to rebind isn't also acted on.
+    fn navigate(&mut self, ctx: &mut Context, actions: Vec<Action>) -> HandlerMessage {
+        for action in actions {
+            match action {
+                Action::Up if self.selected > 0 => self.selected -= 1,
+                Action::Down if self.selected < Action::ALL.len() => self.selected += 1,
+                Action::Confirm if self.selected == Action::ALL.len() => self.reset(ctx),
+                Action::Confirm => self.waiting = true,
+                Action::Pause | Action::Quit => return HandlerMessage::Bail,
+                _ => continue,
+            }
+            self.refresh(ctx);
+            break
+        }
+        HandlerMessage::Keep
+    }
+
+    /// Puts every control back the way it started and saves them.
+    fn reset(&mut self, ctx: &mut Context) {
+        input::set_controls(input::Controls::default());
+        input::save(ctx);
+    }
+
+    /// Binds a key or button to the selected action and saves the controls.
+    fn bind(&mut self, ctx: &mut Context, binding: input::Binding) {
+        if !binding.bindable() {
+            return
+        }
+        input::rebind(Action::ALL[self.selected], binding);
+        input::save(ctx);
+        self.waiting = false;
+        self.refresh(ctx);
+    }
+
+    /// Rebuilds the text listing the controls.
+    fn refresh(&mut self, ctx: &mut Context) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let mut text = "Controls\n\n".to_string();
+        for (i, action) in Action::ALL.iter().enumerate() {
+            let cursor = if i == self.selected { "> " } else { "  " };
+            let bound = if i == self.selected && self.waiting {
+                "press a key or button...".to_string()
+            } else {
+                input::describe(*action)
+            };
+            text.push_str(&format!("{}{}: {}\n", cursor, action.name(), bound));
+        }
+        let cursor = if self.selected == Action::ALL.len() { "> " } else { "  " };
+        text.push_str(&format!("{}Reset to defaults\n", cursor));
+        text.push_str(&format!(
+            "\n{} to rebind, {} to go back",
+            input::describe(Action::Confirm),
+            input::describe(Action::Pause),
+        ));
+        self.text = graphics::Text::new((text, font, 18.0));
+    }
+}
+
+#[cfg(test)]
+mod testcontrols {
+    use super::*;
+
+    fn create_controls_state_and_context() -> (ControlsState, Context) {
+        let (mut ctx, _event_loop) =
+            ggez::ContextBuilder::new("macguffin_quest", "James M. & William O.")
+            .add_resource_path(std::path::PathBuf::from("./resources/texture"))
+            .add_resource_path(std::path::PathBuf::from("./resources/font"))
+            .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
+            .build()
+            .unwrap();
+        let cs = ControlsState::new(&mut ctx);
+        (cs, ctx)
+    }
+
+    #[test]
+    fn test_update() {
+        let (ref mut cs, ref mut ctx) = create_controls_state_and_context();
+        match cs.update(ctx) {
+            HandlerMessage::Keep => (),
+            _ => panic!("HandlerMessage was not Keep"),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+/// How much of the bottom of the screen the dialogue box covers.
+const DIALOGUE_BOX_HEIGHT: f32 = 200.0;
+
+/// Dialogue box for game.
+/// Drawn over the game (which waits underneath) while the player talks to an npc and picks what to say back.
+pub struct DialogueState {
+    text: graphics::Text,
+    // who is talking
+    speaker: String,
+    dialogue: Rc<Dialogue>,
+    // shared with the game, dialogue can give items and set flags
+    inventory: Rc<RefCell<Inventory>>,
+    story: Rc<RefCell<Story>>,
+    // player level at the time the conversation started
+    level: u32,
+    page: Page,
+    // choice the cursor is on
+    selected: usize,
+}
+
+/// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
+/// Allows the state machine to pass on information.
+impl CustomEventHandler for DialogueState {
+    /// Updates DialogueState.
+    /// Does not really do anything... Real magic happens in action_down_event.
+    fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
+        HandlerMessage::Keep
+    }
+
+    /// Draws DialogueState (a box along the bottom of the screen, over the game).
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let (width, height) = graphics::drawable_size(ctx);
+        let area = graphics::Rect::new(20.0, height - DIALOGUE_BOX_HEIGHT - 20.0, width - 40.0, DIALOGUE_BOX_HEIGHT);
+
+        let mut mb = graphics::MeshBuilder::new();
+        mb.rectangle(graphics::DrawMode::fill(), area, graphics::Color::new(0.0, 0.0, 0.0, 0.85));
+        mb.rectangle(graphics::DrawMode::stroke(3.0), area, graphics::WHITE);
+        let mesh = mb.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+
+        let point = nalgebra::Point2::new(area.x + 16.0, area.y + 12.0);
+        graphics::draw(
+            ctx,
+            &self.text,
+            graphics::DrawParam::default().dest(point),
+        )
+        .expect("ERROR drawing Dialogue Text");
+
+        graphics::present(ctx)?;
+        timer::yield_now();
+        Ok(())
+    }
+
+    /// Processes an action (from a key or gamepad button).
+    /// Moves between choices, picks one (the number keys pick straight away), or walks away.
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        let numbers = [Action::Spell1, Action::Spell2, Action::Spell3, Action::Spell4];
+        match action {
+            Action::Up if self.selected > 0 => self.selected -= 1,
+            Action::Down if self.selected + 1 < self.page.choices.len() => self.selected += 1,
+            Action::Confirm => return self.choose(ctx, self.selected),
+            Action::Pause => return HandlerMessage::Bail,
+            _ => match numbers.iter().position(|n| *n == action) {
+                Some(index) if index < self.page.choices.len() => return self.choose(ctx, index),
+                _ => return HandlerMessage::Keep,
+            },
+        }
+        self.refresh(ctx);
+        HandlerMessage::Keep
+    }
+
+    /// The game stays on screen behind the dialogue box.
+    fn overlay(&self) -> bool {
+        true
+    }
+}
+
+impl DialogueState {
+    /// Creates a new DialogueState at the start of the npc's dialogue.
+    /// Returns None if they have nothing to say.
+    pub fn new(
+        ctx: &mut Context,
+        speaker: &str,
+        dialogue: Rc<Dialogue>,
+        inventory: Rc<RefCell<Inventory>>,
+        story: Rc<RefCell<Story>>,
+        level: u32,
+    ) -> Option<DialogueState> {
+        let start = dialogue.start()?.to_string();
+        let mut state = DialogueState {
+            text: graphics::Text::default(),
+            speaker: speaker.to_string(),
+            dialogue,
+            inventory,
+            story,
+            level,
+            page: Page { text: String::new(), choices: Vec::new() },
+            selected: 0,
+        };
+        state.page = state.enter(&start)?;
+        state.refresh(ctx);
+        Some(state)
+    }
+
+    /// Moves the conversation on to a node (None once it is over).
+    fn enter(&mut self, id: &str) -> Option<Page> {
+        let mut inventory = self.inventory.borrow_mut();
+        let mut story = self.story.borrow_mut();
+        let mut who = Listener {
+            level: self.level,
+            inventory: &mut inventory,
+            story: &mut story,
+        };
+        self.dialogue.enter(id, &mut who)
+    }
+
+    /// Picks one of the choices (with nothing to pick the conversation is over).
+    fn choose(&mut self, ctx: &mut Context, index: usize) -> HandlerMessage {
+        let target = self.page.choices.get(index).and_then(|c| c.target.clone());
+        let page = match target {
+            Some(id) => self.enter(&id),
+            None => None,
+        };
+        match page {
+            Some(page) => {
+                self.page = page;
+                self.selected = 0;
+                self.refresh(ctx);
+                HandlerMessage::Keep
+            },
+            None => HandlerMessage::Bail,
+        }
+    }
+
+    /// Rebuilds the text in the dialogue box.
+    fn refresh(&mut self, ctx: &mut Context) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let mut text = format!("{}\n{}\n\n", self.speaker, self.page.text);
+        for (i, choice) in self.page.choices.iter().enumerate() {
+            let cursor = if i == self.selected { "> " } else { "  " };
+            text.push_str(&format!("{}{}. {}\n", cursor, i + 1, choice.text));
+        }
+        if self.page.choices.is_empty() {
+            text.push_str(&format!("  {} to finish\n", input::describe_key(Action::Confirm)));
+        }
+        self.text = graphics::Text::new((text, font, 18.0));
+    }
+}
+
+#[cfg(test)]
+mod testdialoguestate {
+    use super::*;
+
+    fn create_dialogue_state_and_context() -> (DialogueState, Context) {
+        let (mut ctx, _event_loop) =
+            ggez::ContextBuilder::new("macguffin_quest", "James M. & William O.")
+            .add_resource_path(std::path::PathBuf::from("./resources/texture"))
+            .add_resource_path(std::path::PathBuf::from("./resources/font"))
+            .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
+            .build()
+            .unwrap();
+        let dialogue = Rc::new(Dialogue::load(&mut ctx, "/macguffin_man.txt"));
+        let ds = DialogueState::new(
+            &mut ctx,
+            "The MacGuffin Man",
+            dialogue,
+            Rc::new(RefCell::new(Inventory::new())),
+            Rc::new(RefCell::new(Story::new())),
+            1,
+        )
+        .unwrap();
+        (ds, ctx)
+    }
+
+    #[test]
+    fn test_update() {
+        let (ref mut ds, ref mut ctx) = create_dialogue_state_and_context();
+        match ds.update(ctx) {
+            HandlerMessage::Keep => (),
+            _ => panic!("HandlerMessage was not Keep"),
+        }
+    }
+}
@@ -1,3 +1,16 @@
+/// How the run went, shown on the game over screen.
+pub struct RunStats {
+    pub class: Class,
+    pub level: u32,
+    pub kills: u32,
+    // gold found over the run (not what is left after shopping)
+    pub gold: u32,
+    // time spent playing (not counting pauses)
+    pub time: Duration,
+    // where the MacGuffin was hidden (the same seed hides it in the same place)
+    pub seed: u64,
+}
+
 /// Game over menu state for game.
 /// Sadly informs the player they have lost and lets them return to the main game state.
 pub struct GameOverState {
@@ -30,11 +43,11 @@ impl CustomEventHandler for GameOverState {
         Ok(())
     }
     
-    /// Processes a key down event.
+    /// Processes an action (from a key or gamepad button).
     /// This is where transitioning to main menu state occurs.
-    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, _repeat: bool) -> HandlerMessage {
-        match key {
-             KeyCode::Return => {
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+             Action::Confirm => {
                 let state = Box::new(MainMenuState::new(ctx));
                 HandlerMessage::Change(state)
             },
@@ -44,10 +57,22 @@ impl CustomEventHandler for GameOverState {
 }
 
 impl GameOverState {
-    /// Creates a new GameOverState with default values
-    pub fn new(ctx: &mut Context) -> GameOverState {
+    /// Creates a new GameOverState showing how the run went
+    pub fn new(ctx: &mut Context, run: RunStats) -> GameOverState {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
-        let t = graphics::Text::new(("GAME OVER!\nPress Enter To Go Back To The Main Menu".to_string(), font, 22.0));
+        let secs = run.time.as_secs();
+        let text = format!(
+            "GAME OVER!\n\nThe {} fell at level {}\nEnemies slain: {}\nGold found: {}\nTime: {}:{:02}\nSeed: {}\n\nPress {} To Go Back To The Main Menu",
+            run.class.name(),
+            run.level,
+            run.kills,
+            run.gold,
+            secs / 60,
+            secs % 60,
+            run.seed,
+            input::describe(Action::Confirm)
+        );
+        let t = graphics::Text::new((text, font, 22.0));
         GameOverState {
             text: t,
         }
@@ -64,9 +89,18 @@ mod testgameover {
             .add_resource_path(std::path::PathBuf::from("./resources/texture"))
             .add_resource_path(std::path::PathBuf::from("./resources/font"))
             .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
             .build()
             .unwrap();
-        let mm = GameOverState::new(&mut ctx);
+        let run = RunStats {
+            class: Class::ElfFighter,
+            level: 1,
+            kills: 0,
+            gold: 0,
+            time: Duration::new(0, 0),
+            seed: 0,
+        };
+        let mm = GameOverState::new(&mut ctx, run);
         (mm, ctx)
     }
 
@@ -0,0 +1,121 @@
+/// Inventory screen for game.
+/// Lists what the player is carrying and lets them use, drop or move things onto the hotbar.
+pub struct InventoryState {
+    text: graphics::Text,
+    // shared with the player, anything used or dropped here is dealt with once the game carries on
+    inventory: Rc<RefCell<Inventory>>,
+    // slot the cursor is on
+    selected: usize,
+}
+
+/// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
+/// Allows the state machine to pass on information.
+impl CustomEventHandler for InventoryState {
+    /// Updates InventoryState.
+    /// Does not really do anything... Real magic happens in action_down_event.
+    fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
+        HandlerMessage::Keep
+    }
+
+    /// Draws InventoryState.
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        graphics::clear(ctx, graphics::BLACK);
+        let point = nalgebra::Point2::new(100.0, 50.0);
+
+        graphics::draw(
+            ctx,
+            &self.text,
+            graphics::DrawParam::default().dest(point),
+        )
+        .expect("ERROR drawing Inventory Text");
+
+        graphics::present(ctx)?;
+        timer::yield_now();
+        Ok(())
+    }
+
+    /// Processes an action (from a key or gamepad button).
+    /// Moves around the list, uses, drops or puts things on the hotbar, or leaves the inventory.
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+            Action::Up if self.selected > 0 => self.selected -= 1,
+            Action::Down if self.selected < inventory::SLOTS - 1 => self.selected += 1,
+            Action::Confirm => self.inventory.borrow_mut().use_slot(self.selected),
+            Action::Attack => self.inventory.borrow_mut().drop_slot(self.selected),
+            Action::Hotbar1 => self.inventory.borrow_mut().swap(self.selected, 0),
+            Action::Hotbar2 => self.inventory.borrow_mut().swap(self.selected, 1),
+            Action::Hotbar3 => self.inventory.borrow_mut().swap(self.selected, 2),
+            Action::Inventory | Action::Pause => return HandlerMessage::Bail,
+            _ => return HandlerMessage::Keep,
+        }
+        self.refresh(ctx);
+        HandlerMessage::Keep
+    }
+}
+
+impl InventoryState {
+    /// Creates a new InventoryState with the first slot selected
+    pub fn new(ctx: &mut Context, inventory: Rc<RefCell<Inventory>>) -> InventoryState {
+        let mut state = InventoryState {
+            text: graphics::Text::default(),
+            inventory,
+            selected: 0,
+        };
+        state.refresh(ctx);
+        state
+    }
+
+    /// Rebuilds the text listing the inventory.
+    fn refresh(&mut self, ctx: &mut Context) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let inv = self.inventory.borrow();
+        let mut text = "Inventory\n\n".to_string();
+        for i in 0..inventory::SLOTS {
+            let cursor = if i == self.selected { "> " } else { "  " };
+            let hotbar = if i < inventory::HOTBAR { format!("[{}] ", i + 1) } else { "    ".to_string() };
+            let item = match inv.slot(i) {
+                Some(slot) if slot.count > 1 => format!("{} x{}", slot.item.name(), slot.count),
+                Some(slot) => slot.item.name(),
+                None => "-".to_string(),
+            };
+            text.push_str(&format!("{}{}{}\n", cursor, hotbar, item));
+        }
+        text.push_str(&format!(
+            "\n{} to use, {} to drop, {} / {} / {} to put on the hotbar, {} to go back",
+            input::describe_key(Action::Confirm),
+            input::describe_key(Action::Attack),
+            input::describe_key(Action::Hotbar1),
+            input::describe_key(Action::Hotbar2),
+            input::describe_key(Action::Hotbar3),
+            input::describe_key(Action::Inventory),
+        ));
+        self.text = graphics::Text::new((text, font, 18.0));
+    }
+}
+
+#[cfg(test)]
+mod testinventorystate {
+    use super::*;
+
+    fn create_inventory_state_and_context() -> (InventoryState, Context) {
+        let (mut ctx, _event_loop) =
+            ggez::ContextBuilder::new("macguffin_quest", "James M. & William O.")
+            .add_resource_path(std::path::PathBuf::from("./resources/texture"))
+            .add_resource_path(std::path::PathBuf::from("./resources/font"))
+            .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
+            .build()
+            .unwrap();
+        let is = InventoryState::new(&mut ctx, Rc::new(RefCell::new(Inventory::new())));
+        (is, ctx)
+    }
+
+    #[test]
+    fn test_update() {
+        let (ref mut is, ref mut ctx) = create_inventory_state_and_context();
+        match is.update(ctx) {
+            HandlerMessage::Keep => (),
+            _ => panic!("HandlerMessage was not Keep"),
+        }
+    }
+}
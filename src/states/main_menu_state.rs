@@ -1,23 +1,33 @@
+/// Rows on the main menu: one for each adventurer, then the controls screen.
+const MENU_ROWS: usize = Class::ALL.len() + 1;
+
 /// Main menu state for game.
 /// Allows character selection and causes state transition to the main game state.
 pub struct MainMenuState {
     text: graphics::Text,
-    chosen_player: String,
+    // row the cursor is on (index into Class::ALL, or the controls row after them)
+    selected: usize,
+    // controls may have been rebound, so the text needs building again
+    stale: bool,
 }
 
 /// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
 /// Allows the state machine to pass on information.
 impl CustomEventHandler for MainMenuState {
     /// Updates MainMenuState.
-    /// Does not really do anything... Real magic happens in key_down_event.
-    fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
+    /// Rebuilds the text after coming back from the controls screen... Real magic happens in action_down_event.
+    fn update(&mut self, ctx: &mut Context) -> HandlerMessage {
+        if self.stale {
+            self.stale = false;
+            self.refresh(ctx);
+        }
         HandlerMessage::Keep
     }
     
     /// Draws MainMenuState.
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
         graphics::clear(ctx, graphics::BLACK);
-        let point = nalgebra::Point2::new(250.0, 175.0);
+        let point = nalgebra::Point2::new(250.0, 100.0);
         
         graphics::draw(
             ctx,
@@ -30,32 +40,25 @@ impl CustomEventHandler for MainMenuState {
         timer::yield_now();
         Ok(())
     }
-    
-    /// Processes a key down event.
-    /// This is where character selection and transitioning to main game state occurs.
-    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, _repeat: bool) -> HandlerMessage {
-        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
-
-        if !self.chosen_player.is_empty() && key == KeyCode::Return {
-            let state = Box::new(MainState::new(ctx, self.chosen_player.to_string()));
-            return HandlerMessage::Spawn(state);
-        }
 
-        match key {
-            KeyCode::E => {
-                self.chosen_player = "/elf_fighter.png".to_string();
-                self.text = graphics::Text::new(("Macguffin Quest\n\n\nYou Chose the Elf Fighter!\n\nInstructions:\nASWD: Move\nQ: Spell\nSpace: Attack\nHold Shift to Run\nP: Pause\n\nPress Enter to Start".to_string(), font, 22.0));
-            },
-            KeyCode::S => {
-                self.chosen_player = "/dapper-skeleton-sheet.png".to_string();
-                self.text = graphics::Text::new(("Macguffin Quest\n\n\nYou Chose the Dapper Skeleton!\n\nInstructions:\nASWD: Move\nQ: Spell\nSpace: Attack\nHold Shift to Run\nP: Pause\n\nPress Enter to Start".to_string(), font, 22.0));
-            },
-            _ => {
-                self.chosen_player = "".to_string();
-                self.text = graphics::Text::new(("Macguffin Quest\n\n\nPlease Choose an Adventurer!\n\nPress E for Elf Fighter\nPress S for Dapper Skeleton".to_string(), font, 22.0));
+    /// Processes an action (from a key or gamepad button).
+    /// This is where character selection and transitioning to the main game state (or the controls screen) occurs.
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+            Action::Up | Action::Left if self.selected > 0 => self.selected -= 1,
+            Action::Down | Action::Right if self.selected + 1 < MENU_ROWS => self.selected += 1,
+            Action::Confirm => {
+                return match Class::ALL.get(self.selected) {
+                    Some(class) => HandlerMessage::Spawn(Box::new(MainState::new(ctx, *class))),
+                    None => {
+                        self.stale = true;
+                        HandlerMessage::Spawn(Box::new(ControlsState::new(ctx)))
+                    },
+                }
             },
-        };
-
+            _ => return HandlerMessage::Keep,
+        }
+        self.refresh(ctx);
         HandlerMessage::Keep
     }
 }
@@ -63,12 +66,56 @@ impl CustomEventHandler for MainMenuState {
 impl MainMenuState {
     /// Creates a new MainMenuState with default values
     pub fn new(ctx: &mut Context) -> MainMenuState {
+        let mut state = MainMenuState {
+            text: graphics::Text::default(),
+            selected: 0,
+            stale: false,
+        };
+        state.refresh(ctx);
+        state
+    }
+
+    /// Rebuilds the menu text: the adventurers to choose from, what the highlighted one can do,
+    /// and how to play using whatever the controls are currently bound to.
+    fn refresh(&mut self, ctx: &mut Context) {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
-        let t = graphics::Text::new(("Macguffin Quest\n\n\nPlease Choose an Adventurer!\n\nPress E for Elf Fighter\nPress S for Dapper Skeleton".to_string(), font, 22.0));
-        MainMenuState {
-            text: t,
-            chosen_player: "".to_string(),
+        let mut text = "Macguffin Quest\n\n\nPlease Choose an Adventurer!\n\n".to_string();
+        for (i, class) in Class::ALL.iter().enumerate() {
+            let cursor = if i == self.selected { "> " } else { "  " };
+            text.push_str(&format!("{}{} ({})\n", cursor, class.name(), class.describe()));
         }
+        let cursor = if self.selected == Class::ALL.len() { "> " } else { "  " };
+        text.push_str(&format!("{}Controls\n\n", cursor));
+
+        if let Some(class) = Class::ALL.get(self.selected) {
+            text.push_str(&format!("{}: {}\n\n", class.ability().name(), class.ability().describe()));
+        }
+        text.push_str(&MainMenuState::instructions());
+        text.push_str(&format!(
+            "\n\n{} / {} to choose, {} to select",
+            input::describe(Action::Up),
+            input::describe(Action::Down),
+            input::describe(Action::Confirm),
+        ));
+        self.text = graphics::Text::new((text, font, 22.0));
+    }
+
+    /// How to play, using whatever the controls are currently bound to.
+    fn instructions() -> String {
+        format!(
+            "Instructions:\n{}{}{}{}: Move\n{}: Cast Spell\n{}-{} or {}: Choose Spell\n{}: Attack\nHold {} to Run\n{}: Pause",
+            input::describe_key(Action::Up),
+            input::describe_key(Action::Left),
+            input::describe_key(Action::Down),
+            input::describe_key(Action::Right),
+            input::describe_key(Action::Cast),
+            input::describe_key(Action::Spell1),
+            input::describe_key(Action::Spell4),
+            input::describe_key(Action::NextSpell),
+            input::describe_key(Action::Attack),
+            input::describe_key(Action::Run),
+            input::describe_key(Action::Pause),
+        )
     }
 }
 
@@ -82,6 +129,7 @@ mod testmain {
             .add_resource_path(std::path::PathBuf::from("./resources/texture"))
             .add_resource_path(std::path::PathBuf::from("./resources/font"))
             .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
             .build()
             .unwrap();
         let mm = MainMenuState::new(&mut ctx);
@@ -1,12 +1,40 @@
 use super::entities;
 use entities::{CollideEntity, DrawableEntity};
-use entities::player::playerstruct::Player;
+use entities::player::{class::Class, playerstruct::Player};
 use entities::enemies::{ai::AITypes,blob::Blob, skeleton::Skeleton, ghost::Ghost, boss::Boss, enemiesstruct::*};
-use entities::environment::{level::Level, level_builder::LevelBuilder};
-use entities::items::{macguffin::Macguffin, potions::Potions};
-use entities::npcs::macguffin_man::MacguffinMan;
+use entities::environment::{level::Level, level_builder::LevelBuilder, trap::Trap, container::{Breakable, Chest, Lid}, door};
+use entities::items::{inventory::Item, loot::{Loot, Pile}, macguffin::{Hiding, Placement}};
+use entities::enemies::spawner::EnemyKind;
+use entities::combat;
+use entities::movement::unit;
+use entities::npcs::{macguffin_man::MacguffinMan, merchant::Merchant};
 
 use super::ui::UI;
+use std::time::Duration;
+use std::f32::consts::PI;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+/// Where the boss shows up once the MacGuffin is taken (the big hall between the MacGuffin and the MacGuffin Man).
+const BOSS_SPAWN: (f32, f32) = (1050.0, 250.0);
+
+/// Where the MacGuffin is hidden if the level doesn't say (top of the tower north of the start).
+const MACGUFFIN_SPOT: (f32, f32) = (1050.0, -650.0);
+
+/// How far from a decoy the enemies it was hiding jump out.
+const AMBUSH_RADIUS: f32 = 64.0;
+
+/// How far in front of the player dropped items land (so they aren't picked straight back up).
+const DROP_DISTANCE: f32 = 80.0;
+
+/// How long the screen takes to fade out once the player has finished dying.
+const FADE_TIME: Duration = Duration::from_millis(1500);
+
+/// How many times a second the game is updated (no matter how fast it is drawn).
+const UPDATES_PER_SECOND: u32 = 60;
+/// Time covered by each update.
+const FIXED_STEP: Duration = Duration::from_nanos(1_000_000_000 / UPDATES_PER_SECOND as u64);
+/// Most updates run for a single frame before the game gives up catching up.
+const MAX_STEPS_PER_FRAME: u32 = 5;
 
 /// Game state for game (where actual gameplay happens!).
 /// Can transition to the pause menu.
@@ -15,93 +43,67 @@ pub struct MainState {
     player: Player,
     enemies: Enemies,
     level: Level,
-	macguffin: Option<Macguffin>,
-	potions: Vec<Potions>,
+	// everything lying on the ground (placed in the level, dropped by enemies or by the player)
+	loot: Vec<Loot>,
+	// what jumps out of decoy MacGuffins
+	ambush: Option<(EnemyKind, usize)>,
+	// picks where the MacGuffin is hidden (shown on the game over screen)
+	seed: u64,
+	traps: Vec<Trap>,
+	chests: Vec<Chest>,
+	// pots and crates
+	breakables: Vec<Breakable>,
 	//npcs: Vec<NPCs>,
-	macguffin_man: MacguffinMan
+	macguffin_man: MacguffinMan,
+	// what the MacguffinMan has to say
+	dialogue: Rc<Dialogue>,
+	// flags set by talking to npcs (shared with the dialogue box)
+	story: Rc<RefCell<Story>>,
+	merchants: Vec<Merchant>,
+	// what the merchants buy and sell things for
+	prices: Prices,
+	// time played (for the game over screen)
+	elapsed: Duration,
+	// how far the screen has faded out after the player died
+	fade: Duration,
 }
 
 /// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
 /// Allows the state machine to pass on information.
 impl CustomEventHandler for MainState {
     /// Updates MainState...
-    /// Which updates everything relevant to the game (like player, enemies, level, etc)
+    /// Which steps everything relevant to the game (like player, enemies, level, etc) on a fixed timestep.
     fn update(&mut self, ctx: &mut Context) -> HandlerMessage {
-        let delta = timer::delta(ctx);
-
-        let playerx = self.player.x;
-        let playery = self.player.y;
-		
-		// Collision with potions
-		for p in &mut self.potions {
-			if self.player.collision(p) && self.player.stats.hp < self.player.stats.max_hp {
-				self.player.pick_up(ctx, (self.player.stats.max_hp - self.player.stats.hp).to_string());
-				self.player.stats.hp = self.player.stats.max_hp;
-				p.used = true;
-			}
-		}
-		
-		// Collision with macguffin
-		if let Some(mac) = &self.macguffin {
-			if mac.collision(&self.player) {
-				self.player.macguffin = true; // Make a inventory system later
-				self.enemies.push(Box::new(Boss::new(ctx, 1000.0, 1000.0, AITypes::Boss)));
-				self.player.pick_up(ctx, "You Picked Up The MacGuffin!\nA Boss Has Appeared!".to_string());
-			}
-		}
-		
-		// Remove the macguffin.
-		if self.player.macguffin {
-			self.macguffin = None;
-		}
+        let mut steps = 0;
+        while timer::check_update_time(ctx, UPDATES_PER_SECOND) {
+            // way behind (ie just came back from pausing), drop the time instead of racing to catch up
+            if steps == MAX_STEPS_PER_FRAME {
+                while timer::check_update_time(ctx, UPDATES_PER_SECOND) {}
+                break;
+            }
+            steps += 1;
 
-		// another check because of text being setup before ui.update()
-		if self.player.collision(&self.macguffin_man) {
-			if self.player.macguffin {
-				self.macguffin_man.talk(ctx, "You Found The MacGuffin!".to_string());
-			}
-			else {
-				self.macguffin_man.talk(ctx, "Please Bring Me The MacGuffin!".to_string());
-			}
-		}
-		self.macguffin_man.update(delta);
-        self.enemies.update(ctx, delta, &mut self.player, &self.level);
-		self.player.update(ctx, delta);
-		
-		// This could move into the player struct.
-		// Needs to be after the player.update()
-        if self.player.collision(&self.level) {
-            self.player.move_location(playerx, playery);
-        }
-		
-		// Update potions
-		self.potions.retain(|t| !t.used);
-		
-        // Should prob make UI update last all the time.
-        self.ui.update(ctx, self.player.stats.hp, self.player.stats.max_hp, self.player.stats.mp, self.player.stats.max_mp, self.player.stats.lv);
-        
-        // Should prob have it delayed untill after death animation...
-        if self.player.stats.hp <= 0.0 {
-            let state = Box::new(GameOverState::new(ctx));
-            HandlerMessage::Change(state)
-        }
-		else if self.player.collision(&self.macguffin_man) {
-			if self.player.macguffin {
-				let state = Box::new(VictoryState::new(ctx));
-				HandlerMessage::Change(state)
-			}
-			else {
-				HandlerMessage::Keep
-			}
-		}
-        else {
-            HandlerMessage::Keep
+            match self.step(ctx, FIXED_STEP) {
+                HandlerMessage::Keep => (),
+                message => return message,
+            }
         }
+        HandlerMessage::Keep
     }
 
     /// Draws MainState...
     /// Which draws everything relevant to the game (like player, enemies, level, etc)
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        self.draw_scene(ctx)?;
+
+        // This presents the contents of ctx to the game.
+        graphics::present(ctx)?;
+        timer::yield_now();
+        Ok(())
+    }
+
+    /// Draws the game without presenting it (so dialogue boxes can go on top).
+    fn draw_scene(&mut self, ctx: &mut Context) -> GameResult {
         graphics::clear(ctx, graphics::BLACK);
         
         // change screen coords so it seems like following player
@@ -113,19 +115,31 @@ impl CustomEventHandler for MainState {
         )?;
 
         self.level.draw(ctx)?;
+		
+		for t in &self.traps {
+			t.draw(ctx)?;
+		}
+		
+		for c in &self.chests {
+			c.draw(ctx)?;
+		}
+		
+		for b in &self.breakables {
+			b.draw(ctx)?;
+		}
 
         self.player.draw(ctx)?;
 
         self.enemies.draw(ctx)?;
 		
-		if let Some(mac) = &self.macguffin {
-            mac.draw(ctx).expect("Failed to draw macguffin.");
-        }
-		
 		self.macguffin_man.draw(ctx)?;
 		
-		for p in &self.potions {
-			p.draw(ctx)?;
+		for m in &self.merchants {
+			m.draw(ctx)?;
+		}
+		
+		for l in &self.loot {
+			l.draw(ctx)?;
 		}
         
         // reset screen coordinates for drawing UI
@@ -133,31 +147,291 @@ impl CustomEventHandler for MainState {
         
         self.ui.draw(ctx);
 
-        // This presents the contents of ctx to the game.
-        graphics::present(ctx)?;
-        timer::yield_now();
+        // fade out once the player has died
+        if self.fade > Duration::new(0, 0) {
+            let alpha = (self.fade.as_secs_f32() / FADE_TIME.as_secs_f32()).min(1.0);
+            let swh = graphics::drawable_size(ctx);
+            let cover = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(0.0, 0.0, swh.0, swh.1),
+                graphics::Color::new(0.0, 0.0, 0.0, alpha),
+            )?;
+            graphics::draw(ctx, &cover, graphics::DrawParam::default())?;
+        }
         Ok(())
     }
 
-    /// Processes a key down event.
+    /// Processes an action (from a key or gamepad button).
     /// This is where transitioning to pause state occurs.
-    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, _repeat: bool) -> HandlerMessage {
-        match key {
-            KeyCode::P => {
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        // no more input once the player has died
+        if self.player.dead() {
+            return HandlerMessage::Keep;
+        }
+        match action {
+            Action::Pause => {
                 let state = Box::new(PauseState::new(ctx));
                 HandlerMessage::Spawn(state)
             },
+            // picking spells
+            Action::NextSpell => {
+                self.player.spellbook.next();
+                HandlerMessage::Keep
+            },
+            Action::Spell1 | Action::Spell2 | Action::Spell3 | Action::Spell4 => {
+                let spells = [Action::Spell1, Action::Spell2, Action::Spell3, Action::Spell4];
+                let index = spells.iter().position(|s| *s == action).unwrap();
+                self.player.spellbook.select(index);
+                HandlerMessage::Keep
+            },
+            // using things on the hotbar
+            Action::Hotbar1 | Action::Hotbar2 | Action::Hotbar3 => {
+                let hotbar = [Action::Hotbar1, Action::Hotbar2, Action::Hotbar3];
+                let index = hotbar.iter().position(|h| *h == action).unwrap();
+                self.player.use_hotbar(index);
+                HandlerMessage::Keep
+            },
+            Action::Inventory => {
+                let state = Box::new(InventoryState::new(ctx, self.player.inventory.clone()));
+                HandlerMessage::Spawn(state)
+            },
+            Action::Character => {
+                let state = Box::new(CharacterState::new(ctx, &self.player));
+                HandlerMessage::Spawn(state)
+            },
+            // rolls start on the press, holding the key doesn't keep rolling
+            Action::Dodge => {
+                self.player.dodge.request();
+                HandlerMessage::Keep
+            },
+            Action::Interact => self.interact(ctx),
             _ => HandlerMessage::Keep
         }
     }
 }
 
 impl MainState {
+    /// Moves the game forward by delta...
+    /// Which updates everything relevant to the game (like player, enemies, level, etc)
+    fn step(&mut self, ctx: &mut Context, delta: Duration) -> HandlerMessage {
+
+        // the player has died, everything stops while they finish dying
+        if self.player.dead() {
+            return self.step_dying(ctx, delta);
+        }
+        self.elapsed += delta;
+
+        // a hit just landed, hold everything still for a moment
+        if self.player.hit_stopped(delta) {
+            return HandlerMessage::Keep;
+        }
+
+		// Collision with loot (the MacGuffin goes in the inventory as a quest item and wakes the boss)
+		for l in &mut self.loot {
+			if !self.player.collision(l) {
+				continue;
+			}
+			if l.pile.item == Item::MacGuffin {
+				if self.player.inventory.borrow_mut().add(Item::MacGuffin) {
+					l.used = true;
+					let boss = Box::new(Boss::new(ctx, BOSS_SPAWN.0, BOSS_SPAWN.1, AITypes::Boss));
+					self.enemies.push(ctx, boss);
+					self.player.pick_up(ctx, "You Picked Up The MacGuffin!\nA Boss Has Appeared!".to_string());
+				}
+			}
+			// a decoy falls apart and whatever was hiding in it jumps out
+			else if l.pile.item == Item::Decoy {
+				l.used = true;
+				if let Some((kind, amount)) = self.ambush {
+					for i in 0..amount {
+						let angle = 2.0 * PI * i as f32 / amount as f32;
+						let x = l.x + angle.cos() * AMBUSH_RADIUS;
+						let y = l.y + angle.sin() * AMBUSH_RADIUS;
+						let enemy = kind.create(ctx, x, y, AITypes::MeleeDirect);
+						self.enemies.push(ctx, enemy);
+					}
+				}
+				self.player.pick_up(ctx, "It Was A Fake!".to_string());
+			}
+			else if self.player.collect(ctx, l.pile) {
+				l.used = true;
+			}
+		}
+		
+		// Stepping on traps
+		for t in &mut self.traps {
+			t.update(delta);
+			if self.player.collision(t) {
+				if let Some(kind) = t.spring() {
+					self.player.apply_status(kind);
+				}
+			}
+		}
+		
+		// another check because of text being setup before ui.update()
+		if self.macguffin_man.in_reach(self.player.get_hitbox()) {
+			self.macguffin_man.talk(ctx, format!("{} To Talk", input::describe_key(Action::Interact)));
+		}
+		self.macguffin_man.update(delta);
+		for m in &mut self.merchants {
+			if m.in_reach(self.player.get_hitbox()) {
+				m.talk(ctx, format!("{} To Trade", input::describe_key(Action::Interact)));
+			}
+			m.update(delta);
+		}
+		// chests and unbroken pots and crates are in the way of everyone
+		let props = self.chests.iter().map(|c| c.get_hitbox())
+			.chain(self.breakables.iter().filter(|b| !b.broken).map(|b| b.get_hitbox()))
+			.collect();
+		self.level.set_props(props);
+        self.enemies.update(ctx, delta, &mut self.player, &self.level);
+        // locked encounter rooms wall the player in
+        self.level.set_barriers(self.enemies.barriers());
+		// the player slides along walls instead of walking into them
+		self.player.update(ctx, delta, &self.level);
+        self.player.resolve_knockback(&self.level, delta);
+		
+		// Items used or dropped from the hotbar or inventory screen
+		self.apply_inventory(ctx);
+		
+		// Chests finish opening and pots or crates the player hits break, spilling whatever was inside
+		for c in &mut self.chests {
+			for (pile, x, y) in c.update(delta) {
+				self.loot.push(Loot::new(ctx, x, y, pile));
+			}
+		}
+		for b in &mut self.breakables {
+			if !b.broken && self.player.attack_hitting(b, b.id).is_some() {
+				for (pile, x, y) in b.smash() {
+					self.loot.push(Loot::new(ctx, x, y, pile));
+				}
+			}
+		}
+		
+		// Update loot (and drop anything new from dead enemies)
+		self.loot.retain(|t| !t.used);
+		for (pile, x, y) in self.enemies.take_drops() {
+			self.loot.push(Loot::new(ctx, x, y, pile));
+		}
+		
+        // Should prob make UI update last all the time.
+        self.ui.update(ctx, self.player.stats.hp, self.player.stats.max_hp, self.player.stats.mp, self.player.stats.max_mp, self.player.stats.lv);
+        self.ui.update_boss(self.enemies.boss_health());
+        self.ui.update_status(self.player.status.active());
+        self.ui.update_exp(self.player.stats.exp, self.player.stats.exp_to_next());
+        self.ui.update_stamina(self.player.stats.stamina, self.player.stats.max_stamina);
+        self.ui.update_spell(ctx, &self.player.spellbook);
+        self.ui.update_hotbar(ctx, &self.player.inventory.borrow());
+        self.ui.update_gold(ctx, self.player.inventory.borrow().gold());
+        
+        // the MacguffinMan has been handed the MacGuffin
+        if self.story.borrow().victory {
+			let state = Box::new(VictoryState::new(ctx));
+			HandlerMessage::Change(state)
+		}
+        else {
+            HandlerMessage::Keep
+        }
+    }
+
+    /// Plays out the player's death...
+    /// The die animation plays to the end, then the screen fades before the game over screen shows up.
+    fn step_dying(&mut self, ctx: &mut Context, delta: Duration) -> HandlerMessage {
+        if !self.player.update_dying(delta) {
+            return HandlerMessage::Keep;
+        }
+        self.fade += delta;
+        if self.fade < FADE_TIME {
+            return HandlerMessage::Keep;
+        }
+        let run = RunStats {
+            class: self.player.class,
+            level: self.player.stats.lv,
+            kills: self.enemies.kills(),
+            gold: self.player.inventory.borrow().gold_found(),
+            time: self.elapsed,
+            seed: self.seed,
+        };
+        let state = Box::new(GameOverState::new(ctx, run));
+        HandlerMessage::Change(state)
+    }
+
+    /// Talks to the MacguffinMan or opens the shop of the merchant the player is next to,
+    /// or unlocks the door or opens the chest the player is next to (locked ones use up a key).
+    fn interact(&mut self, ctx: &mut Context) -> HandlerMessage {
+        let reach = self.player.get_hitbox();
+        if self.macguffin_man.in_reach(reach) {
+            let state = DialogueState::new(
+                ctx,
+                "The MacGuffin Man",
+                self.dialogue.clone(),
+                self.player.inventory.clone(),
+                self.story.clone(),
+                self.player.stats.lv,
+            );
+            return match state {
+                Some(state) => HandlerMessage::Spawn(Box::new(state)),
+                None => HandlerMessage::Keep,
+            };
+        }
+        if self.merchants.iter().any(|m| m.in_reach(reach)) {
+            let state = Box::new(ShopState::new(ctx, self.player.inventory.clone(), self.prices.clone()));
+            return HandlerMessage::Spawn(state);
+        }
+        let door = self.level.doors.iter().position(|d| !d.open && d.in_reach(reach));
+        if let Some(index) = door {
+            let (colour, consume) = (self.level.doors[index].colour, self.level.doors[index].consume);
+            let key = Item::ColouredKey(colour);
+            if !self.player.inventory.borrow().has(key) {
+                self.player.pick_up(ctx, format!("Needs {}", key.name()));
+                return HandlerMessage::Keep;
+            }
+            if consume {
+                self.player.inventory.borrow_mut().remove(key);
+            }
+            door::open_joined(&mut self.level.doors, index);
+            return HandlerMessage::Keep;
+        }
+        let chest = self.chests.iter_mut().find(|c| c.lid == Lid::Closed && c.in_reach(reach));
+        if let Some(chest) = chest {
+            if chest.locked {
+                if !self.player.inventory.borrow_mut().remove(Item::Key) {
+                    self.player.pick_up(ctx, "Locked".to_string());
+                    return HandlerMessage::Keep;
+                }
+                chest.locked = false;
+            }
+            chest.open();
+        }
+        HandlerMessage::Keep
+    }
+
+    /// Deals with items taken out of the inventory...
+    /// Used ones take effect (or go back if they can't be used right now) and dropped ones land in front of the player.
+    fn apply_inventory(&mut self, ctx: &mut Context) {
+        let pending = self.player.inventory.borrow_mut().take_pending();
+        for (item, from) in pending {
+            match from {
+                Some(slot) => if !self.player.use_item(ctx, item) {
+                    self.player.inventory.borrow_mut().put_back(slot, item);
+                },
+                None => {
+                    let (dx, dy) = unit(self.player.direction);
+                    let x = self.player.x + dx * DROP_DISTANCE;
+                    let y = self.player.y + dy * DROP_DISTANCE;
+                    self.loot.push(Loot::new(ctx, x, y, Pile::one(item)));
+                },
+            }
+        }
+    }
+
     /// Creates a new MainState with player value
-    pub fn new(ctx: &mut Context, chosen_player: String) -> MainState {
+    pub fn new(ctx: &mut Context, class: Class) -> MainState {
          // create player
-        let mut player = Player::new(ctx, chosen_player);
+        let mut player = Player::new(ctx, class);
         player.move_location(150f32, 150f32);
+        player.home = (150f32, 150f32);
         let hp = player.stats.hp;
         let max_hp = player.stats.max_hp;
         let mp = player.stats.mp;
@@ -166,39 +440,82 @@ impl MainState {
 
         // create enemies
         let mut e = Enemies::new();
-        e.push(Box::new(Blob::new(ctx, 700.0, 250.0, AITypes::MeleeDirect)));
-        e.push(Box::new(Blob::new(ctx, 700.0, 350.0, AITypes::MeleeDirect)));
-        e.push(Box::new(Blob::new(ctx, 700.0, 150.0, AITypes::MeleeDirect)));
-        e.push(Box::new(Skeleton::new(ctx, 950.0, 300.0, AITypes::MeleeLineOfSight)));
-        e.push(Box::new(Skeleton::new(ctx, 950.0, -350.0, AITypes::MeleeLineOfSight)));
-        e.push(Box::new(Skeleton::new(ctx, 1200.0, -350.0, AITypes::MeleeLineOfSight)));
-        e.push(Box::new(Ghost::new(ctx, 600.0, 550.0, AITypes::MeleeLineOfSight)));
-        e.push(Box::new(Ghost::new(ctx, 1600.0, 150.0, AITypes::MeleeLineOfSight)));
-        e.push(Box::new(Ghost::new(ctx, 1600.0, 350.0, AITypes::MeleeLineOfSight)));
-        e.push(Box::new(Ghost::new(ctx, 1600.0, 550.0, AITypes::MeleeLineOfSight)));
+        let placed: Vec<Box<dyn Positioned>> = vec![
+            Box::new(Blob::new(ctx, 700.0, 250.0, AITypes::MeleeDirect)),
+            Box::new(Blob::new(ctx, 700.0, 350.0, AITypes::MeleeDirect)),
+            Box::new(Blob::new(ctx, 700.0, 150.0, AITypes::MeleeDirect)),
+            Box::new(Skeleton::new(ctx, 950.0, 300.0, AITypes::MeleeLineOfSight)),
+            Box::new(Skeleton::new(ctx, 950.0, -350.0, AITypes::MeleeLineOfSight)),
+            Box::new(Skeleton::new(ctx, 1200.0, -350.0, AITypes::MeleeLineOfSight)),
+            Box::new(Ghost::new(ctx, 600.0, 550.0, AITypes::MeleeLineOfSight)),
+            Box::new(Ghost::new(ctx, 1600.0, 150.0, AITypes::MeleeLineOfSight)),
+            Box::new(Ghost::new(ctx, 1600.0, 350.0, AITypes::MeleeLineOfSight)),
+            Box::new(Ghost::new(ctx, 1600.0, 550.0, AITypes::MeleeLineOfSight)),
+        ];
+        for enemy in placed {
+            e.push(ctx, enemy);
+        }
 
         // build level
         let img = graphics::Image::new(ctx, "/testwalls.png").unwrap();
         let mut lb = LevelBuilder::new(ctx, None);
-        let level = lb.fromfile(ctx, &img, &"/BasicLevel.lvl".to_string());
+        let level = lb.fromfile(ctx, &img, "/BasicLevel.lvl");
+        e.load(&level);
+        let traps = Trap::parse_all(&level.extras);
 
-		let mac = Macguffin::new(ctx, 1050.0, -650.0);
+		// every run gets its own seed, which decides where the MacGuffin is hidden and how the fights roll
+		let seed = thread_rng().gen::<u64>();
+		let mut rng = StdRng::seed_from_u64(seed);
+		combat::seed(seed);
+		
+		// chests, pots and crates are filled up front (so the seed decides what is in them too)
+		let mut chests = Chest::parse_all(&level.extras);
+		chests.iter_mut().for_each(|c| c.fill(&mut rng));
+		let mut breakables = Breakable::parse_all(&level.extras);
+		breakables.iter_mut().for_each(|b| b.fill(&mut rng));
 		
-		let mut pot = Vec::new();
-		pot.push(Potions::new(ctx, 1800.0, 350.0));
-		pot.push(Potions::new(ctx, 250.0, 250.0));
+		// items placed in the level, then the MacGuffin in one of its hiding spots (and decoys in some of the others)
+		let mut placed_items = Loot::parse_all(&level.extras);
+		let hiding = Hiding::parse(&level.extras);
+		let placed = hiding.choose(&mut rng).unwrap_or(Placement { real: MACGUFFIN_SPOT, decoys: Vec::new() });
+		placed_items.push((Pile::one(Item::MacGuffin), placed.real.0, placed.real.1));
+		for (x, y) in placed.decoys {
+			placed_items.push((Pile::one(Item::Decoy), x, y));
+		}
+		// anything placed on a chest goes inside it
+		let mut loot = Vec::new();
+		for (pile, x, y) in placed_items {
+			match chests.iter_mut().find(|c| c.holds(x, y)) {
+				Some(chest) => chest.contents.push(pile),
+				None => loot.push(Loot::new(ctx, x, y, pile)),
+			}
+		}
 		
 		let npc = MacguffinMan::new(ctx, 250.0, 350.0);
+		let merchants = Merchant::parse_all(&level.extras).into_iter()
+			.map(|(x, y)| Merchant::new(ctx, x, y))
+			.collect();
+		let prices = Prices::load(ctx, "/prices.txt");
 		
         // create state
         MainState {
             level,
             enemies: e,
             player,
-            ui: UI::new(ctx, "Adventurer".to_string(), hp, max_hp, mp, max_mp, lv),
-			macguffin: Some(mac),
-			potions: pot,
+            ui: UI::new(ctx, class.name().to_string(), hp, max_hp, mp, max_mp, lv),
+			loot,
+			ambush: hiding.ambush,
+			seed,
+			traps,
+			chests,
+			breakables,
 			macguffin_man: npc,
+			dialogue: Rc::new(Dialogue::load(ctx, "/macguffin_man.txt")),
+			story: Rc::new(RefCell::new(Story::new())),
+			merchants,
+			prices,
+			elapsed: Duration::new(0, 0),
+			fade: Duration::new(0, 0),
         }
     }
 
@@ -1,8 +1,15 @@
 // Namespace states
 // Contains modules related to drawable states
 
-use ggez::event::{EventHandler, KeyCode, KeyMods};
+use crate::entities::items::inventory::{self, Inventory};
+use crate::entities::items::shop::{Prices, Refusal};
+use crate::entities::npcs::dialogue::{Dialogue, Listener, Page, Story};
+use crate::entities::player::equipment::{EquipSlot, Equipment, StatBlock};
+use crate::input::{self, Action};
+use ggez::event::{Axis, Button, EventHandler, GamepadId, KeyCode, KeyMods};
 use ggez::*;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 include!("./state_machine.rs");
 
@@ -15,3 +22,13 @@ include!("./main_menu_state.rs");
 include!("./game_over_state.rs");
 
 include!("./victory_state.rs");
+
+include!("./controls_state.rs");
+
+include!("./inventory_state.rs");
+
+include!("./character_state.rs");
+
+include!("./shop_state.rs");
+
+include!("./dialogue_state.rs");
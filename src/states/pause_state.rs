@@ -8,7 +8,7 @@ pub struct PauseState {
 /// Allows the state machine to pass on information.
 impl CustomEventHandler for PauseState {
     /// Updates PauseState.
-    /// Does not really do anything... Real magic happens in key_down_event.
+    /// Does not really do anything... Real magic happens in action_down_event.
     fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
         HandlerMessage::Keep
     }
@@ -29,17 +29,21 @@ impl CustomEventHandler for PauseState {
         timer::yield_now();
         Ok(())
     }
-    /// Processes a key down event.
-    /// This is where transitioning to main menu or game state occurs.
-    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, _repeat: bool) -> HandlerMessage {
-        match key {
-            KeyCode::P => {
+    /// Processes an action (from a key or gamepad button).
+    /// This is where transitioning to main menu, game state or the controls screen occurs.
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+            Action::Pause => {
                 HandlerMessage::Bail
             },
-             KeyCode::Q => {
+            Action::Quit => {
                 let state = Box::new(MainMenuState::new(ctx));
                 HandlerMessage::Change(state)
             },
+            Action::Confirm => {
+                let state = Box::new(ControlsState::new(ctx));
+                HandlerMessage::Spawn(state)
+            },
             _ => HandlerMessage::Keep
         }
     }
@@ -49,7 +53,13 @@ impl PauseState {
     /// Creates a new PauseState with default values
     pub fn new(ctx: &mut Context) -> PauseState {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
-        let t = graphics::Text::new(("Paused\nPress P to Unpause\nPress Q to Quit".to_string(), font, 22.0));
+        let text = format!(
+            "Paused\nPress {} to Unpause\nPress {} to Quit\nPress {} for Controls",
+            input::describe(Action::Pause),
+            input::describe(Action::Quit),
+            input::describe(Action::Confirm),
+        );
+        let t = graphics::Text::new((text, font, 22.0));
         PauseState {
             text: t,
         }
@@ -66,6 +76,7 @@ mod testpause {
             .add_resource_path(std::path::PathBuf::from("./resources/texture"))
             .add_resource_path(std::path::PathBuf::from("./resources/font"))
             .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
             .build()
             .unwrap();
         let mm = PauseState::new(&mut ctx);
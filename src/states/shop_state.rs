@@ -0,0 +1,183 @@
+/// The two sides of the shop screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ShopTab {
+    Buy,
+    Sell,
+}
+
+/// Shop screen for game.
+/// Lists what the merchant sells (or what the player could sell them) and trades for gold.
+pub struct ShopState {
+    text: graphics::Text,
+    // shared with the player, gold and items change straight away
+    inventory: Rc<RefCell<Inventory>>,
+    prices: Prices,
+    tab: ShopTab,
+    // row the cursor is on (item in stock when buying, inventory slot when selling)
+    selected: usize,
+    // what happened with the last trade
+    message: String,
+}
+
+/// Implement CustomEventHandler from macguffin_quest::states::CustomEventHandler.
+/// Allows the state machine to pass on information.
+impl CustomEventHandler for ShopState {
+    /// Updates ShopState.
+    /// Does not really do anything... Real magic happens in action_down_event.
+    fn update(&mut self, _ctx: &mut Context) -> HandlerMessage {
+        HandlerMessage::Keep
+    }
+
+    /// Draws ShopState.
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        graphics::clear(ctx, graphics::BLACK);
+        let point = nalgebra::Point2::new(100.0, 50.0);
+
+        graphics::draw(
+            ctx,
+            &self.text,
+            graphics::DrawParam::default().dest(point),
+        )
+        .expect("ERROR drawing Shop Text");
+
+        graphics::present(ctx)?;
+        timer::yield_now();
+        Ok(())
+    }
+
+    /// Processes an action (from a key or gamepad button).
+    /// Moves around the list, switches between buying and selling, trades, or leaves the shop.
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+            Action::Up if self.selected > 0 => self.selected -= 1,
+            Action::Down if self.selected + 1 < self.rows() => self.selected += 1,
+            Action::Left | Action::Right => {
+                self.tab = if self.tab == ShopTab::Buy { ShopTab::Sell } else { ShopTab::Buy };
+                self.selected = 0;
+                self.message.clear();
+            },
+            Action::Confirm => self.trade(),
+            Action::Pause => return HandlerMessage::Bail,
+            _ => return HandlerMessage::Keep,
+        }
+        self.refresh(ctx);
+        HandlerMessage::Keep
+    }
+}
+
+impl ShopState {
+    /// Creates a new ShopState on the buying side
+    pub fn new(ctx: &mut Context, inventory: Rc<RefCell<Inventory>>, prices: Prices) -> ShopState {
+        let mut state = ShopState {
+            text: graphics::Text::default(),
+            inventory,
+            prices,
+            tab: ShopTab::Buy,
+            selected: 0,
+            message: String::new(),
+        };
+        state.refresh(ctx);
+        state
+    }
+
+    /// How many rows the current side of the shop has.
+    fn rows(&self) -> usize {
+        match self.tab {
+            ShopTab::Buy => self.prices.stock().len(),
+            ShopTab::Sell => inventory::SLOTS,
+        }
+    }
+
+    /// Buys or sells whatever the cursor is on.
+    fn trade(&mut self) {
+        let mut inv = self.inventory.borrow_mut();
+        let result = match self.tab {
+            ShopTab::Buy => match self.prices.stock().get(self.selected) {
+                Some((item, _)) => self.prices.buy(&mut inv, *item).map(|price| format!("Bought {} for {} gold", item.name(), price)),
+                None => Err(Refusal::NotForSale),
+            },
+            ShopTab::Sell => {
+                let item = inv.slot(self.selected).map(|s| s.item);
+                self.prices.sell(&mut inv, self.selected).map(|price| format!("Sold {} for {} gold", item.unwrap().name(), price))
+            },
+        };
+        self.message = match result {
+            Ok(text) => text,
+            Err(refusal) => refusal.text().to_string(),
+        };
+    }
+
+    /// Rebuilds the text listing the shop.
+    fn refresh(&mut self, ctx: &mut Context) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let inv = self.inventory.borrow();
+        let (buy, sell) = if self.tab == ShopTab::Buy { ("[Buy]", " Sell ") } else { (" Buy ", "[Sell]") };
+        let mut text = format!("Shop    {} {}    Gold: {}\n\n", buy, sell, inv.gold());
+        match self.tab {
+            ShopTab::Buy => {
+                let stock = self.prices.stock();
+                if stock.is_empty() {
+                    text.push_str("  Nothing for sale\n");
+                }
+                for (i, (item, price)) in stock.iter().enumerate() {
+                    let cursor = if i == self.selected { "> " } else { "  " };
+                    text.push_str(&format!("{}{} - {} gold\n", cursor, item.name(), price));
+                }
+            },
+            ShopTab::Sell => {
+                for i in 0..inventory::SLOTS {
+                    let cursor = if i == self.selected { "> " } else { "  " };
+                    let row = match inv.slot(i) {
+                        Some(slot) => {
+                            let count = if slot.count > 1 { format!(" x{}", slot.count) } else { String::new() };
+                            let price = match self.prices.sell_price(slot.item) {
+                                Some(price) => format!("{} gold", price),
+                                None => "won't buy".to_string(),
+                            };
+                            format!("{}{} - {}", slot.item.name(), count, price)
+                        },
+                        None => "-".to_string(),
+                    };
+                    text.push_str(&format!("{}{}\n", cursor, row));
+                }
+            },
+        }
+        text.push_str(&format!("\n{}\n", self.message));
+        text.push_str(&format!(
+            "\n{} to trade, {} / {} to switch between buying and selling, {} to go back",
+            input::describe_key(Action::Confirm),
+            input::describe_key(Action::Left),
+            input::describe_key(Action::Right),
+            input::describe_key(Action::Pause),
+        ));
+        self.text = graphics::Text::new((text, font, 18.0));
+    }
+}
+
+#[cfg(test)]
+mod testshopstate {
+    use super::*;
+
+    fn create_shop_state_and_context() -> (ShopState, Context) {
+        let (mut ctx, _event_loop) =
+            ggez::ContextBuilder::new("macguffin_quest", "James M. & William O.")
+            .add_resource_path(std::path::PathBuf::from("./resources/texture"))
+            .add_resource_path(std::path::PathBuf::from("./resources/font"))
+            .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
+            .build()
+            .unwrap();
+        let prices = Prices::load(&mut ctx, "/prices.txt");
+        let ss = ShopState::new(&mut ctx, Rc::new(RefCell::new(Inventory::new())), prices);
+        (ss, ctx)
+    }
+
+    #[test]
+    fn test_update() {
+        let (ref mut ss, ref mut ctx) = create_shop_state_and_context();
+        match ss.update(ctx) {
+            HandlerMessage::Keep => (),
+            _ => panic!("HandlerMessage was not Keep"),
+        }
+    }
+}
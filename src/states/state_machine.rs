@@ -11,7 +11,19 @@ pub trait CustomEventHandler {
 
     /// Used to pass parameters from ggez::event::EventHandler draw to state
     fn key_down_event(&mut self, _ctx: &mut Context, _keycode: KeyCode, _keymods: KeyMods, _repeat: bool) -> HandlerMessage { HandlerMessage::Keep }
-    
+
+    /// Used to pass on gamepad button presses to state
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, _btn: Button) -> HandlerMessage { HandlerMessage::Keep }
+
+    /// Used to pass on actions (from keys or gamepad buttons bound in input::Controls) to state
+    fn action_down_event(&mut self, _ctx: &mut Context, _action: Action) -> HandlerMessage { HandlerMessage::Keep }
+
+    /// States that draw over the state underneath them (ie dialogue boxes) return true
+    fn overlay(&self) -> bool { false }
+
+    /// Used to draw the state without presenting it, so an overlay can be drawn on top
+    fn draw_scene(&mut self, _ctx: &mut Context) -> GameResult { Ok(()) }
+
     // add more EventHandler method wrappers as needed
 }
 
@@ -70,6 +82,23 @@ impl StateMachine {
         self.states.pop()
     }
 
+    /// Passes actions to the current state one at a time.
+    /// Stops once the state asks for a transition (the new state didn't see the key get pressed).
+    fn action_down(&mut self, ctx: &mut Context, actions: Vec<Action>) {
+        for action in actions {
+            if self.is_empty(ctx) {
+                return
+            }
+            match self.states.last_mut().unwrap().action_down_event(ctx, action) {
+                HandlerMessage::Keep => (),
+                message => {
+                    let _ = message.handle(self);
+                    return
+                },
+            }
+        }
+    }
+
     /// Does the state machine have any states.
     /// Quits the game if true.
     fn is_empty(&self, ctx: &mut Context) -> bool {
@@ -104,7 +133,11 @@ impl EventHandler for StateMachine {
             return Ok(())
         }
 
-        
+        // overlays go on top of whatever the state underneath them looks like
+        let top = self.states.len() - 1;
+        if top > 0 && self.states[top].overlay() {
+            self.states[top - 1].draw_scene(ctx)?;
+        }
         self.states.last_mut().unwrap().draw(ctx)
     }
 
@@ -119,9 +152,46 @@ impl EventHandler for StateMachine {
             return
         }
 
-        match self.states.last_mut().unwrap().key_down_event(ctx, keycode, keymods, repeat).handle(self) {
-            _ => (),
+        match self.states.last_mut().unwrap().key_down_event(ctx, keycode, keymods, repeat) {
+            HandlerMessage::Keep => (),
+            message => {
+                let _ = message.handle(self);
+                return
+            },
+        }
+
+        // held keys repeat, actions only happen once per press
+        if !repeat {
+            self.action_down(ctx, input::actions_for_key(keycode));
+        }
+    }
+
+    /// Passes gamepad button presses to current state, then any actions bound to the button.
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button, _id: GamepadId) {
+        input::button_event(btn, true);
+        if self.is_empty(ctx) {
+            return
+        }
+
+        match self.states.last_mut().unwrap().gamepad_button_down_event(ctx, btn) {
+            HandlerMessage::Keep => (),
+            message => {
+                let _ = message.handle(self);
+                return
+            },
         }
+
+        self.action_down(ctx, input::actions_for_button(btn));
+    }
+
+    /// Keeps track of which gamepad buttons are held (for input::is_pressed).
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, btn: Button, _id: GamepadId) {
+        input::button_event(btn, false);
+    }
+
+    /// Keeps track of the thumb sticks (for input::is_pressed).
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        input::axis_event(axis, value);
     }
 }
 
@@ -135,6 +205,7 @@ mod tests {
             .add_resource_path(std::path::PathBuf::from("./resources/texture"))
             .add_resource_path(std::path::PathBuf::from("./resources/font"))
             .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
             .build()
             .unwrap();
         let mm = MainMenuState::new(&mut ctx);
@@ -29,11 +29,11 @@ impl CustomEventHandler for VictoryState {
         timer::yield_now();
         Ok(())
     }
-    /// Processes a key down event.
+    /// Processes an action (from a key or gamepad button).
     /// This is where transitioning to main menu state occurs.
-    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, _repeat: bool) -> HandlerMessage {
-        match key {
-             KeyCode::Q => {
+    fn action_down_event(&mut self, ctx: &mut Context, action: Action) -> HandlerMessage {
+        match action {
+             Action::Quit => {
                 let state = Box::new(MainMenuState::new(ctx));
                 HandlerMessage::Change(state)
             },
@@ -46,7 +46,7 @@ impl VictoryState {
     /// Creates a new VictoryState with default values
     pub fn new(ctx: &mut Context) -> VictoryState {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
-        let t = graphics::Text::new(("You Found the MacGuffin!!!\n\nyay..\n\nPress Q to Quit".to_string(), font, 22.0));
+        let t = graphics::Text::new((format!("You Found the MacGuffin!!!\n\nyay..\n\nPress {} to Quit", input::describe(Action::Quit)), font, 22.0));
         VictoryState {
             text: t,
         }
@@ -63,6 +63,7 @@ mod testvictory {
             .add_resource_path(std::path::PathBuf::from("./resources/texture"))
             .add_resource_path(std::path::PathBuf::from("./resources/font"))
             .add_resource_path(std::path::PathBuf::from("./resources/level"))
+            .add_resource_path(std::path::PathBuf::from("./resources/data"))
             .build()
             .unwrap();
         let mm = VictoryState::new(&mut ctx);
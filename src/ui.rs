@@ -1,3 +1,7 @@
+use crate::entities::combat::Damage;
+use crate::entities::items::inventory::{Inventory, HOTBAR};
+use crate::entities::player::spells::Spellbook;
+use crate::entities::status::StatusKind;
 use ggez::*;
 use std::time::Duration;
 
@@ -7,8 +11,31 @@ pub struct UI {
     pub player_health: graphics::Text,
     pub player_mp: graphics::Text,
     pub player_level: graphics::Text,
+    pub boss_name: graphics::Text,
+    pub boss_health: Option<(f32, f32)>,
+    pub status: Vec<(StatusKind, u32)>,
+    pub exp: (u32, u32),
+    pub stamina: (f32, f32),
+    pub spell: graphics::Text,
+    pub hotbar: graphics::Text,
+    pub gold: graphics::Text,
 }
 
+/// Size of the boss health bar drawn along the top of the screen
+const BOSS_BAR_WIDTH: f32 = 400.0;
+const BOSS_BAR_HEIGHT: f32 = 16.0;
+
+/// Size of the experience bar drawn under the player's level
+const EXP_BAR_WIDTH: f32 = 150.0;
+const EXP_BAR_HEIGHT: f32 = 6.0;
+
+/// Size of the stamina bar drawn under the experience bar
+const STAMINA_BAR_WIDTH: f32 = 150.0;
+const STAMINA_BAR_HEIGHT: f32 = 5.0;
+
+/// Size of the status effect icons drawn under the player's level
+const STATUS_ICON_SIZE: f32 = 24.0;
+
 impl UI {
     pub fn new(
         ctx: &mut Context,
@@ -22,22 +49,31 @@ impl UI {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
         let p_name = graphics::Text::new((name, font, 22.0));
         let p_health = graphics::Text::new((
-            "hp: ".to_string() + &health.to_string() + &"/".to_string() + &max_health.to_string(),
+            "hp: ".to_string() + &health.to_string() + "/" + &max_health.to_string(),
             font,
             22.0,
         ));
         let p_mp = graphics::Text::new((
-            "mp: ".to_string() + &mp.to_string() + &"/".to_string() + &max_mp.to_string(),
+            "mp: ".to_string() + &mp.to_string() + "/" + &max_mp.to_string(),
             font,
             22.0,
         ));
         let lev = graphics::Text::new(("Level: ".to_string() + &level.to_string(), font, 22.0));
+        let boss = graphics::Text::new(("The Grue".to_string(), font, 22.0));
 
         UI {
             player_name: p_name,
             player_health: p_health,
             player_mp: p_mp,
             player_level: lev,
+            boss_name: boss,
+            boss_health: None,
+            status: Vec::new(),
+            exp: (0, 1),
+            stamina: (1.0, 1.0),
+            spell: graphics::Text::default(),
+            hotbar: graphics::Text::default(),
+            gold: graphics::Text::default(),
         }
     }
 
@@ -52,12 +88,12 @@ impl UI {
     ) {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
         self.player_health = graphics::Text::new((
-            "hp: ".to_string() + &health.to_string() + &"/".to_string() + &max_health.to_string(),
+            "hp: ".to_string() + &health.to_string() + "/" + &max_health.to_string(),
             font,
             22.0,
         ));
         self.player_mp = graphics::Text::new((
-            "mp: ".to_string() + &mp.to_string() + &"/".to_string() + &max_mp.to_string(),
+            "mp: ".to_string() + &mp.to_string() + "/" + &max_mp.to_string(),
             font,
             22.0,
         ));
@@ -65,6 +101,172 @@ impl UI {
             graphics::Text::new(("Level: ".to_string() + &level.to_string(), font, 22.0));
     }
 
+    // selected spell, what it costs and how long until it can be cast again
+    pub fn update_spell(&mut self, ctx: &mut Context, spellbook: &Spellbook) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let spell = spellbook.selected_spell();
+        let cooldown = spellbook.cooldown_left(spell).as_secs_f32();
+        let ready = if cooldown > 0.0 {
+            format!("{:.1}s", cooldown)
+        } else {
+            "ready".to_string()
+        };
+        self.spell = graphics::Text::new((
+            format!("{} ({} mp) {}", spell.name(), spell.cost(), ready),
+            font,
+            18.0,
+        ));
+    }
+
+    // what is in each hotbar slot (and how many)
+    pub fn update_hotbar(&mut self, ctx: &mut Context, inventory: &Inventory) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let slots: Vec<String> = (0..HOTBAR)
+            .map(|i| match inventory.slot(i) {
+                Some(slot) if slot.count > 1 => {
+                    format!("{}: {} x{}", i + 1, slot.item.name(), slot.count)
+                }
+                Some(slot) => format!("{}: {}", i + 1, slot.item.name()),
+                None => format!("{}: -", i + 1),
+            })
+            .collect();
+        self.hotbar = graphics::Text::new((slots.join("  "), font, 18.0));
+    }
+
+    // how much gold the player is carrying
+    pub fn update_gold(&mut self, ctx: &mut Context, gold: u32) {
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        self.gold = graphics::Text::new((format!("Gold: {}", gold), font, 18.0));
+    }
+
+    // boss health is None when there is no boss around
+    pub fn update_boss(&mut self, health: Option<(f32, f32)>) {
+        self.boss_health = health;
+    }
+
+    // experience towards the next level (and how much the next level needs)
+    pub fn update_exp(&mut self, exp: u32, next: u32) {
+        self.exp = (exp, next.max(1));
+    }
+
+    // draws a thin bar showing how close the player is to levelling up
+    fn draw_exp_bar(&self, ctx: &mut Context) {
+        let (x, y) = (100.0, 96.0);
+        let fill = (self.exp.0 as f32 / self.exp.1 as f32).clamp(0.0, 1.0) * EXP_BAR_WIDTH;
+
+        let mut mb = graphics::MeshBuilder::new();
+        mb.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x, y, EXP_BAR_WIDTH, EXP_BAR_HEIGHT),
+            graphics::Color::from_rgb(40u8, 40u8, 40u8),
+        );
+        if fill > 0.0 {
+            mb.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x, y, fill, EXP_BAR_HEIGHT),
+                graphics::Color::from_rgb(240u8, 200u8, 40u8),
+            );
+        }
+        let bar = mb.build(ctx).expect("Error Building Exp Bar");
+        graphics::draw(ctx, &bar, graphics::DrawParam::default()).expect("Error Drawing Exp Bar");
+    }
+
+    // stamina left for dodging (and the most there can be)
+    pub fn update_stamina(&mut self, stamina: f32, max_stamina: f32) {
+        self.stamina = (stamina, max_stamina.max(1.0));
+    }
+
+    // draws a thin bar showing how much stamina is left
+    fn draw_stamina_bar(&self, ctx: &mut Context) {
+        let (x, y) = (100.0, 103.0);
+        let fill = (self.stamina.0 / self.stamina.1).clamp(0.0, 1.0) * STAMINA_BAR_WIDTH;
+
+        let mut mb = graphics::MeshBuilder::new();
+        mb.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x, y, STAMINA_BAR_WIDTH, STAMINA_BAR_HEIGHT),
+            graphics::Color::from_rgb(40u8, 40u8, 40u8),
+        );
+        if fill > 0.0 {
+            mb.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x, y, fill, STAMINA_BAR_HEIGHT),
+                graphics::Color::from_rgb(60u8, 200u8, 80u8),
+            );
+        }
+        let bar = mb.build(ctx).expect("Error Building Stamina Bar");
+        graphics::draw(ctx, &bar, graphics::DrawParam::default())
+            .expect("Error Drawing Stamina Bar");
+    }
+
+    // status effects on the player (and how many stacks of each)
+    pub fn update_status(&mut self, status: Vec<(StatusKind, u32)>) {
+        self.status = status;
+    }
+
+    // draws a coloured square for each status effect with its label (and stacks if more than one)
+    fn draw_status(&self, ctx: &mut Context) {
+        if self.status.is_empty() {
+            return;
+        }
+        let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
+        let y = 110.0;
+
+        let mut mb = graphics::MeshBuilder::new();
+        for (i, (kind, stacks)) in self.status.iter().enumerate() {
+            let x = 100.0 + i as f32 * (STATUS_ICON_SIZE + 4.0);
+            mb.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x, y, STATUS_ICON_SIZE, STATUS_ICON_SIZE),
+                kind.tint(),
+            );
+
+            let label = if *stacks > 1 {
+                format!("{}{}", kind.label(), stacks)
+            } else {
+                kind.label().to_string()
+            };
+            let text = graphics::Text::new((label, font, 16.0));
+            let dest = nalgebra::Point2::new(x + 4.0, y + 4.0);
+            graphics::queue_text(ctx, &text, dest, Some(graphics::BLACK));
+        }
+        let icons = mb.build(ctx).expect("Error Building Status Icons");
+        graphics::draw(ctx, &icons, graphics::DrawParam::default())
+            .expect("Error Drawing Status Icons");
+    }
+
+    // draws boss name and health bar centered along the top of the screen
+    fn draw_boss_bar(&self, ctx: &mut Context, health: f32, max_health: f32) {
+        let (width, _) = graphics::drawable_size(ctx);
+        let x = (width - BOSS_BAR_WIDTH) / 2.0;
+        let y = 40.0;
+        let fill = (health / max_health).clamp(0.0, 1.0) * BOSS_BAR_WIDTH;
+
+        let mut mb = graphics::MeshBuilder::new();
+        mb.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x, y, BOSS_BAR_WIDTH, BOSS_BAR_HEIGHT),
+            graphics::Color::from_rgb(64u8, 0u8, 0u8),
+        );
+        if fill > 0.0 {
+            mb.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x, y, fill, BOSS_BAR_HEIGHT),
+                graphics::Color::from_rgb(200u8, 0u8, 0u8),
+            );
+        }
+        mb.rectangle(
+            graphics::DrawMode::stroke(2.0),
+            graphics::Rect::new(x, y, BOSS_BAR_WIDTH, BOSS_BAR_HEIGHT),
+            graphics::WHITE,
+        );
+        let bar = mb.build(ctx).expect("Error Building Boss Bar");
+        graphics::draw(ctx, &bar, graphics::DrawParam::default()).expect("Error Drawing Boss Bar");
+
+        let boss_name_dest = nalgebra::Point2::new(x, y - 24.0);
+        graphics::queue_text(ctx, &self.boss_name, boss_name_dest, Some(graphics::WHITE));
+    }
+
     pub fn draw(&mut self, ctx: &mut Context) {
         // make sure text queue is cleared
         graphics::draw_queued_text(
@@ -106,6 +308,33 @@ impl UI {
             Some(graphics::WHITE),
         );
 
+        // queue selected spell for drawing (under the status icons)
+        let spell_dest = nalgebra::Point2::new(100.0, 140.0);
+        graphics::queue_text(ctx, &self.spell, spell_dest, Some(graphics::WHITE));
+
+        // queue hotbar for drawing (under the selected spell)
+        let hotbar_dest = nalgebra::Point2::new(100.0, 162.0);
+        graphics::queue_text(ctx, &self.hotbar, hotbar_dest, Some(graphics::WHITE));
+
+        // queue gold for drawing (under the hotbar)
+        let gold_dest = nalgebra::Point2::new(100.0, 184.0);
+        let gold_color = graphics::Color::from_rgb(240u8, 200u8, 40u8);
+        graphics::queue_text(ctx, &self.gold, gold_dest, Some(gold_color));
+
+        // draw experience bar
+        self.draw_exp_bar(ctx);
+
+        // draw stamina bar
+        self.draw_stamina_bar(ctx);
+
+        // draw status effect icons (labels get queued with the rest of the text)
+        self.draw_status(ctx);
+
+        // draw boss health bar (name gets queued with the rest of the text)
+        if let Some((health, max_health)) = self.boss_health {
+            self.draw_boss_bar(ctx, health, max_health);
+        }
+
         // draw ui
         graphics::draw_queued_text(
             ctx,
@@ -117,7 +346,7 @@ impl UI {
     }
 }
 
-/// Floating text (primarily for damage)
+// Floating text (primarily for damage)
 
 const FLOAT_SPEED: f64 = 25f64; // move 25 units every second
 const LIFETIME: Duration = Duration::from_millis(1000); // text lives one sec
@@ -131,8 +360,25 @@ pub struct FloatingText {
 
 impl FloatingText {
     pub fn new(ctx: &mut Context, xpos: f32, ypos: f32, text: String, color: &str) -> FloatingText {
+        FloatingText::sized(ctx, xpos, ypos, text, color, 22.0)
+    }
+
+    // Damage text, crits are bigger
+    pub fn damage(ctx: &mut Context, xpos: f32, ypos: f32, damage: &Damage) -> FloatingText {
+        let size = if damage.crit { 30.0 } else { 22.0 };
+        FloatingText::sized(ctx, xpos, ypos, damage.text(), damage.text_color(), size)
+    }
+
+    fn sized(
+        ctx: &mut Context,
+        xpos: f32,
+        ypos: f32,
+        text: String,
+        color: &str,
+        size: f32,
+    ) -> FloatingText {
         let font = graphics::Font::new(ctx, "/square.ttf").unwrap();
-        let t = graphics::Text::new((text, font, 22.0));
+        let t = graphics::Text::new((text, font, size));
 
         let c = match color {
             "White" => graphics::WHITE,
@@ -140,6 +386,9 @@ impl FloatingText {
             "Red" => graphics::Color::from_rgb(255u8, 0u8, 0u8),
             "Green" => graphics::Color::from_rgb(0u8, 128u8, 0u8),
             "Blue" => graphics::Color::from_rgb(0u8, 0u8, 255u8),
+            "Yellow" => graphics::Color::from_rgb(255u8, 255u8, 0u8),
+            "Orange" => graphics::Color::from_rgb(255u8, 128u8, 0u8),
+            "Cyan" => graphics::Color::from_rgb(0u8, 200u8, 255u8),
             _ => graphics::WHITE,
         };
 